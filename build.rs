@@ -118,8 +118,156 @@ fn main() -> Result<(), BuildError> {
             entry: "main",
             name_overwrite: None,
         },
+        SimpleSource {
+            path: "src/shaders/blend.frag",
+            kind: ShaderKind::Fragment,
+            entry: "main",
+            name_overwrite: None,
+        },
+        SimpleSource {
+            path: "src/shaders/blend_mode.frag",
+            kind: ShaderKind::Fragment,
+            entry: "main",
+            name_overwrite: None,
+        },
+        SimpleSource {
+            path: "src/shaders/separable.frag",
+            kind: ShaderKind::Fragment,
+            entry: "main",
+            name_overwrite: None,
+        },
+        SimpleSource {
+            path: "src/shaders/convolve.frag",
+            kind: ShaderKind::Fragment,
+            entry: "main",
+            name_overwrite: None,
+        },
+        SimpleSource {
+            path: "src/shaders/median.frag",
+            kind: ShaderKind::Fragment,
+            entry: "main",
+            name_overwrite: None,
+        },
+        SimpleSource {
+            path: "src/shaders/bilateral.frag",
+            kind: ShaderKind::Fragment,
+            entry: "main",
+            name_overwrite: None,
+        },
+        SimpleSource {
+            path: "src/shaders/kuwahara.frag",
+            kind: ShaderKind::Fragment,
+            entry: "main",
+            name_overwrite: None,
+        },
+        SimpleSource {
+            path: "src/shaders/resample.frag",
+            kind: ShaderKind::Fragment,
+            entry: "main",
+            name_overwrite: None,
+        },
+        SimpleSource {
+            path: "src/shaders/warp.frag",
+            kind: ShaderKind::Fragment,
+            entry: "main",
+            name_overwrite: None,
+        },
+        SimpleSource {
+            path: "src/shaders/orient.frag",
+            kind: ShaderKind::Fragment,
+            entry: "main",
+            name_overwrite: None,
+        },
+        SimpleSource {
+            path: "src/shaders/extend.frag",
+            kind: ShaderKind::Fragment,
+            entry: "main",
+            name_overwrite: None,
+        },
+        SimpleSource {
+            path: "src/shaders/gradient.frag",
+            kind: ShaderKind::Fragment,
+            entry: "main",
+            name_overwrite: None,
+        },
+        SimpleSource {
+            path: "src/shaders/canny.frag",
+            kind: ShaderKind::Fragment,
+            entry: "main",
+            name_overwrite: None,
+        },
+        SimpleSource {
+            path: "src/shaders/hough.frag",
+            kind: ShaderKind::Fragment,
+            entry: "main",
+            name_overwrite: None,
+        },
+        SimpleSource {
+            path: "src/shaders/ssim.frag",
+            kind: ShaderKind::Fragment,
+            entry: "main",
+            name_overwrite: None,
+        },
+        SimpleSource {
+            path: "src/shaders/ms_ssim.frag",
+            kind: ShaderKind::Fragment,
+            entry: "main",
+            name_overwrite: None,
+        },
+        SimpleSource {
+            path: "src/shaders/hash.frag",
+            kind: ShaderKind::Fragment,
+            entry: "main",
+            name_overwrite: None,
+        },
+        SimpleSource {
+            path: "src/shaders/histogram.frag",
+            kind: ShaderKind::Fragment,
+            entry: "main",
+            name_overwrite: None,
+        },
+        SimpleSource {
+            path: "src/shaders/equalize.frag",
+            kind: ShaderKind::Fragment,
+            entry: "equalize_count",
+            name_overwrite: Some("equalize_count"),
+        },
+        SimpleSource {
+            path: "src/shaders/equalize.frag",
+            kind: ShaderKind::Fragment,
+            entry: "equalize_apply",
+            name_overwrite: Some("equalize_apply"),
+        },
+        SimpleSource {
+            path: "src/shaders/statistics.frag",
+            kind: ShaderKind::Fragment,
+            entry: "main",
+            name_overwrite: None,
+        },
+        SimpleSource {
+            path: "src/shaders/noise.frag",
+            kind: ShaderKind::Fragment,
+            entry: "main",
+            name_overwrite: None,
+        },
+        SimpleSource {
+            path: "src/shaders/white_noise.frag",
+            kind: ShaderKind::Fragment,
+            entry: "main",
+            name_overwrite: None,
+        },
+        SimpleSource {
+            path: "src/shaders/blue_noise.frag",
+            kind: ShaderKind::Fragment,
+            entry: "main",
+            name_overwrite: None,
+        },
     ];
 
+    /// Sources shared between shaders, through `#include "name"` with a name relative to
+    /// `src/shaders`. They are not compiled on their own.
+    const INCLUDES: &[&str] = &["src/shaders/border.glsl", "src/shaders/words.glsl"];
+
     let mut compiler = Compiler::new().unwrap();
     let mut str_source = String::new();
 
@@ -128,6 +276,10 @@ fn main() -> Result<(), BuildError> {
 
     fs::create_dir_all(&target_dir)?;
 
+    for include in INCLUDES {
+        println!("cargo:rerun-if-changed={}", include);
+    }
+
     for shader in SHADERS {
         println!("cargo:rerun-if-changed={}", shader.path);
         let mut file = fs::File::open(shader.path)?;
@@ -140,6 +292,19 @@ fn main() -> Result<(), BuildError> {
             options.add_macro_definition(&macro_name, Some("main"));
         }
 
+        options.set_include_callback(|name, _, _, _| {
+            let path = format!("src/shaders/{}", name);
+            if !INCLUDES.contains(&path.as_str()) {
+                return Err(format!("{} is not listed as an include", path));
+            }
+
+            let content = fs::read_to_string(&path).map_err(|err| err.to_string())?;
+            Ok(shaderc::ResolvedInclude {
+                resolved_name: path,
+                content,
+            })
+        });
+
         let binary = compiler.compile_into_spirv(
             &str_source,
             shader.kind,
@@ -418,7 +418,7 @@ impl Descriptor {
         },
     };
 
-    fn with_texel(texel: Texel, width: u32, height: u32) -> Option<Self> {
+    pub(crate) fn with_texel(texel: Texel, width: u32, height: u32) -> Option<Self> {
         let layout = BufferLayout::with_texel(&texel, width, height)?;
         Some(Descriptor { layout, texel })
     }
@@ -659,6 +659,24 @@ impl Color {
         whitepoint: Whitepoint::D65,
     };
 
+    /// The weights of the linear representation giving its luminance, lightness or value.
+    ///
+    /// For Rgb this is the luminance Y of CIE XYZ, for Oklab the lightness L. Scalars do not
+    /// define any of these and we use the first component as their value.
+    pub(crate) fn luma_weights(&self) -> [f32; 3] {
+        match self {
+            Color::Rgb {
+                primary,
+                whitepoint,
+                ..
+            } => {
+                let rows = primary.to_xyz(*whitepoint).into_inner();
+                [rows[3], rows[4], rows[5]]
+            }
+            Color::Oklab | Color::Scalars { .. } => [1.0, 0.0, 0.0],
+        }
+    }
+
     /// Check if this color space contains the sample parts.
     ///
     /// For example, an Xyz color is expressed in terms of a subset of Rgb while HSV color spaces
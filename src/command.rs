@@ -11,6 +11,7 @@ pub use crate::shaders::distribution_normal2d::Shader as DistributionNormal2d;
 use crate::shaders::{self, FragmentShader, PaintOnTopKind};
 
 use std::collections::HashMap;
+use std::convert::TryFrom;
 
 /// A reference to one particular value.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
@@ -36,6 +37,8 @@ pub struct Register(pub(crate) usize);
 #[derive(Default)]
 pub struct CommandBuffer {
     ops: Vec<Op>,
+    /// The smallest scale times the footprint of the sampling, if sparse sampling is refused.
+    sparse_sampling_limit: Option<f32>,
 }
 
 #[derive(Clone)]
@@ -72,8 +75,19 @@ pub(crate) enum ConstructOp {
     Bilinear(Bilinear),
     /// A 2d normal distribution.
     DistributionNormal(shaders::DistributionNormal2d),
-    // TODO: can optimize this repr for the common case.
-    Solid(Vec<u8>),
+    /// A solid color, already decoded to its linear representation.
+    Solid([f32; 4]),
+    /// Coherent noise, summed over octaves.
+    Noise(Noise),
+    /// Independent random values for each pixel.
+    WhiteNoise {
+        distribution: WhiteNoise,
+        seed: u32,
+    },
+    /// A blue noise mask, ranked from an initial pattern chosen by the seed.
+    BlueNoise {
+        seed: u32,
+    },
 }
 
 /// A high-level, device independent, translation of ops.
@@ -134,12 +148,43 @@ pub(crate) enum UnaryOp {
     Transmute,
     /// Op(T) = T
     Derivative(Derivative),
+    /// Op(T) = T
+    Smooth(Smoothing),
+    /// Op(T) = T
+    Convolve(Kernel),
+    /// Op(T) = T[.layout=resized]
+    Resize(ResizeFilter),
+    /// Op(T) = T[.layout=oriented]
+    Orient(shaders::orient::Shader),
+    /// Op(T) = T[.layout=extended]
+    Extend(shaders::extend::Shader),
+    /// Op(T) = T[.texel=two scalars]
+    Gradient(shaders::gradient::Shader),
+    /// Op(T) = T[.texel=scalar]
+    /// where T is a polar gradient.
+    Canny { low: f32, high: f32 },
+    /// Op(T) = U
+    /// where U is a scalar accumulator.
+    Hough(shaders::hough::Shader),
+    /// Op(T) = U
+    /// where U is an 8×8 binary scalar image.
+    PerceptualHash(shaders::hash::Shader),
+    /// Op(T) = U
+    /// where U is a row of counts.
+    Histogram(shaders::histogram::Shader),
+    /// Op(T) = T
+    Equalize(shaders::equalize::Shader),
+    /// Op(T) = U
+    /// where U is a 4×5 table of numbers.
+    Statistics(shaders::statistics::Shader),
 }
 
 #[derive(Clone, Debug)]
 pub(crate) enum BinaryOp {
     /// Op = id
     Affine(Affine),
+    /// Op = id
+    Perspective(Perspective),
     /// Op[T, U] = T
     /// where T = U
     Inscribe { placement: Rectangle },
@@ -153,6 +198,17 @@ pub(crate) enum BinaryOp {
     /// Sample from a palette based on the color value of another image.
     /// Op[T, U] = T
     Palette(shaders::PaletteShader),
+    /// Op[T, U] = T
+    /// where T = U
+    Blend { placement: Rectangle, blend: Blend },
+    /// Sample T at positions offset by U.
+    /// Op[T, U] = T
+    /// where U has the size of T and is Scalars
+    Warp(Warp),
+    /// Compare T and U structurally.
+    /// Op[T, U] = V
+    /// where T = U and V is a scalar map
+    Ssim(Ssim),
 }
 
 /// A rectangle in `u32` space.
@@ -167,10 +223,107 @@ pub struct Rectangle {
     pub max_y: u32,
 }
 
-#[derive(Clone, Copy)]
+/// The compositing operator used when blending one image onto another.
+///
+/// The first group are the operators of Porter and Duff's algebra. In their terms, the image
+/// placed above is the _source_ and the image below is the _destination_. These operators work on
+/// the linear representation of colors, i.e. on the values after any electrical transfer function
+/// has been undone, with premultiplied alpha. The areas of the image below that are not covered by
+/// the placement are treated as if the source was fully transparent there.
+///
+/// Reference: Porter, Duff, Compositing Digital Images <https://doi.org/10.1145/964965.808606>
+///
+/// The second group are the blend modes of the W3C Compositing and Blending specification. They
+/// mix the colors of source and backdrop with a blend function `B(Cb, Cs)` and then composite the
+/// result with source-over. These are only available for RGB images. The specification defines
+/// them on sRGB encoded values, which is not always the best choice. Each mode documents in which
+/// space its blend function is evaluated. The encoded values are those of the image's own transfer
+/// function and such modes also composite them, like the specification. All other modes composite
+/// in linear RGB.
+///
+/// Reference: <https://www.w3.org/TR/compositing-1/#blending>
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[non_exhaustive]
 pub enum Blend {
-    Alpha,
+    /// Neither source nor destination remain, the result is fully transparent.
+    Clear,
+    /// The source is placed over the destination.
+    ///
+    /// This is the usual alpha blending.
+    SourceOver,
+    /// The destination is placed over the source.
+    DestinationOver,
+    /// The part of the source inside the destination replaces the destination.
+    In,
+    /// The part of the source outside the destination replaces the destination.
+    Out,
+    /// The part of the source inside the destination is placed over the destination.
+    Atop,
+    /// The parts of source and destination outside the other are combined.
+    Xor,
+    /// Source and destination are added.
+    Plus,
+    /// Multiplies the colors, `B = Cb·Cs`.
+    ///
+    /// Evaluated in linear RGB, where it corresponds to physically filtering light.
+    Multiply,
+    /// The complement of multiplying the complements, `B = Cb + Cs - Cb·Cs`.
+    ///
+    /// Evaluated in linear RGB.
+    Screen,
+    /// Multiplies or screens depending on the backdrop, i.e. `HardLight` with swapped inputs.
+    ///
+    /// Evaluated on encoded values since the switch at `0.5` is meant as perceptual mid-gray.
+    Overlay,
+    /// Selects the darker of each component, `B = min(Cb, Cs)`.
+    ///
+    /// Evaluated in linear RGB. The result is identical in any monotonic encoding.
+    Darken,
+    /// Selects the lighter of each component, `B = max(Cb, Cs)`.
+    ///
+    /// Evaluated in linear RGB. The result is identical in any monotonic encoding.
+    Lighten,
+    /// Brightens the backdrop to reflect the source, `B = min(1, Cb / (1 - Cs))`.
+    ///
+    /// Evaluated in linear RGB, like an exposure adjustment.
+    ColorDodge,
+    /// Darkens the backdrop to reflect the source, `B = 1 - min(1, (1 - Cb) / Cs)`.
+    ///
+    /// Evaluated in linear RGB.
+    ColorBurn,
+    /// Multiplies or screens depending on the source.
+    ///
+    /// Evaluated on encoded values, see `Overlay`.
+    HardLight,
+    /// Darkens or lightens depending on the source, a softer form of `HardLight`.
+    ///
+    /// Evaluated on encoded values, the specification's formula is tuned for them.
+    SoftLight,
+    /// The absolute difference of the components, `B = |Cb - Cs|`.
+    ///
+    /// Evaluated in linear RGB.
+    Difference,
+    /// Like `Difference` but with lower contrast, `B = Cb + Cs - 2·Cb·Cs`.
+    ///
+    /// Evaluated in linear RGB.
+    Exclusion,
+    /// The hue of the source with saturation and luminosity of the backdrop.
+    ///
+    /// Evaluated on encoded values. The luminosity weights `0.3, 0.59, 0.11` of the
+    /// specification are the ones of Rec. 601 luma, which is defined on encoded values.
+    Hue,
+    /// The saturation of the source with hue and luminosity of the backdrop.
+    ///
+    /// Evaluated on encoded values, see `Hue`.
+    Saturation,
+    /// The hue and saturation of the source with the luminosity of the backdrop.
+    ///
+    /// Evaluated on encoded values, see `Hue`.
+    Color,
+    /// The luminosity of the source with hue and saturation of the backdrop.
+    ///
+    /// Evaluated on encoded values, see `Hue`.
+    Luminosity,
 }
 
 /// Describes an affine transformation of an image.
@@ -187,12 +340,29 @@ pub struct Affine {
     pub sampling: AffineSample,
 }
 
+/// Describes a projective transformation of an image, also called a homography.
+///
+/// In contrast to affine transformations, parallel lines need not stay parallel. A rectangle can
+/// be mapped onto any convex quadrilateral, which corrects the perspective of a photographed
+/// document for example.
+#[derive(Clone, Copy, Debug)]
+pub struct Perspective {
+    /// The projective transformation, as a row-major homogeneous matrix.
+    ///
+    /// It maps pixel coordinates of the transformed image to pixel coordinates of the image
+    /// below. The result `[x, y, w]` represents the point `[x/w, y/w]`.
+    pub transformation: [f32; 9],
+    /// How pixels are resolved from the underlying texture.
+    pub sampling: AffineSample,
+}
+
 /// The way to perform sampling of an texture that was transformed with an affine transformation.
 ///
-/// You have to be careful that there is NO built-in functionality to avoid attacks that downscale
-/// an image so far that a very particular subset of pixels (or linear interpolation) is shown that
-/// results in an image visually very different from the original. Such an attack works because
-/// scaling down leads to many pixels being ignored.
+/// You have to be careful that these methods do NOT avoid attacks that downscale an image so far
+/// that a very particular subset of pixels (or linear interpolation) is shown that results in an
+/// image visually very different from the original. Such an attack works because scaling down
+/// leads to many pixels being ignored. Use [`CommandBuffer::resize`] for downscaling images from
+/// untrusted sources, and [`CommandBuffer::refuse_sparse_sampling`] to reject such transformations.
 #[derive(Clone, Copy, Debug)]
 pub enum AffineSample {
     /// Choose the nearest pixel.
@@ -202,7 +372,9 @@ pub enum AffineSample {
     /// Interpolate bi-linearly between nearest pixels.
     ///
     /// We rely on the executing GPU sampler2D for determining the color, in particular it will happen
-    /// in _linear_ RGB and this method can only be used on RGB-ish images.
+    /// in the _linear_ representation and this method can only be used on colors where mixing
+    /// that is meaningful. These are RGB-ish images (except `Yuv` samples), scalars, and Oklab
+    /// whose linear representation is Lab.
     BiLinear,
 }
 
@@ -385,9 +557,183 @@ pub enum DerivativeMethod {
     Scharr3To8Bit,
 }
 
+/// The representation of the gradient computed by [`CommandBuffer::gradient`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum GradientKind {
+    /// The derivatives along width and height, `(Dx, Dy)`.
+    ///
+    /// Both are signed and stored with an offset of `0.5`, that is a value of `0.5` means there
+    /// is no change along that axis.
+    Cartesian,
+    /// The magnitude and the direction of the gradient.
+    ///
+    /// The direction is the fraction of a full turn, offset by `0.5` such that `0.5` points along
+    /// the width, `0.75` along the height and `0.0` as well as `1.0` against the width. Pixels
+    /// without any gradient have a magnitude of `0.0` and a direction of `0.5`.
+    Polar,
+}
+
+/// The shapes accumulated by [`CommandBuffer::hough`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum Hough {
+    /// Lines, in the normal form `x·cos(θ) + y·sin(θ) = ρ` relative to the center of the image.
+    ///
+    /// The accumulator has `thetas` columns and `rhos` rows. Column `i` has the angle
+    /// `θ = π·i/thetas` and row `j` the distance `ρ = ((j + 0.5)/rhos - 0.5)·d` where `d` is the
+    /// length of the image diagonal. See [`Hough::line`] for this conversion.
+    Lines { thetas: u32, rhos: u32 },
+    /// Circles of a fixed radius, in pixels.
+    ///
+    /// The accumulator has the size of the image and each pixel is the center of a circle.
+    Circles { radius: f32 },
+}
+
+/// A local maximum of a Hough accumulator, see [`Hough::peaks`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HoughPeak {
+    /// The column of the accumulator.
+    pub x: u32,
+    /// The row of the accumulator.
+    pub y: u32,
+    /// The fraction of samples on the shape which are edge pixels.
+    pub votes: f32,
+}
+
+/// The variants of structural similarity computed by [`CommandBuffer::ssim`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Ssim {
+    /// The structural similarity index at the resolution of the images.
+    ///
+    /// Reference: Wang, Bovik, Sheikh, Simoncelli, Image quality assessment: from error visibility
+    /// to structural similarity <https://doi.org/10.1109/TIP.2003.819861>
+    Single,
+    /// The multi-scale structural similarity index over five scales.
+    ///
+    /// Each scale is half the size of the previous one. The contrast and structure terms of all
+    /// scales, and the luminance term of the coarsest one, are combined with the exponents of the
+    /// paper. Unlike the paper, this is done per pixel, taking the terms at the corresponding
+    /// position of each scale, such that the result is a map of the original size.
+    ///
+    /// Reference: Wang, Simoncelli, Bovik, Multiscale structural similarity for image quality
+    /// assessment <https://doi.org/10.1109/ACSSC.2003.1292216>
+    MultiScale,
+}
+
+/// The perceptual hashes computed by [`CommandBuffer::perceptual_hash`].
+///
+/// All of them have 64 bits and follow the definitions of the `imagehash` Python library. Similar
+/// images have hashes with a small Hamming distance.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum PerceptualHash {
+    /// aHash, comparing the pixels of an 8×8 thumbnail with their mean.
+    Average,
+    /// dHash, comparing the pixels of a 9×8 thumbnail with their left neighbor.
+    Difference,
+    /// pHash, comparing the lowest 8×8 frequencies of the DCT of a 32×32 thumbnail with their
+    /// median.
+    Dct,
+}
+
+/// The histogram equalizations performed by [`CommandBuffer::equalize`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum Equalization {
+    /// Map the lightness with the cumulative histogram of the whole image.
+    Global,
+    /// Contrast limited adaptive histogram equalization, CLAHE.
+    ///
+    /// The image is divided into a grid of `tiles` along width and height, each with a mapping
+    /// curve of its own. Pixels are mapped by bilinear interpolation between the curves of the
+    /// four closest tile centers. Before accumulating a curve, bins with more than `clip_limit`
+    /// times the average count are clipped and the excess is spread evenly over all bins. This
+    /// limits the amplification of noise in uniform regions.
+    ///
+    /// Reference: Zuiderveld, Contrast limited adaptive histogram equalization, Graphics Gems IV
+    Adaptive { tiles: [u32; 2], clip_limit: f32 },
+}
+
+/// Statistics of each channel of an image, see [`CommandBuffer::statistics`].
+///
+/// Each array has one entry for each of the four components of the image, in the order of its
+/// linear representation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Statistics {
+    /// The smallest value.
+    pub min: [f32; 4],
+    /// The largest value.
+    pub max: [f32; 4],
+    /// The sum of all values.
+    pub sum: [f32; 4],
+    /// The arithmetic mean.
+    pub mean: [f32; 4],
+    /// The population variance, the mean squared difference to the mean.
+    pub variance: [f32; 4],
+}
+
+/// The basis functions of coherent noise, see [`Noise`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum NoiseKind {
+    /// Random values on the lattice, interpolated smoothly in between.
+    Value,
+    /// Random gradients on the lattice, Ken Perlin's improved noise.
+    Perlin,
+    /// Random gradients on the corners of a triangular lattice.
+    Simplex,
+    /// The distance to the closest of random feature points, one in each lattice cell.
+    ///
+    /// Also called cellular noise. Distances are clamped to the size of one cell.
+    Worley,
+}
+
+/// Parameters of coherent noise, see [`CommandBuffer::noise`].
+///
+/// The noise is a sum of octaves, also called fractional Brownian motion. Each octave has the
+/// frequency of the previous multiplied by `lacunarity` and its amplitude multiplied by `gain`.
+/// The sum is normalized such that all values are in the range `[0, 1]`.
+///
+/// The struct is non-exhaustive, so outside this crate it is created with [`Noise::new`] and
+/// adjusted with [`Noise::with_octaves`] and [`Noise::with_fbm`]. The fields can still be read and
+/// modified.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct Noise {
+    /// The noise function of each octave.
+    pub kind: NoiseKind,
+    /// Each seed defines a different, independent noise.
+    pub seed: u32,
+    /// The frequency of the first octave, in lattice cells per pixel.
+    pub frequency: f32,
+    /// The number of octaves, at least one.
+    pub octaves: u32,
+    /// The factor of the frequency from one octave to the next.
+    pub lacunarity: f32,
+    /// The factor of the amplitude from one octave to the next.
+    pub gain: f32,
+}
+
+/// The distribution of white noise, see [`CommandBuffer::white_noise`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum WhiteNoise {
+    /// Uniformly distributed values in `[0, 1)`.
+    ///
+    /// Each value is one of `2^24` equally spaced values, which are exact on every device.
+    Uniform,
+    /// Normally distributed values, with the given mean and standard deviation.
+    ///
+    /// The values are derived from uniform values with the Box-Muller transform. They are not
+    /// clamped, an image with normalized samples clamps them when it is encoded.
+    Gaussian { mean: f32, std_dev: f32 },
+}
+
 /// Methods for removing noise from an image.
 ///
-/// WIP: these are not yet implemented.
+/// See [`CommandBuffer::smooth`] for the methods that are currently implemented.
 ///
 /// This intuitive understanding applies to single valued, gray scale images. The operator will
 /// also work for any colored images as long as the color space defines a luminance, lightness,
@@ -401,11 +747,13 @@ pub enum SmoothingMethod {
     Gaussian,
     /// Choose the median value from surrounding pixels.
     ///
-    /// The choice is made through the Luma channel.
+    /// The choice is made through the Luma channel and the chosen pixel is returned as a whole,
+    /// there is no separate median of each channel.
     Median,
     /// Bilateral filter, weighting pixels by values.
     ///
-    /// The weighting is made through the Luma channel.
+    /// The weighting is made through the Luma channel by default, see [`RangeDistance`] for the
+    /// alternatives.
     Bilteral,
     /// Chooses a value from the surrounding region with minimal variance.
     ///
@@ -413,6 +761,8 @@ pub enum SmoothingMethod {
     /// mean value and the regions are defined as exactly 4 regions overlapping on the axes and the
     /// pixel itself.
     ///
+    /// The variance is measured on the Luma channel.
+    ///
     /// Using hexadecimal bit masks to represent the regions, an example with 3×3 regions:
     ///
     /// ```text
@@ -425,6 +775,133 @@ pub enum SmoothingMethod {
     Kuwahara,
 }
 
+/// Parameters of a smoothing operation, see [`CommandBuffer::smooth`].
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct Smoothing {
+    pub method: SmoothingMethod,
+    /// The number of pixels considered on each side of the center pixel.
+    pub radius: u32,
+    /// The standard deviation of spatial weights, in pixels.
+    pub sigma: f32,
+    /// The standard deviation of range weights, for methods weighting pixels by value.
+    pub range_sigma: f32,
+    /// How the difference in value between two pixels is measured.
+    pub range: RangeDistance,
+}
+
+/// A convolution kernel, see [`CommandBuffer::convolve`].
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct Kernel {
+    pub weights: KernelWeights,
+    /// How pixels outside the image are sampled.
+    pub border: BorderMode,
+}
+
+/// The weights of a convolution kernel.
+///
+/// The weight in the center is applied to the pixel itself, for even sizes that is the weight at
+/// index `size / 2`. The other weights are applied to the pixels at their offset from the center.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum KernelWeights {
+    /// A matrix of `width` by `height` weights, in row-major order.
+    Dense {
+        width: u32,
+        height: u32,
+        weights: Vec<f32>,
+    },
+    /// The outer product of a row of weights along the width and a column along the height.
+    Separable { width: Vec<f32>, height: Vec<f32> },
+}
+
+/// Parameters of a displacement warp, see [`CommandBuffer::warp`].
+#[derive(Clone, Copy, Debug)]
+#[non_exhaustive]
+pub struct Warp {
+    /// The factor applied to displacement values to get offsets in pixels.
+    pub scale: f32,
+    /// The displacement value that corresponds to no offset.
+    ///
+    /// For unsigned normalized samples this is usually `0.5`, such that values above and below
+    /// it shift in opposite directions.
+    pub neutral: f32,
+    /// How pixels are resolved from the warped image.
+    pub sampling: AffineSample,
+    /// How pixels outside the image are sampled.
+    pub border: BorderMode,
+}
+
+/// How pixels outside of an image are sampled.
+///
+/// These are evaluated in shaders on integer pixel indices. The address modes of a sampler are
+/// not used since `ClampToBorder` is an optional feature with only three colors available, and
+/// there is no equivalent of `Reflect101`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum BorderMode {
+    /// Repeat the closest edge pixel, `aaa|abcd|ddd`.
+    ///
+    /// Also called replicate.
+    Clamp,
+    /// Reflect at the edge, including the edge pixel, `cba|abcd|dcb`.
+    Mirror,
+    /// Repeat the image periodically, `bcd|abcd|abc`.
+    Wrap,
+    /// A constant color in the linear representation of the image, `xxx|abcd|xxx`.
+    Constant([f32; 4]),
+    /// Reflect at the edge pixel, without repeating it, `dcb|abcd|cba`.
+    Reflect101,
+}
+
+/// Measures the difference of two colors, for smoothing methods that weight pixels by value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum RangeDistance {
+    /// The difference in luma of the linear representation.
+    ///
+    /// That is the luminance Y for Rgb colors, lightness for Oklab, and the first component of
+    /// scalars.
+    Luma,
+    /// The euclidean distance in the linear representation.
+    Linear,
+    /// The euclidean distance in Oklab, a perceptual difference of colors.
+    ///
+    /// This requires an Rgb color with D65 whitepoint, or Oklab itself.
+    Oklab,
+}
+
+/// The reconstruction filter of a resize, see [`CommandBuffer::resize`].
+///
+/// Each filter is a function of the distance to the sampled position, measured in pixels of the
+/// source image. It is zero outside of its support.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ResizeFilter {
+    /// A tent function, i.e. linear interpolation, with a support of 1.
+    Triangle,
+    /// The cubic Catmull-Rom spline, with a support of 2.
+    ///
+    /// This is the bicubic filter of many image editors, a Mitchell-Netravali filter with `B = 0`
+    /// and `C = 0.5`. It is interpolating but slightly overshoots at edges.
+    CatmullRom,
+    /// The cubic filter recommended by Mitchell and Netravali, with a support of 2.
+    ///
+    /// With `B = C = 1/3` it trades some sharpness for less ringing than `CatmullRom`.
+    Mitchell,
+    /// A sinc windowed by a sinc with two lobes, with a support of 2.
+    Lanczos2,
+    /// A sinc windowed by a sinc with three lobes, with a support of 3.
+    Lanczos3,
+    /// The average of the source pixels, weighted by the area covered by each target pixel.
+    ///
+    /// Every source pixel contributes to the result with its true share when downscaling, which
+    /// makes this the method of choice for images from untrusted sources. When upscaling it is
+    /// equivalent to choosing the nearest pixel, except at pixel edges.
+    Area,
+}
+
 #[derive(Debug)]
 pub struct CommandError {
     inner: CommandErrorKind,
@@ -449,6 +926,22 @@ enum CommandErrorKind {
 ///   other holomorphic functions. Ways to construct mobius transfrom from three key points. That
 ///   is particular relevant for color correction.
 impl CommandBuffer {
+    /// Refuse operations that sample an image sparsely.
+    ///
+    /// An operation that reads only a subset of the pixels is rejected with an error from then on
+    /// if it shrinks the image too much along any direction. Such sampling is what image-scaling
+    /// attacks exploit, where a crafted image shows an entirely different picture once
+    /// downscaled. The `threshold` is compared to the scale factor times the number of pixels read
+    /// along each axis by the sampling method, `1` for nearest and `2` for bi-linear sampling. A
+    /// threshold of `1.0` thus rejects exactly those transformations that skip pixels, which are
+    /// factors below `1.0` for nearest and below `0.5` for bi-linear sampling.
+    ///
+    /// Use [`CommandBuffer::resize`] instead, all its filters cover every source pixel. Warps are
+    /// refused regardless of the threshold, see [`CommandBuffer::warp`].
+    pub fn refuse_sparse_sampling(&mut self, threshold: f32) {
+        self.sparse_sampling_limit = Some(threshold);
+    }
+
     /// Declare an input.
     ///
     /// Inputs MUST later be bound from the pool during launch.
@@ -472,8 +965,32 @@ impl CommandBuffer {
     }
 
     /// Select a rectangular part of an image.
+    ///
+    /// The output has the size of the rectangle, which must not be empty. It may extend past the
+    /// right and bottom edge of the image, where the result is transparent. See
+    /// [`CommandBuffer::crop_with_border`] for other ways to fill these pixels.
     pub fn crop(&mut self, src: Register, rect: Rectangle) -> Result<Register, CommandError> {
         let desc = self.describe_reg(src)?.clone();
+
+        if !Rectangle::from(&desc).contains(rect) {
+            let transparent = BorderMode::Constant([0.0; 4]);
+            return self.crop_with_border(
+                src,
+                i32::try_from(rect.x).map_err(|_| CommandError::OTHER)?,
+                i32::try_from(rect.y).map_err(|_| CommandError::OTHER)?,
+                rect.width(),
+                rect.height(),
+                transparent,
+            );
+        }
+
+        if rect.width() == 0 || rect.height() == 0 {
+            return Err(CommandError::OTHER);
+        }
+
+        let desc = Descriptor::with_texel(desc.texel, rect.width(), rect.height())
+            .ok_or(CommandError::OTHER)?;
+
         Ok(self.push(Op::Unary {
             src,
             op: UnaryOp::Crop(rect),
@@ -481,6 +998,71 @@ impl CommandBuffer {
         }))
     }
 
+    /// Select a rectangular part of an image, which may extend past any of its edges.
+    ///
+    /// The part starts at pixel `(x, y)` of the image, negative coordinates are left of or above
+    /// the image, and has the given width and height. Pixels outside the image are sampled
+    /// according to the border mode.
+    ///
+    /// Pixels are addressed by integer indices only. Those inside the image, and all of those
+    /// which the border mode maps into the image, are copied exactly.
+    pub fn crop_with_border(
+        &mut self,
+        src: Register,
+        x: i32,
+        y: i32,
+        width: u32,
+        height: u32,
+        border: BorderMode,
+    ) -> Result<Register, CommandError> {
+        let desc = self.describe_reg(src)?.clone();
+
+        if width == 0 || height == 0 {
+            return Err(CommandError::OTHER);
+        }
+
+        let desc = Descriptor::with_texel(desc.texel, width, height).ok_or(CommandError::OTHER)?;
+
+        let (border, border_color) = border.shader_parameters();
+        let op = Op::Unary {
+            src,
+            op: UnaryOp::Extend(shaders::extend::Shader {
+                origin: [x, y],
+                border,
+                border_color,
+            }),
+            desc,
+        };
+
+        Ok(self.push(op))
+    }
+
+    /// Add pixels on each side of an image, filled according to the border mode.
+    ///
+    /// The output is larger than the input by `left + right` in width and by `top + bottom` in
+    /// height. This is a `crop_with_border` of a rectangle around the image.
+    pub fn pad(
+        &mut self,
+        src: Register,
+        left: u32,
+        top: u32,
+        right: u32,
+        bottom: u32,
+        border: BorderMode,
+    ) -> Result<Register, CommandError> {
+        let (width, height) = self.describe_reg(src)?.size();
+
+        let grow =
+            |size: u32, before: u32, after: u32| size.checked_add(before)?.checked_add(after);
+        let width = grow(width, left, right).ok_or(CommandError::OTHER)?;
+        let height = grow(height, top, bottom).ok_or(CommandError::OTHER)?;
+
+        let x = i32::try_from(left).map_err(|_| CommandError::OTHER)?;
+        let y = i32::try_from(top).map_err(|_| CommandError::OTHER)?;
+
+        self.crop_with_border(src, -x, -y, width, height, border)
+    }
+
     /// Create an image with different color encoding.
     ///
     /// This goes through linear RGB, not ICC, and requires the two models to have same whitepoint.
@@ -624,12 +1206,36 @@ impl CommandBuffer {
     }
 
     /// Embed this image as part of a larger one.
+    ///
+    /// Both images must have the same texel. The rectangle must have the size of `above` and it
+    /// must be contained in `below`.
     pub fn inscribe(
         &mut self,
         below: Register,
         rect: Rectangle,
         above: Register,
     ) -> Result<Register, CommandError> {
+        let desc = self.check_placement(below, rect, above)?;
+
+        let op = Op::Binary {
+            lhs: below,
+            rhs: above,
+            op: BinaryOp::Inscribe {
+                placement: rect.normalize(),
+            },
+            desc,
+        };
+
+        Ok(self.push(op))
+    }
+
+    /// Check the placement of one image in another, returning the descriptor of the result.
+    fn check_placement(
+        &self,
+        below: Register,
+        rect: Rectangle,
+        above: Register,
+    ) -> Result<Descriptor, CommandError> {
         let desc_below = self.describe_reg(below)?;
         let desc_above = self.describe_reg(above)?;
 
@@ -639,7 +1245,7 @@ impl CommandBuffer {
             });
         }
 
-        if Rectangle::with_layout(&desc_above.layout) != rect {
+        if (rect.width(), rect.height()) != desc_above.size() {
             return Err(CommandError::OTHER);
         }
 
@@ -647,16 +1253,7 @@ impl CommandBuffer {
             return Err(CommandError::OTHER);
         }
 
-        let op = Op::Binary {
-            lhs: below,
-            rhs: above,
-            op: BinaryOp::Inscribe {
-                placement: rect.normalize(),
-            },
-            desc: desc_below.clone(),
-        };
-
-        Ok(self.push(op))
+        Ok(desc_below.clone())
     }
 
     /// Extract some channels from an image data into a new view.
@@ -875,68 +1472,841 @@ impl CommandBuffer {
         Ok(self.push(op))
     }
 
-    /// Overlay this image as part of a larger one, performing blending.
-    pub fn blend(
+    /// Calculate the gradient of an image, both of its first derivatives in a single pass.
+    ///
+    /// The derivatives are taken of the luma of the image, with the smoothing and difference
+    /// operator defined by `method`. The output is a `Scalars` image of the same size with two
+    /// 16-bit channels, luma and alpha parts, whose meaning is described by `kind`. For a pixel
+    /// these are the derivatives along width and height or the magnitude and direction of their
+    /// combined vector. Pixels outside the image are treated as repetitions of the closest edge
+    /// pixel.
+    ///
+    /// The luma is the first value for `Scalars` images, the luminance of `Rgb` and the lightness
+    /// of `Oklab`. The alpha channel of the source is ignored.
+    ///
+    /// Returns an error if the method does not yet have an implementation.
+    pub fn gradient(
         &mut self,
-        _below: Register,
-        _rect: Rectangle,
-        _above: Register,
-        _blend: Blend,
+        image: Register,
+        method: DerivativeMethod,
+        kind: GradientKind,
     ) -> Result<Register, CommandError> {
-        // TODO: What blending should we support
-        Err(CommandError::UNIMPLEMENTED)
-    }
+        let desc_src = self.describe_reg(image)?;
 
-    /// A solid color image, from a descriptor and a single texel.
-    pub fn solid(&mut self, describe: Descriptor, data: &[u8]) -> Result<Register, CommandError> {
-        if !describe.is_consistent() {
-            return Err(CommandError {
-                inner: CommandErrorKind::BadDescriptor(describe),
-            });
-        }
+        let smoothing = method
+            .smoothing()
+            .map_err(|_| CommandError::UNIMPLEMENTED)?;
 
-        if data.len() != usize::from(describe.layout.bytes_per_texel) {
-            return Err(CommandError {
-                inner: CommandErrorKind::BadDescriptor(describe),
-            });
-        }
+        let texel = Texel {
+            block: buffer::Block::Pixel,
+            samples: buffer::Samples {
+                bits: buffer::SampleBits::Int16x2,
+                parts: SampleParts::LumaA,
+            },
+            color: Color::Scalars {
+                transfer: buffer::Transfer::Linear,
+            },
+        };
 
-        Ok(self.push(Op::Construct {
-            desc: describe,
-            op: ConstructOp::Solid(data.to_owned()),
-        }))
+        let (width, height) = desc_src.size();
+        let desc = Descriptor::with_texel(texel, width, height).ok_or(CommandError::OTHER)?;
+
+        let op = Op::Unary {
+            src: image,
+            op: UnaryOp::Gradient(shaders::gradient::Shader {
+                luma: desc_src.texel.color.luma_weights(),
+                smoothing,
+                kind: kind as u32,
+            }),
+            desc,
+        };
+
+        Ok(self.push(op))
     }
 
-    /// A 2d image with a normal distribution.
+    /// Detect edges with the Canny edge detector.
     ///
-    /// The parameters are controlled through the `distribution` parameter while the `texel`
-    /// parameter controls the eventual binary encoding of the image. It must be compatible with a
-    /// single gray channel (but you can have electrical transfer functions, choose arbitrary bit
-    /// widths etc.).
-    pub fn distribution_normal2d(
+    /// The image is smoothed with a Gaussian of standard deviation `1.4` pixels, then its
+    /// [`gradient`](Self::gradient) is computed with `method`. Edges are thinned to the pixels
+    /// whose gradient magnitude is maximal across the edge. Of those, pixels with a magnitude of
+    /// at least `high` are edges and pixels with a magnitude of at least `low` are edges if they
+    /// are connected to another edge pixel. The magnitude is measured in the units of the
+    /// gradient, that is differences of luma per pixel.
+    ///
+    /// The connection is made by a bounded number of propagation passes, each extending edges by
+    /// one pixel. A chain of weak pixels is only detected up to 32 pixels away from the closest
+    /// strong pixel.
+    ///
+    /// The output is a `Scalars` image of the same size with a single 8-bit luma channel, which is
+    /// `1.0` on edges and `0.0` elsewhere.
+    ///
+    /// Returns an error if the thresholds are not ordered, negative, or if the method does not yet
+    /// have an implementation.
+    pub fn canny(
         &mut self,
-        describe: Descriptor,
-        distribution: shaders::DistributionNormal2d,
+        image: Register,
+        low: f32,
+        high: f32,
+        method: DerivativeMethod,
     ) -> Result<Register, CommandError> {
-        if !describe.is_consistent() {
-            return Err(CommandError {
-                inner: CommandErrorKind::BadDescriptor(describe),
-            });
+        if !(low.is_finite() && high.is_finite() && 0.0 <= low && low <= high) {
+            return Err(CommandError::OTHER);
         }
 
-        if describe.texel.samples.parts != SampleParts::Luma
-            && describe.texel.samples.parts != SampleParts::LumaA
-        {
-            return Err(CommandError {
-                inner: CommandErrorKind::BadDescriptor(describe),
-            });
-        }
+        let smoothed = self.smooth(image, Smoothing::gaussian(1.4))?;
+        let gradient = self.gradient(smoothed, method, GradientKind::Polar)?;
 
-        Ok(self.push(Op::Construct {
-            desc: describe,
-            op: ConstructOp::DistributionNormal(distribution),
-        }))
-    }
+        let texel = Texel {
+            block: buffer::Block::Pixel,
+            samples: buffer::Samples {
+                bits: buffer::SampleBits::Int8,
+                parts: SampleParts::Luma,
+            },
+            color: Color::Scalars {
+                transfer: buffer::Transfer::Linear,
+            },
+        };
+
+        let (width, height) = self.describe_reg(gradient)?.size();
+        let desc = Descriptor::with_texel(texel, width, height).ok_or(CommandError::OTHER)?;
+
+        let op = Op::Unary {
+            src: gradient,
+            op: UnaryOp::Canny { low, high },
+            desc,
+        };
+
+        Ok(self.push(op))
+    }
+
+    /// Accumulate the votes of edge pixels for lines or circles, the Hough transform.
+    ///
+    /// The source is a binary edge image, such as the output of [`canny`](Self::canny), where
+    /// pixels with a luma above `0.5` are edges. Each pixel of the output describes one shape, as
+    /// detailed by the variants of [`Hough`]. Its value is the fraction of samples along the shape
+    /// which hit an edge pixel, where samples are spaced about one pixel apart. For lines, this
+    /// is relative to the length of the image diagonal such that shorter lines through the
+    /// corners are not preferred.
+    ///
+    /// The output is a `Scalars` image with a single 16-bit luma channel. Use [`Hough::peaks`] on
+    /// the retired image to find the most prominent shapes.
+    pub fn hough(&mut self, image: Register, hough: Hough) -> Result<Register, CommandError> {
+        let desc_src = self.describe_reg(image)?;
+        let luma = desc_src.texel.color.luma_weights();
+
+        let (shader, (width, height)) = match hough {
+            Hough::Lines { thetas, rhos } => {
+                if thetas == 0 || rhos == 0 {
+                    return Err(CommandError::OTHER);
+                }
+
+                let shader = shaders::hough::Shader {
+                    luma,
+                    kind: 0,
+                    radius: 0.0,
+                    lines: [thetas, rhos],
+                };
+
+                (shader, (thetas, rhos))
+            }
+            Hough::Circles { radius } => {
+                if !(radius.is_finite() && radius > 0.0) {
+                    return Err(CommandError::OTHER);
+                }
+
+                let shader = shaders::hough::Shader {
+                    luma,
+                    kind: 1,
+                    radius,
+                    lines: [0, 0],
+                };
+
+                (shader, desc_src.size())
+            }
+        };
+
+        let texel = Texel {
+            block: buffer::Block::Pixel,
+            samples: buffer::Samples {
+                bits: buffer::SampleBits::Int16,
+                parts: SampleParts::Luma,
+            },
+            color: Color::Scalars {
+                transfer: buffer::Transfer::Linear,
+            },
+        };
+
+        let desc = Descriptor::with_texel(texel, width, height).ok_or(CommandError::OTHER)?;
+
+        let op = Op::Unary {
+            src: image,
+            op: UnaryOp::Hough(shader),
+            desc,
+        };
+
+        Ok(self.push(op))
+    }
+
+    /// Compute a 64-bit perceptual hash of an image.
+    ///
+    /// The luma of the image is scaled to a small thumbnail with a Lanczos filter, from which the
+    /// bits are derived as described by the variants of [`PerceptualHash`]. Like common
+    /// implementations, RGB images are hashed by the luma of their encoded values with the weights
+    /// of Rec. 601, other colors by the first value of their linear representation. The alpha
+    /// channel is ignored.
+    ///
+    /// The output is an 8×8 `Scalars` image with a single 8-bit luma channel, where each pixel is
+    /// one bit of the hash, `1.0` if it is set. Use [`PerceptualHash::bits`] on the retired image
+    /// to get the hash itself.
+    pub fn perceptual_hash(
+        &mut self,
+        image: Register,
+        hash: PerceptualHash,
+    ) -> Result<Register, CommandError> {
+        const REC601: [f32; 3] = [0.299, 0.587, 0.114];
+
+        let desc = self.describe_reg(image)?.clone();
+
+        let (source, luma) = match desc.texel.color {
+            Color::Rgb {
+                luminance,
+                primary,
+                whitepoint,
+                transfer: _,
+            } => {
+                // Reinterpret the encoded values as linear ones, all further processing is done
+                // on them just like the reference does.
+                let encoded = Texel {
+                    color: Color::Rgb {
+                        luminance,
+                        primary,
+                        whitepoint,
+                        transfer: buffer::Transfer::Linear,
+                    },
+                    ..desc.texel.clone()
+                };
+
+                (self.transmute(image, encoded)?, REC601)
+            }
+            ref color => (image, color.luma_weights()),
+        };
+
+        let (kind, (width, height)) = match hash {
+            PerceptualHash::Average => (0, (8, 8)),
+            PerceptualHash::Difference => (1, (9, 8)),
+            PerceptualHash::Dct => (2, (32, 32)),
+        };
+
+        let texel = Texel {
+            block: buffer::Block::Pixel,
+            samples: buffer::Samples {
+                bits: buffer::SampleBits::Int8,
+                parts: SampleParts::Luma,
+            },
+            color: Color::Scalars {
+                transfer: buffer::Transfer::Linear,
+            },
+        };
+
+        let shader = |pass| shaders::hash::Shader { pass, luma, kind };
+
+        // Scale the luma, not the color, just like the reference converts to gray first.
+        let (full_width, full_height) = (desc.pixel_width(), desc.pixel_height());
+        let gray = Op::Unary {
+            src: source,
+            op: UnaryOp::PerceptualHash(shader(shaders::hash::Pass::Luma)),
+            desc: Descriptor::with_texel(texel.clone(), full_width, full_height)
+                .ok_or(CommandError::OTHER)?,
+        };
+
+        let gray = self.push(gray);
+        let thumbnail = self.resize(gray, width, height, ResizeFilter::Lanczos3)?;
+
+        let desc = Descriptor::with_texel(texel, 8, 8).ok_or(CommandError::OTHER)?;
+
+        let op = Op::Unary {
+            src: thumbnail,
+            op: UnaryOp::PerceptualHash(shader(shaders::hash::Pass::Bits)),
+            desc,
+        };
+
+        Ok(self.push(op))
+    }
+
+    /// Count the values of one channel of an image into bins.
+    ///
+    /// The range `0.0..=1.0` is divided into `bins` of equal width, values outside of it are
+    /// counted in the first or the last bin. Like for [`perceptual_hash`], the channels of RGB
+    /// images are counted by their encoded values while other colors are counted by their linear
+    /// representation. The `Luma` channel is available for all colors, as the luma of the values
+    /// that would be counted. The number of bins must be between 1 and 4096.
+    ///
+    /// The output holds numbers rather than colors. It is a `bins`×1 image of [`COUNT_TEXEL`],
+    /// read the counts with [`Retire::counts`] instead of moving it into the pool.
+    ///
+    /// [`perceptual_hash`]: Self::perceptual_hash
+    /// [`Retire::counts`]: crate::run::Retire::counts
+    pub fn histogram(
+        &mut self,
+        image: Register,
+        channel: ColorChannel,
+        bins: u32,
+    ) -> Result<Register, CommandError> {
+        let desc = self.describe_reg(image)?.clone();
+
+        if bins == 0 || bins > shaders::histogram::MAX_BINS {
+            return Err(CommandError::OTHER);
+        }
+
+        let source = match desc.texel.color {
+            Color::Rgb {
+                luminance,
+                primary,
+                whitepoint,
+                transfer: _,
+            } => {
+                // Count the encoded values, by reinterpreting them as linear ones.
+                let encoded = Texel {
+                    color: Color::Rgb {
+                        luminance,
+                        primary,
+                        whitepoint,
+                        transfer: buffer::Transfer::Linear,
+                    },
+                    ..desc.texel.clone()
+                };
+
+                self.transmute(image, encoded)?
+            }
+            _ => image,
+        };
+
+        let weights = match channel {
+            ColorChannel::Luma => {
+                let [r, g, b] = desc.texel.color.luma_weights();
+                [r, g, b, 0.0]
+            }
+            ColorChannel::Alpha => {
+                use SampleParts::*;
+                match desc.texel.samples.parts {
+                    A | LumaA | Rgba | Bgra | Argb | Abgr | LChA | LabA => [0.0, 0.0, 0.0, 1.0],
+                    _ => return Err(CommandError::OTHER),
+                }
+            }
+            channel => match desc.texel.color {
+                Color::Rgb { .. } => ChannelPosition::new(channel)
+                    .ok_or(CommandError::OTHER)?
+                    .into_vec4(),
+                _ => return Err(CommandError::OTHER),
+            },
+        };
+
+        let desc = Descriptor::with_texel(COUNT_TEXEL, bins, 1).ok_or(CommandError::OTHER)?;
+
+        let op = Op::Unary {
+            src: source,
+            op: UnaryOp::Histogram(shaders::histogram::Shader {
+                pass: shaders::histogram::Pass::Rows,
+                weights,
+                bins,
+            }),
+            desc,
+        };
+
+        Ok(self.push(op))
+    }
+
+    /// Enhance the contrast of an image by equalizing the histogram of its lightness.
+    ///
+    /// Only the lightness is changed, all other components are kept. For RGB images this is the
+    /// lightness `L` of Oklab, such that the chroma stays intact, which requires a D65 whitepoint.
+    /// Oklab images have their `L` equalized directly and `Scalars` their first value, all other
+    /// colors are rejected. The lightness is counted in 256 bins over the range `0.0..=1.0` and
+    /// mapped to the share of pixels in its own and all lower bins. See [`Equalization`] for the
+    /// global and the adaptive method.
+    ///
+    /// The output has the same descriptor as the input. The adaptive method requires a clip
+    /// limit above zero and between 1 and 32 tiles along each axis, but no more than pixels.
+    pub fn equalize(
+        &mut self,
+        image: Register,
+        equalization: Equalization,
+    ) -> Result<Register, CommandError> {
+        use shaders::equalize::{Mode, Pass, MAX_TILES};
+        let desc = self.describe_reg(image)?.clone();
+
+        let (mode, to_xyz) = match desc.texel.color {
+            Color::Rgb {
+                primary,
+                whitepoint: Whitepoint::D65,
+                ..
+            } => (Mode::Oklab, primary.to_xyz(Whitepoint::D65)),
+            Color::Oklab | Color::Scalars { .. } => (Mode::First, RowMatrix::diag(1.0, 1.0, 1.0)),
+            _ => {
+                return Err(CommandError {
+                    inner: CommandErrorKind::BadDescriptor(desc),
+                })
+            }
+        };
+
+        let (tiles, clip_limit) = match equalization {
+            Equalization::Global => ([1, 1], 0.0),
+            Equalization::Adaptive { tiles, clip_limit } => {
+                if !(clip_limit.is_finite() && clip_limit > 0.0) {
+                    return Err(CommandError::OTHER);
+                }
+
+                let (width, height) = desc.size();
+                let valid_tiles =
+                    |count: u32, size: u32| (1..=MAX_TILES.min(size)).contains(&count);
+                if !valid_tiles(tiles[0], width) || !valid_tiles(tiles[1], height) {
+                    return Err(CommandError::OTHER);
+                }
+
+                (tiles, clip_limit)
+            }
+        };
+
+        let op = Op::Unary {
+            src: image,
+            op: UnaryOp::Equalize(shaders::equalize::Shader {
+                pass: Pass::Apply,
+                mode,
+                to_xyz,
+                tiles,
+                clip_limit,
+            }),
+            desc,
+        };
+
+        Ok(self.push(op))
+    }
+
+    /// Compute the minimum, maximum, sum, mean and variance of each channel of an image.
+    ///
+    /// If a `region` is given then only its pixels are considered, it must not be empty and lie
+    /// within the image. Like for [`histogram`], the channels of RGB images are their encoded
+    /// values while other colors use their linear representation. All four components are
+    /// reduced, regardless of the number of channels of the image.
+    ///
+    /// The output holds numbers rather than colors. It is a 4×5 image of [`COUNT_TEXEL`] with the
+    /// bits of an `f32` in each pixel, read it with [`Retire::statistics`].
+    ///
+    /// [`histogram`]: Self::histogram
+    /// [`Retire::statistics`]: crate::run::Retire::statistics
+    pub fn statistics(
+        &mut self,
+        image: Register,
+        region: Option<Rectangle>,
+    ) -> Result<Register, CommandError> {
+        let desc = self.describe_reg(image)?.clone();
+
+        let image = match region {
+            None => image,
+            Some(region) => {
+                if !Rectangle::from(&desc).contains(region) {
+                    return Err(CommandError::OTHER);
+                }
+
+                self.crop(image, region)?
+            }
+        };
+
+        let source = match desc.texel.color {
+            Color::Rgb {
+                luminance,
+                primary,
+                whitepoint,
+                transfer: _,
+            } => {
+                // Reduce the encoded values, by reinterpreting them as linear ones.
+                let encoded = Texel {
+                    color: Color::Rgb {
+                        luminance,
+                        primary,
+                        whitepoint,
+                        transfer: buffer::Transfer::Linear,
+                    },
+                    ..desc.texel.clone()
+                };
+
+                self.transmute(image, encoded)?
+            }
+            _ => image,
+        };
+
+        let (width, height) = self.describe_reg(source)?.size();
+        let desc = Descriptor::with_texel(COUNT_TEXEL, 4, 5).ok_or(CommandError::OTHER)?;
+
+        let op = Op::Unary {
+            src: source,
+            op: UnaryOp::Statistics(shaders::statistics::Shader {
+                pass: shaders::statistics::Pass::Rows,
+                level: 0,
+                width,
+                height,
+            }),
+            desc,
+        };
+
+        Ok(self.push(op))
+    }
+
+    /// Smooth an image, removing noise.
+    ///
+    /// The output has the same descriptor as the input. Pixels outside the image are treated as
+    /// repetitions of the closest edge pixel. The implemented methods are:
+    ///
+    /// * `Gaussian` and `Laplace` are separable and rendered in two passes, along the width and
+    ///   then along the height. Colors are averaged in their linear representation and weighted
+    ///   by their alpha, such that transparent pixels do not bleed into their surroundings. The
+    ///   radius must not exceed 127.
+    /// * `Median` ranks the pixels of the window by their luma and returns the middle one as a
+    ///   whole. Alpha does not influence the rank but is returned with the chosen pixel. The
+    ///   radius must not exceed 5, that is an 11×11 window.
+    /// * `Bilteral` weights pixels by their distance and their difference in value, which
+    ///   preserves edges. Colors are averaged as with `Gaussian`. The radius must not exceed 16.
+    /// * `Kuwahara` returns the mean of the one region whose luma has the least variance. Colors
+    ///   are averaged as with `Gaussian`. The radius must be between 1 and 16.
+    pub fn smooth(&mut self, image: Register, config: Smoothing) -> Result<Register, CommandError> {
+        let desc = self.describe_reg(image)?.clone();
+
+        match config.method {
+            SmoothingMethod::Gaussian => {
+                if !(config.sigma.is_finite() && config.sigma > 0.0) {
+                    return Err(CommandError::OTHER);
+                }
+            }
+            SmoothingMethod::Laplace => {}
+            SmoothingMethod::Median => {
+                if config.radius > shaders::median::MAX_RADIUS {
+                    return Err(CommandError::OTHER);
+                }
+            }
+            SmoothingMethod::Kuwahara => {
+                if config.radius == 0 || config.radius > shaders::kuwahara::MAX_RADIUS {
+                    return Err(CommandError::OTHER);
+                }
+            }
+            SmoothingMethod::Bilteral => {
+                let valid_sigma = |sigma: f32| sigma.is_finite() && sigma > 0.0;
+                if !valid_sigma(config.sigma) || !valid_sigma(config.range_sigma) {
+                    return Err(CommandError::OTHER);
+                }
+
+                if config.radius > shaders::bilateral::MAX_RADIUS {
+                    return Err(CommandError::OTHER);
+                }
+
+                let has_oklab = matches!(
+                    desc.texel.color,
+                    Color::Oklab
+                        | Color::Rgb {
+                            whitepoint: Whitepoint::D65,
+                            ..
+                        }
+                );
+
+                if config.range == RangeDistance::Oklab && !has_oklab {
+                    return Err(CommandError {
+                        inner: CommandErrorKind::BadDescriptor(desc),
+                    });
+                }
+            }
+            _ => return Err(CommandError::UNIMPLEMENTED),
+        }
+
+        if config.radius as usize > (shaders::separable::MAX_TAPS - 1) / 2 {
+            return Err(CommandError::OTHER);
+        }
+
+        let op = Op::Unary {
+            src: image,
+            op: UnaryOp::Smooth(config),
+            desc,
+        };
+
+        Ok(self.push(op))
+    }
+
+    /// Convolve an image with a kernel.
+    ///
+    /// The output has the same descriptor as the input. All channels, including alpha, are
+    /// convolved independently in their linear representation. Note that, as is common for image
+    /// filters, the kernel is not mirrored. That is, this computes a cross-correlation.
+    ///
+    /// Dense kernels are rendered in a single pass and may have at most 256 weights. Separable
+    /// kernels are rendered in two passes with up to 256 weights each, except when the first
+    /// pass could leave the value range of the image. That is the case if it has negative
+    /// weights or weights that sum to more than one. Such kernels are expanded to a dense one
+    /// instead.
+    pub fn convolve(&mut self, image: Register, kernel: Kernel) -> Result<Register, CommandError> {
+        let desc = self.describe_reg(image)?.clone();
+
+        if !kernel.is_valid() || kernel.passes().is_none() {
+            return Err(CommandError::OTHER);
+        }
+
+        let op = Op::Unary {
+            src: image,
+            op: UnaryOp::Convolve(kernel),
+            desc,
+        };
+
+        Ok(self.push(op))
+    }
+
+    /// Resize an image to a new width and height.
+    ///
+    /// The output has the texel of the input. The image is resampled with the filter in two
+    /// passes, first along the height and then along the width. When an axis is scaled down, the
+    /// support of the filter is widened by the scale factor such that every source pixel
+    /// contributes to the result. Pixels outside the image are ignored and the weights of the
+    /// remaining ones are normalized.
+    ///
+    /// The colors are filtered in their linear representation and weighted by their alpha. This
+    /// requires a color that can be interpolated, the same ones as for [`AffineSample::BiLinear`].
+    /// Note that the intermediate result is quantized to the texel of the image.
+    ///
+    /// For opaque images with a linear transfer function the results match those of the `image`
+    /// crate's `imageops::resize` with the same filter to within 3 levels of 8-bit samples. The
+    /// larger part of that deviation is rounding of the intermediate result and overshoot that is
+    /// clamped after the first pass. For images with an electrical transfer, such as sRGB, the
+    /// results differ systematically as `image` filters the encoded values instead.
+    pub fn resize(
+        &mut self,
+        src: Register,
+        width: u32,
+        height: u32,
+        filter: ResizeFilter,
+    ) -> Result<Register, CommandError> {
+        let source = self.describe_reg(src)?.clone();
+
+        if !can_interpolate(&source.texel) {
+            return Err(CommandError {
+                inner: CommandErrorKind::BadDescriptor(source),
+            });
+        }
+
+        if width == 0 || height == 0 {
+            return Err(CommandError::OTHER);
+        }
+
+        // The intermediate result of the first pass must be representable as well.
+        let (src_width, _) = source.size();
+        Descriptor::with_texel(source.texel.clone(), src_width, height)
+            .ok_or(CommandError::OTHER)?;
+        let desc =
+            Descriptor::with_texel(source.texel, width, height).ok_or(CommandError::OTHER)?;
+
+        let op = Op::Unary {
+            src,
+            op: UnaryOp::Resize(filter),
+            desc,
+        };
+
+        Ok(self.push(op))
+    }
+
+    /// Mirror an image along its width, such that left and right are swapped.
+    pub fn flip_horizontal(&mut self, src: Register) -> Result<Register, CommandError> {
+        self.orient(src, false, true, false)
+    }
+
+    /// Mirror an image along its height, such that top and bottom are swapped.
+    pub fn flip_vertical(&mut self, src: Register) -> Result<Register, CommandError> {
+        self.orient(src, false, false, true)
+    }
+
+    /// Mirror an image along its main diagonal, swapping rows and columns.
+    ///
+    /// The output has the width and height of the input swapped.
+    pub fn transpose(&mut self, src: Register) -> Result<Register, CommandError> {
+        self.orient(src, true, false, false)
+    }
+
+    /// Rotate an image by 90° clockwise.
+    ///
+    /// The output has the width and height of the input swapped.
+    pub fn rotate90(&mut self, src: Register) -> Result<Register, CommandError> {
+        self.orient(src, true, false, true)
+    }
+
+    /// Rotate an image by 180°.
+    pub fn rotate180(&mut self, src: Register) -> Result<Register, CommandError> {
+        self.orient(src, false, true, true)
+    }
+
+    /// Rotate an image by 270° clockwise, that is 90° counter-clockwise.
+    ///
+    /// The output has the width and height of the input swapped.
+    pub fn rotate270(&mut self, src: Register) -> Result<Register, CommandError> {
+        self.orient(src, true, true, false)
+    }
+
+    /// Reorient the pixel grid of an image.
+    ///
+    /// Every pixel is copied from exactly one pixel of the input, addressed by integer indices,
+    /// which makes the result bit-exact on all backends. This is unlike `affine`, where the
+    /// sampled positions are subject to floating point rounding.
+    fn orient(
+        &mut self,
+        src: Register,
+        transpose: bool,
+        flip_width: bool,
+        flip_height: bool,
+    ) -> Result<Register, CommandError> {
+        let desc = self.describe_reg(src)?.clone();
+
+        let desc = if transpose {
+            let (width, height) = desc.size();
+            Descriptor::with_texel(desc.texel, height, width).ok_or(CommandError::OTHER)?
+        } else {
+            desc
+        };
+
+        let op = Op::Unary {
+            src,
+            op: UnaryOp::Orient(shaders::orient::Shader {
+                transpose,
+                flip_width,
+                flip_height,
+            }),
+            desc,
+        };
+
+        Ok(self.push(op))
+    }
+
+    /// Overlay this image as part of a larger one, performing blending.
+    ///
+    /// The typing is the same as for [`Self::inscribe`]. The blend modes other than the
+    /// Porter-Duff operators additionally require an RGB color model.
+    pub fn blend(
+        &mut self,
+        below: Register,
+        rect: Rectangle,
+        above: Register,
+        blend: Blend,
+    ) -> Result<Register, CommandError> {
+        let desc = self.check_placement(below, rect, above)?;
+
+        if !blend.is_porter_duff() && !matches!(desc.texel.color, Color::Rgb { .. }) {
+            return Err(CommandError {
+                inner: CommandErrorKind::BadDescriptor(desc),
+            });
+        }
+
+        // Some modes are defined on the encoded values, mix those by reinterpreting them as linear.
+        let encoded = match desc.texel.color {
+            Color::Rgb {
+                luminance,
+                primary,
+                whitepoint,
+                transfer: _,
+            } if blend.is_encoded() => Texel {
+                color: Color::Rgb {
+                    luminance,
+                    primary,
+                    whitepoint,
+                    transfer: buffer::Transfer::Linear,
+                },
+                ..desc.texel.clone()
+            },
+            _ => desc.texel.clone(),
+        };
+
+        if encoded == desc.texel {
+            let op = Op::Binary {
+                lhs: below,
+                rhs: above,
+                op: BinaryOp::Blend {
+                    placement: rect,
+                    blend,
+                },
+                desc,
+            };
+
+            return Ok(self.push(op));
+        }
+
+        let below = self.transmute(below, encoded.clone())?;
+        let above = self.transmute(above, encoded)?;
+        let blended = self.blend(below, rect, above, blend)?;
+        self.transmute(blended, desc.texel)
+    }
+
+    /// A solid color image, from a descriptor and a single texel.
+    ///
+    /// The texel is interpreted exactly like the texels of an input image with the same
+    /// descriptor. Texels wider than 32 bits are not yet supported.
+    pub fn solid(&mut self, describe: Descriptor, data: &[u8]) -> Result<Register, CommandError> {
+        if !describe.is_consistent() {
+            return Err(CommandError {
+                inner: CommandErrorKind::BadDescriptor(describe),
+            });
+        }
+
+        if data.len() != usize::from(describe.layout.bytes_per_texel) {
+            return Err(CommandError {
+                inner: CommandErrorKind::BadDescriptor(describe),
+            });
+        }
+
+        // Decode the texel exactly like the staging shader would, so that the color we paint is
+        // what an input image with this texel would have in its linear representation.
+        let transfer = match describe.texel.color {
+            Color::Rgb { transfer, .. } | Color::Scalars { transfer } => transfer.into(),
+            Color::Oklab => shaders::stage::Transfer::Oklab,
+        };
+
+        let parameter = shaders::stage::XyzParameter {
+            bits: describe.texel.samples.bits,
+            parts: describe.texel.samples.parts,
+            transfer,
+        };
+
+        let color = match parameter.decode_texel(data) {
+            Some(color) => color,
+            None => return Err(CommandError::UNIMPLEMENTED),
+        };
+
+        Ok(self.push(Op::Construct {
+            desc: describe,
+            op: ConstructOp::Solid(color),
+        }))
+    }
+
+    /// A 2d image with a normal distribution.
+    ///
+    /// The parameters are controlled through the `distribution` parameter while the `texel`
+    /// parameter controls the eventual binary encoding of the image. It must be compatible with a
+    /// single gray channel (but you can have electrical transfer functions, choose arbitrary bit
+    /// widths etc.).
+    pub fn distribution_normal2d(
+        &mut self,
+        describe: Descriptor,
+        distribution: shaders::DistributionNormal2d,
+    ) -> Result<Register, CommandError> {
+        if !describe.is_consistent() {
+            return Err(CommandError {
+                inner: CommandErrorKind::BadDescriptor(describe),
+            });
+        }
+
+        if describe.texel.samples.parts != SampleParts::Luma
+            && describe.texel.samples.parts != SampleParts::LumaA
+        {
+            return Err(CommandError {
+                inner: CommandErrorKind::BadDescriptor(describe),
+            });
+        }
+
+        Ok(self.push(Op::Construct {
+            desc: describe,
+            op: ConstructOp::DistributionNormal(distribution),
+        }))
+    }
 
     /// Evaluate a bilinear function over a 2d image.
     ///
@@ -961,6 +2331,125 @@ impl CommandBuffer {
         }))
     }
 
+    /// Generate coherent noise, such as Perlin noise, over a 2d image.
+    ///
+    /// The noise depends only on its parameters and the pixel coordinates, the result is
+    /// identical for the same seed. Like [`Self::distribution_normal2d`] the `describe` parameter
+    /// must be compatible with a single gray channel, or have scalar color in which case all
+    /// components but alpha hold the noise.
+    pub fn noise(&mut self, describe: Descriptor, noise: Noise) -> Result<Register, CommandError> {
+        if !describe.is_consistent() {
+            return Err(CommandError {
+                inner: CommandErrorKind::BadDescriptor(describe),
+            });
+        }
+
+        if !can_hold_noise(&describe.texel) {
+            return Err(CommandError {
+                inner: CommandErrorKind::BadDescriptor(describe),
+            });
+        }
+
+        if !noise.is_valid() {
+            return Err(CommandError::OTHER);
+        }
+
+        Ok(self.push(Op::Construct {
+            desc: describe,
+            op: ConstructOp::Noise(noise),
+        }))
+    }
+
+    /// Generate white noise, independent random values for each pixel.
+    ///
+    /// The values are derived from a counter-based hash of the seed and the pixel coordinate
+    /// alone, they do not depend on the image size or any other state. The `describe` parameter
+    /// has the same requirements as for [`Self::noise`], for scalars each component but alpha is
+    /// independent of the others. The distribution is that of the stored values, the transfer
+    /// function of the texel is ignored.
+    pub fn white_noise(
+        &mut self,
+        describe: Descriptor,
+        distribution: WhiteNoise,
+        seed: u32,
+    ) -> Result<Register, CommandError> {
+        if !describe.is_consistent() || !can_hold_noise(&describe.texel) {
+            return Err(CommandError {
+                inner: CommandErrorKind::BadDescriptor(describe),
+            });
+        }
+
+        if let WhiteNoise::Gaussian { mean, std_dev } = distribution {
+            if !(mean.is_finite() && std_dev.is_finite() && std_dev >= 0.0) {
+                return Err(CommandError::OTHER);
+            }
+        }
+
+        self.construct_encoded(describe, ConstructOp::WhiteNoise { distribution, seed })
+    }
+
+    /// Generate a blue noise mask, for example as a threshold for dithering.
+    ///
+    /// A mask of 64×64 pixels is ranked with the void-and-cluster method, starting from a random
+    /// pattern chosen by the seed, and repeated over the image. Pixels with close ranks are far
+    /// apart from each other such that any threshold leaves evenly spread pixels without clumps.
+    /// The stored values are uniformly distributed in `(0, 1)`, the transfer function of the
+    /// texel is ignored. The `describe` parameter has the same requirements as for
+    /// [`Self::noise`].
+    pub fn blue_noise(
+        &mut self,
+        describe: Descriptor,
+        seed: u32,
+    ) -> Result<Register, CommandError> {
+        if !describe.is_consistent() || !can_hold_noise(&describe.texel) {
+            return Err(CommandError {
+                inner: CommandErrorKind::BadDescriptor(describe),
+            });
+        }
+
+        self.construct_encoded(describe, ConstructOp::BlueNoise { seed })
+    }
+
+    /// Construct the stored values of an image, by reinterpreting them as linear ones.
+    fn construct_encoded(
+        &mut self,
+        describe: Descriptor,
+        op: ConstructOp,
+    ) -> Result<Register, CommandError> {
+        let color = match describe.texel.color {
+            Color::Rgb {
+                luminance,
+                primary,
+                whitepoint,
+                transfer: _,
+            } => Color::Rgb {
+                luminance,
+                primary,
+                whitepoint,
+                transfer: buffer::Transfer::Linear,
+            },
+            Color::Scalars { transfer: _ } => Color::Scalars {
+                transfer: buffer::Transfer::Linear,
+            },
+            Color::Oklab => Color::Oklab,
+        };
+
+        if color == describe.texel.color {
+            return Ok(self.push(Op::Construct { desc: describe, op }));
+        }
+
+        let encoded = Descriptor {
+            texel: Texel {
+                color,
+                ..describe.texel.clone()
+            },
+            layout: describe.layout.clone(),
+        };
+
+        let values = self.push(Op::Construct { desc: encoded, op });
+        self.transmute(values, describe.texel)
+    }
+
     /// Overlay an affine transformation of the image.
     pub fn affine(
         &mut self,
@@ -980,13 +2469,9 @@ impl CommandBuffer {
             return Err(CommandError::OTHER);
         }
 
-        match affine.sampling {
-            AffineSample::Nearest => (),
-            AffineSample::BiLinear => {
-                // "Check for a color which we can sample bi-linearly"
-                return Err(CommandError::UNIMPLEMENTED);
-            }
-        }
+        self.check_sparse_sampling(affine.sampling, affine.min_scale())?;
+
+        affine.sampling.check_color(&lhs)?;
 
         Ok(self.push(Op::Binary {
             lhs: below,
@@ -996,6 +2481,168 @@ impl CommandBuffer {
         }))
     }
 
+    /// Overlay a projective transformation of the image.
+    ///
+    /// The typing follows `affine`. Additionally, the transformed image must lie entirely in
+    /// front of the horizon of the projection, that is the homogeneous weight of all its corners
+    /// must have the same sign. Texture coordinates are interpolated perspective-correct.
+    pub fn perspective(
+        &mut self,
+        below: Register,
+        perspective: Perspective,
+        above: Register,
+    ) -> Result<Register, CommandError> {
+        let lhs = self.describe_reg(below)?.clone();
+        let rhs = self.describe_reg(above)?.clone();
+
+        if lhs.texel != rhs.texel {
+            return Err(CommandError::TYPE_ERR);
+        }
+
+        let matrix = RowMatrix::new(perspective.transformation);
+        if !(matrix.det().abs() >= f32::EPSILON) {
+            return Err(CommandError::OTHER);
+        }
+
+        let upper_region = Rectangle::from(&rhs);
+        if QuadTarget::from(upper_region).projective(&matrix).is_none() {
+            return Err(CommandError::OTHER);
+        }
+
+        self.check_sparse_sampling(perspective.sampling, perspective.min_scale(upper_region))?;
+
+        perspective.sampling.check_color(&lhs)?;
+
+        Ok(self.push(Op::Binary {
+            lhs: below,
+            rhs: above,
+            op: BinaryOp::Perspective(perspective),
+            desc: lhs,
+        }))
+    }
+
+    /// Warp an image by sampling it at positions offset by a displacement image.
+    ///
+    /// The displacement must be a `Scalars` image of the same size with red and green channels.
+    /// For each pixel, these channels of its linear representation are the offset `(dx, dy)`
+    /// along width and height, which are transformed to pixels by the parameters of `warp`. The
+    /// output has the descriptor of `src` and each pixel is sampled from `src` at its own
+    /// position shifted by the offset.
+    ///
+    /// Neighboring offsets may differ arbitrarily, such that a warp can shrink an image by any
+    /// factor. This depends on the displacement values which are not known when recording. A warp
+    /// with a non-zero `scale` is thus always refused after
+    /// [`refuse_sparse_sampling`](Self::refuse_sparse_sampling).
+    pub fn warp(
+        &mut self,
+        src: Register,
+        displacement: Register,
+        warp: Warp,
+    ) -> Result<Register, CommandError> {
+        let desc = self.describe_reg(src)?.clone();
+        let desc_displacement = self.describe_reg(displacement)?;
+
+        let is_displacement = matches!(desc_displacement.texel.color, Color::Scalars { .. })
+            && matches!(
+                desc_displacement.texel.samples.parts,
+                SampleParts::Rgb
+                    | SampleParts::Bgr
+                    | SampleParts::Rgba
+                    | SampleParts::Bgra
+                    | SampleParts::Rgb_
+                    | SampleParts::Bgr_
+                    | SampleParts::Argb
+                    | SampleParts::_Rgb
+                    | SampleParts::Abgr
+                    | SampleParts::_Bgr
+            );
+
+        if !is_displacement {
+            return Err(CommandError {
+                inner: CommandErrorKind::BadDescriptor(desc_displacement.clone()),
+            });
+        }
+
+        if desc.size() != desc_displacement.size() {
+            return Err(CommandError {
+                inner: CommandErrorKind::ConflictingTypes(desc, desc_displacement.clone()),
+            });
+        }
+
+        if !(warp.scale.is_finite() && warp.neutral.is_finite()) {
+            return Err(CommandError::OTHER);
+        }
+
+        if self.sparse_sampling_limit.is_some() && warp.scale != 0.0 {
+            return Err(CommandError::OTHER);
+        }
+
+        warp.sampling.check_color(&desc)?;
+
+        Ok(self.push(Op::Binary {
+            lhs: src,
+            rhs: displacement,
+            op: BinaryOp::Warp(warp),
+            desc,
+        }))
+    }
+
+    /// Compare two images with the structural similarity index (SSIM).
+    ///
+    /// The images must have the same size and color. They are compared by their luma, see
+    /// [`gradient`](Self::gradient), with statistics over a Gaussian window with a standard
+    /// deviation of `1.5` pixels and a radius of `5` pixels. Pixels outside the image are treated
+    /// as repetitions of the closest edge pixel.
+    ///
+    /// The output is a `Scalars` image of the same size with a single 16-bit luma channel, the
+    /// similarity at each pixel. It is `1.0` for equal images and decreases with differences.
+    /// Negative similarities, of anti-correlated structure, are clamped to `0.0`. Use
+    /// [`Ssim::mean`] on the retired map for the usual single score.
+    ///
+    /// The multi-scale variant requires both dimensions to be at least 16 pixels, such that the
+    /// coarsest scale is not empty.
+    pub fn ssim(
+        &mut self,
+        lhs: Register,
+        rhs: Register,
+        ssim: Ssim,
+    ) -> Result<Register, CommandError> {
+        let desc_lhs = self.describe_reg(lhs)?;
+        let desc_rhs = self.describe_reg(rhs)?;
+
+        if desc_lhs.size() != desc_rhs.size() || desc_lhs.texel.color != desc_rhs.texel.color {
+            return Err(CommandError {
+                inner: CommandErrorKind::ConflictingTypes(desc_lhs.clone(), desc_rhs.clone()),
+            });
+        }
+
+        let (width, height) = desc_lhs.size();
+        let smallest = 1 << (shaders::ssim::SCALES - 1);
+        if ssim == Ssim::MultiScale && (width < smallest || height < smallest) {
+            return Err(CommandError::OTHER);
+        }
+
+        let texel = Texel {
+            block: buffer::Block::Pixel,
+            samples: buffer::Samples {
+                bits: buffer::SampleBits::Int16,
+                parts: SampleParts::Luma,
+            },
+            color: Color::Scalars {
+                transfer: buffer::Transfer::Linear,
+            },
+        };
+
+        let desc = Descriptor::with_texel(texel, width, height).ok_or(CommandError::OTHER)?;
+
+        Ok(self.push(Op::Binary {
+            lhs,
+            rhs,
+            op: BinaryOp::Ssim(ssim),
+            desc,
+        }))
+    }
+
     /// Declare an output.
     ///
     /// Outputs MUST later be bound from the pool during launch.
@@ -1044,6 +2691,8 @@ impl CommandBuffer {
 
         let mut textures = ImageBufferPlan::default();
         let mut reg_to_texture: HashMap<Register, Texture> = HashMap::default();
+        // Ranking a blue noise mask is slow, constructions with the same seed share it.
+        let mut blue_noise_masks: HashMap<u32, shaders::blue_noise::Shader> = HashMap::default();
 
         for (idx, op) in self.ops.iter().enumerate() {
             let liveness = first_use[idx]..last_use[idx];
@@ -1085,7 +2734,67 @@ impl CommandBuffer {
                                 shader: FragmentShader::Bilinear(bilinear.clone()),
                             },
                         }),
-                        _ => return Err(CompileError::NotYetImplemented),
+                        &ConstructOp::Solid(color) => high_ops.push(High::Construct {
+                            dst: Target::Discard(texture),
+                            fn_: Function::PaintFullScreen {
+                                shader: FragmentShader::Fill(shaders::fill::Shader { color }),
+                            },
+                        }),
+                        &ConstructOp::Noise(noise) => high_ops.push(High::Construct {
+                            dst: Target::Discard(texture),
+                            fn_: Function::PaintFullScreen {
+                                shader: FragmentShader::Noise(shaders::noise::Shader {
+                                    kind: match noise.kind {
+                                        NoiseKind::Value => shaders::noise::Kind::Value,
+                                        NoiseKind::Perlin => shaders::noise::Kind::Perlin,
+                                        NoiseKind::Simplex => shaders::noise::Kind::Simplex,
+                                        NoiseKind::Worley => shaders::noise::Kind::Worley,
+                                    },
+                                    seed: noise.seed,
+                                    frequency: noise.frequency,
+                                    octaves: noise.octaves,
+                                    lacunarity: noise.lacunarity,
+                                    gain: noise.gain,
+                                }),
+                            },
+                        }),
+                        &ConstructOp::WhiteNoise { distribution, seed } => {
+                            let (distribution, gaussian) = match distribution {
+                                WhiteNoise::Uniform => {
+                                    (shaders::white_noise::Distribution::Uniform, [0.0; 2])
+                                }
+                                WhiteNoise::Gaussian { mean, std_dev } => (
+                                    shaders::white_noise::Distribution::Gaussian,
+                                    [mean, std_dev],
+                                ),
+                            };
+
+                            high_ops.push(High::Construct {
+                                dst: Target::Discard(texture),
+                                fn_: Function::PaintFullScreen {
+                                    shader: FragmentShader::WhiteNoise(
+                                        shaders::white_noise::Shader {
+                                            distribution,
+                                            seed,
+                                            gaussian,
+                                        },
+                                    ),
+                                },
+                            })
+                        }
+                        &ConstructOp::BlueNoise { seed } => high_ops.push(High::Construct {
+                            dst: Target::Discard(texture),
+                            fn_: Function::PaintFullScreen {
+                                shader: FragmentShader::BlueNoise(
+                                    blue_noise_masks
+                                        .entry(seed)
+                                        .or_insert_with(|| {
+                                            shaders::blue_noise::Shader::with_seed(seed)
+                                        })
+                                        .clone(),
+                                ),
+                            },
+                        }),
                     }
 
                     reg_to_texture.insert(Register(idx), texture);
@@ -1178,6 +2887,281 @@ impl CommandBuffer {
                                 fn_: Function::PaintFullScreen { shader },
                             })
                         }
+                        UnaryOp::Smooth(smoothing)
+                            if smoothing.method == SmoothingMethod::Median =>
+                        {
+                            let shader = shaders::median::Shader {
+                                luma: descriptor.texel.color.luma_weights(),
+                                radius: smoothing.radius,
+                            };
+
+                            high_ops.push(High::PushOperand(reg_to_texture[src]));
+                            high_ops.push(High::Construct {
+                                dst: Target::Discard(texture),
+                                fn_: Function::PaintFullScreen {
+                                    shader: FragmentShader::Median(shader),
+                                },
+                            });
+                        }
+                        UnaryOp::Smooth(smoothing)
+                            if smoothing.method == SmoothingMethod::Kuwahara =>
+                        {
+                            let shader = shaders::kuwahara::Shader {
+                                luma: descriptor.texel.color.luma_weights(),
+                                radius: smoothing.radius,
+                            };
+
+                            high_ops.push(High::PushOperand(reg_to_texture[src]));
+                            high_ops.push(High::Construct {
+                                dst: Target::Discard(texture),
+                                fn_: Function::PaintFullScreen {
+                                    shader: FragmentShader::Kuwahara(shader),
+                                },
+                            });
+                        }
+                        UnaryOp::Smooth(smoothing)
+                            if smoothing.method == SmoothingMethod::Bilteral =>
+                        {
+                            let shader = smoothing.bilateral_shader(&descriptor.texel.color);
+
+                            high_ops.push(High::PushOperand(reg_to_texture[src]));
+                            high_ops.push(High::Construct {
+                                dst: Target::Discard(texture),
+                                fn_: Function::PaintFullScreen {
+                                    shader: FragmentShader::Bilateral(shader),
+                                },
+                            });
+                        }
+                        UnaryOp::Smooth(smoothing) => {
+                            let passes = smoothing.separable_passes()?;
+                            push_passes(
+                                &mut high_ops,
+                                &mut textures,
+                                descriptor,
+                                reg_to_texture[src],
+                                texture,
+                                passes,
+                            );
+                        }
+                        UnaryOp::Convolve(kernel) => {
+                            let passes = kernel.passes().ok_or(CompileError::NotYetImplemented)?;
+                            push_passes(
+                                &mut high_ops,
+                                &mut textures,
+                                descriptor,
+                                reg_to_texture[src],
+                                texture,
+                                passes,
+                            );
+                        }
+                        &UnaryOp::Resize(filter) => {
+                            let source = self.describe_reg(*src).unwrap();
+                            let (src_width, src_height) = source.size();
+                            let (width, height) = descriptor.size();
+                            let intermediate =
+                                Descriptor::with_texel(descriptor.texel.clone(), src_width, height)
+                                    .ok_or(CompileError::NotYetImplemented)?;
+
+                            let passes = vec![
+                                filter.shader(src_height as f32 / height as f32, true),
+                                filter.shader(src_width as f32 / width as f32, false),
+                            ];
+
+                            push_passes(
+                                &mut high_ops,
+                                &mut textures,
+                                &intermediate,
+                                reg_to_texture[src],
+                                texture,
+                                passes,
+                            );
+                        }
+                        UnaryOp::Extend(shader) => {
+                            high_ops.push(High::PushOperand(reg_to_texture[src]));
+                            high_ops.push(High::Construct {
+                                dst: Target::Discard(texture),
+                                fn_: Function::PaintFullScreen {
+                                    shader: FragmentShader::Extend(shader.clone()),
+                                },
+                            });
+                        }
+                        UnaryOp::Gradient(shader) => {
+                            high_ops.push(High::PushOperand(reg_to_texture[src]));
+                            high_ops.push(High::Construct {
+                                dst: Target::Discard(texture),
+                                fn_: Function::PaintFullScreen {
+                                    shader: FragmentShader::Gradient(shader.clone()),
+                                },
+                            });
+                        }
+                        &UnaryOp::Canny { low, high } => {
+                            use shaders::canny::{self, Pass};
+                            let pass =
+                                |pass| FragmentShader::Canny(canny::Shader { pass, low, high });
+
+                            let mut passes = vec![pass(Pass::Suppress)];
+                            for _ in 0..canny::HYSTERESIS_PASSES {
+                                passes.push(pass(Pass::Propagate));
+                            }
+                            passes.push(pass(Pass::Finish));
+
+                            push_passes(
+                                &mut high_ops,
+                                &mut textures,
+                                descriptor,
+                                reg_to_texture[src],
+                                texture,
+                                passes,
+                            );
+                        }
+                        UnaryOp::PerceptualHash(shader) => {
+                            high_ops.push(High::PushOperand(reg_to_texture[src]));
+                            high_ops.push(High::Construct {
+                                dst: Target::Discard(texture),
+                                fn_: Function::PaintFullScreen {
+                                    shader: FragmentShader::Hash(shader.clone()),
+                                },
+                            });
+                        }
+                        UnaryOp::Histogram(shader) => {
+                            use shaders::histogram::Pass;
+                            let source = self.describe_reg(*src).unwrap();
+                            let rows = Descriptor::with_texel(
+                                COUNT_TEXEL,
+                                shader.bins,
+                                source.pixel_height(),
+                            )
+                            .ok_or(CompileError::NotYetImplemented)?;
+
+                            let pass = |pass| {
+                                FragmentShader::Histogram(shaders::histogram::Shader {
+                                    pass,
+                                    ..shader.clone()
+                                })
+                            };
+
+                            push_passes(
+                                &mut high_ops,
+                                &mut textures,
+                                &rows,
+                                reg_to_texture[src],
+                                texture,
+                                vec![pass(Pass::Rows), pass(Pass::Sum)],
+                            );
+                        }
+                        UnaryOp::Statistics(shader) => {
+                            use shaders::statistics::Pass;
+                            let pass = |pass, level| {
+                                FragmentShader::Statistics(shaders::statistics::Shader {
+                                    pass,
+                                    level,
+                                    ..shader.clone()
+                                })
+                            };
+
+                            // Reduce each row, then merge pairs of rows until at most two are
+                            // left. Each pass has its own number of rows.
+                            let mut passes = vec![(pass(Pass::Rows, 0), shader.height)];
+                            let (mut rows, mut level) = (shader.height, 0);
+                            while rows > 2 {
+                                rows = (rows + 1) / 2;
+                                passes.push((pass(Pass::Merge, level), rows));
+                                level += 1;
+                            }
+
+                            let mut operand = reg_to_texture[src];
+                            for (partial_pass, rows) in passes {
+                                let partial = Descriptor::with_texel(COUNT_TEXEL, 16, rows)
+                                    .ok_or(CompileError::NotYetImplemented)?;
+                                let target = textures.allocate_intermediate(&partial);
+
+                                high_ops.push(High::PushOperand(operand));
+                                high_ops.push(High::Construct {
+                                    dst: Target::Discard(target),
+                                    fn_: Function::PaintFullScreen {
+                                        shader: partial_pass,
+                                    },
+                                });
+
+                                operand = target;
+                            }
+
+                            high_ops.push(High::PushOperand(operand));
+                            high_ops.push(High::Construct {
+                                dst: Target::Discard(texture),
+                                fn_: Function::PaintFullScreen {
+                                    shader: pass(Pass::Finish, level),
+                                },
+                            });
+                        }
+                        UnaryOp::Equalize(shader) => {
+                            use shaders::equalize::{Pass, BINS};
+                            let pass = |pass| {
+                                FragmentShader::Equalize(shaders::equalize::Shader {
+                                    pass,
+                                    ..shader.clone()
+                                })
+                            };
+
+                            // The bins of all tiles along the width are side by side.
+                            let [tiles_x, tiles_y] = shader.tiles;
+                            let (_, height) = descriptor.size();
+                            let mapping = Texel {
+                                samples: buffer::Samples {
+                                    bits: buffer::SampleBits::Int16,
+                                    parts: SampleParts::Luma,
+                                },
+                                ..COUNT_TEXEL
+                            };
+
+                            let rows = Descriptor::with_texel(COUNT_TEXEL, BINS * tiles_x, height)
+                                .ok_or(CompileError::NotYetImplemented)?;
+                            let counts =
+                                Descriptor::with_texel(COUNT_TEXEL, BINS * tiles_x, tiles_y)
+                                    .ok_or(CompileError::NotYetImplemented)?;
+                            let mapping = Descriptor::with_texel(mapping, BINS * tiles_x, tiles_y)
+                                .ok_or(CompileError::NotYetImplemented)?;
+
+                            let rows = textures.allocate_intermediate(&rows);
+                            let counts = textures.allocate_intermediate(&counts);
+                            let mapping = textures.allocate_intermediate(&mapping);
+
+                            let steps = [
+                                (vec![reg_to_texture[src]], rows, Pass::Rows),
+                                (vec![rows], counts, Pass::Tiles),
+                                (vec![counts], mapping, Pass::Map),
+                                (vec![reg_to_texture[src], mapping], texture, Pass::Apply),
+                            ];
+
+                            for (operands, target, step) in steps.iter().cloned() {
+                                for operand in operands {
+                                    high_ops.push(High::PushOperand(operand));
+                                }
+
+                                high_ops.push(High::Construct {
+                                    dst: Target::Discard(target),
+                                    fn_: Function::PaintFullScreen { shader: pass(step) },
+                                });
+                            }
+                        }
+                        UnaryOp::Hough(shader) => {
+                            high_ops.push(High::PushOperand(reg_to_texture[src]));
+                            high_ops.push(High::Construct {
+                                dst: Target::Discard(texture),
+                                fn_: Function::PaintFullScreen {
+                                    shader: FragmentShader::Hough(shader.clone()),
+                                },
+                            });
+                        }
+                        UnaryOp::Orient(shader) => {
+                            high_ops.push(High::PushOperand(reg_to_texture[src]));
+                            high_ops.push(High::Construct {
+                                dst: Target::Discard(texture),
+                                fn_: Function::PaintFullScreen {
+                                    shader: FragmentShader::Orient(shader.clone()),
+                                },
+                            });
+                        }
                         UnaryOp::Transmute => high_ops.push(High::Copy {
                             src: *src,
                             dst: Register(idx),
@@ -1217,14 +3201,169 @@ impl CommandBuffer {
                                 fn_: Function::PaintToSelection {
                                     texture: reg_to_texture[rhs],
                                     selection: upper_region,
-                                    target: QuadTarget::from(upper_region).affine(&affine_matrix),
+                                    target: QuadTarget::from(upper_region).affine(&affine_matrix),
+                                    viewport: lower_region,
+                                    shader: FragmentShader::PaintOnTop(
+                                        affine.sampling.as_paint_on_top()?,
+                                    ),
+                                },
+                            })
+                        }
+                        BinaryOp::Perspective(perspective) => {
+                            let matrix = RowMatrix::new(perspective.transformation);
+                            let target = QuadTarget::from(upper_region)
+                                .projective(&matrix)
+                                .ok_or(CompileError::NotYetImplemented)?;
+
+                            high_ops.push(High::PushOperand(reg_to_texture[lhs]));
+                            high_ops.push(High::Construct {
+                                dst: Target::Discard(texture),
+                                fn_: Function::PaintToSelection {
+                                    texture: reg_to_texture[lhs],
+                                    selection: lower_region,
+                                    target: lower_region.into(),
+                                    viewport: lower_region,
+                                    shader: FragmentShader::PaintOnTop(PaintOnTopKind::Copy),
+                                },
+                            });
+
+                            high_ops.push(High::PushOperand(reg_to_texture[rhs]));
+                            high_ops.push(High::Construct {
+                                dst: Target::Load(texture),
+                                fn_: Function::PaintToSelection {
+                                    texture: reg_to_texture[rhs],
+                                    selection: upper_region,
+                                    target,
                                     viewport: lower_region,
                                     shader: FragmentShader::PaintOnTop(
-                                        affine.sampling.as_paint_on_top()?,
+                                        perspective.sampling.as_paint_on_top()?,
                                     ),
                                 },
                             })
                         }
+                        BinaryOp::Warp(warp) => {
+                            let (border, border_color) = warp.border.shader_parameters();
+                            let shader = shaders::warp::Shader {
+                                scale: warp.scale,
+                                neutral: warp.neutral,
+                                border,
+                                border_color,
+                                bilinear: matches!(warp.sampling, AffineSample::BiLinear),
+                            };
+
+                            high_ops.push(High::PushOperand(reg_to_texture[lhs]));
+                            high_ops.push(High::PushOperand(reg_to_texture[rhs]));
+                            high_ops.push(High::Construct {
+                                dst: Target::Discard(texture),
+                                fn_: Function::PaintFullScreen {
+                                    shader: FragmentShader::Warp(shader),
+                                },
+                            })
+                        }
+                        BinaryOp::Ssim(ssim) => {
+                            let luma =
+                                |reg| self.describe_reg(reg).unwrap().texel.color.luma_weights();
+                            let shader = shaders::ssim::Shader {
+                                luma_lhs: luma(*lhs),
+                                luma_rhs: luma(*rhs),
+                            };
+
+                            let scales = match ssim {
+                                Ssim::Single => 1,
+                                Ssim::MultiScale => shaders::ssim::SCALES,
+                            };
+
+                            // Scales are compared in a linear representation of their own.
+                            // The components are not colors, they need the precision of floats
+                            // and contrast-structure may be negative.
+                            let texel = Texel {
+                                block: buffer::Block::Pixel,
+                                samples: buffer::Samples {
+                                    bits: buffer::SampleBits::Float16x4,
+                                    parts: SampleParts::Rgba,
+                                },
+                                color: Color::Scalars {
+                                    transfer: buffer::Transfer::Linear,
+                                },
+                            };
+
+                            let (mut width, mut height) = descriptor.size();
+                            let mut operands = (reg_to_texture[lhs], reg_to_texture[rhs]);
+                            let mut components = vec![];
+
+                            for scale in 0..scales {
+                                if scale > 0 {
+                                    // Halve the size of both images with an area filter.
+                                    let (src_width, src_height) = (width, height);
+                                    width = (width + 1) / 2;
+                                    height = (height + 1) / 2;
+
+                                    let scaled =
+                                        Descriptor::with_texel(texel.clone(), width, height)
+                                            .ok_or(CompileError::NotYetImplemented)?;
+                                    let intermediate =
+                                        Descriptor::with_texel(texel.clone(), src_width, height)
+                                            .ok_or(CompileError::NotYetImplemented)?;
+
+                                    let mut halve = |src| {
+                                        let dst = textures.allocate_intermediate(&scaled);
+                                        let passes = vec![
+                                            ResizeFilter::Area
+                                                .shader(src_height as f32 / height as f32, true),
+                                            ResizeFilter::Area
+                                                .shader(src_width as f32 / width as f32, false),
+                                        ];
+
+                                        push_passes(
+                                            &mut high_ops,
+                                            &mut textures,
+                                            &intermediate,
+                                            src,
+                                            dst,
+                                            passes,
+                                        );
+
+                                        dst
+                                    };
+
+                                    operands = (halve(operands.0), halve(operands.1));
+                                }
+
+                                let target = if scales == 1 {
+                                    texture
+                                } else {
+                                    let map = Descriptor::with_texel(texel.clone(), width, height)
+                                        .ok_or(CompileError::NotYetImplemented)?;
+                                    textures.allocate_intermediate(&map)
+                                };
+
+                                high_ops.push(High::PushOperand(operands.0));
+                                high_ops.push(High::PushOperand(operands.1));
+                                high_ops.push(High::Construct {
+                                    dst: Target::Discard(target),
+                                    fn_: Function::PaintFullScreen {
+                                        shader: FragmentShader::Ssim(shader.clone()),
+                                    },
+                                });
+
+                                components.push(target);
+                            }
+
+                            if scales > 1 {
+                                for &map in &components {
+                                    high_ops.push(High::PushOperand(map));
+                                }
+
+                                high_ops.push(High::Construct {
+                                    dst: Target::Discard(texture),
+                                    fn_: Function::PaintFullScreen {
+                                        shader: FragmentShader::MultiScaleSsim(
+                                            shaders::ssim::MultiScale,
+                                        ),
+                                    },
+                                });
+                            }
+                        }
                         BinaryOp::Inject {
                             channel,
                             from_channels,
@@ -1278,180 +3417,808 @@ impl CommandBuffer {
                                 },
                             });
                         }
+                        BinaryOp::Blend { placement, blend } => {
+                            // Blending reads both images at every pixel of the lower one. Outside
+                            // the placement the upper image is transparent, which is still
+                            // relevant for operators such as `In` that clear the destination.
+                            let (width, height) =
+                                (lower_region.width() as f32, lower_region.height() as f32);
+
+                            let placement = [
+                                placement.x as f32 / width,
+                                placement.y as f32 / height,
+                                placement.max_x as f32 / width,
+                                placement.max_y as f32 / height,
+                            ];
+
+                            high_ops.push(High::PushOperand(reg_to_texture[lhs]));
+                            high_ops.push(High::PushOperand(reg_to_texture[rhs]));
+
+                            high_ops.push(High::Construct {
+                                dst: Target::Discard(texture),
+                                fn_: Function::PaintFullScreen {
+                                    shader: blend.into_shader(placement),
+                                },
+                            });
+                        }
                     }
 
                     reg_to_texture.insert(Register(idx), texture);
                 }
             }
 
-            high_ops.push(High::Done(Register(idx)));
-        }
+            high_ops.push(High::Done(Register(idx)));
+        }
+
+        Ok(Program {
+            ops: high_ops,
+            textures,
+        })
+    }
+
+    /// Check the smallest scale factor of a sampling against [`Self::refuse_sparse_sampling`].
+    fn check_sparse_sampling(
+        &self,
+        sampling: AffineSample,
+        min_scale: f32,
+    ) -> Result<(), CommandError> {
+        match self.sparse_sampling_limit {
+            Some(limit) if min_scale * sampling.footprint() < limit => Err(CommandError::OTHER),
+            _ => Ok(()),
+        }
+    }
+
+    /// Get the descriptor for a register.
+    fn describe_reg(&self, Register(reg): Register) -> Result<&Descriptor, CommandError> {
+        match self.ops.get(reg) {
+            None | Some(Op::Output { .. }) => Err(CommandError::BAD_REGISTER),
+            Some(Op::Input { desc })
+            | Some(Op::Construct { desc, .. })
+            | Some(Op::Unary { desc, .. })
+            | Some(Op::Binary { desc, .. }) => Ok(desc),
+        }
+    }
+
+    fn push(&mut self, op: Op) -> Register {
+        let reg = Register(self.ops.len());
+        self.ops.push(op);
+        reg
+    }
+}
+
+impl ColorConversion {
+    pub(crate) fn into_shader(&self) -> FragmentShader {
+        match self {
+            ColorConversion::Xyz {
+                to_xyz_matrix,
+                from_xyz_matrix,
+            } => {
+                let from = from_xyz_matrix.inv().into();
+                let matrix = to_xyz_matrix.multiply_right(from).into();
+
+                FragmentShader::LinearColorMatrix(shaders::LinearColorTransform { matrix })
+            }
+            ColorConversion::XyzToOklab { to_xyz_matrix } => {
+                FragmentShader::Oklab(shaders::oklab::Shader::with_encode(*to_xyz_matrix))
+            }
+            ColorConversion::OklabToXyz { from_xyz_matrix } => {
+                let from_xyz_matrix = from_xyz_matrix.inv().into();
+                FragmentShader::Oklab(shaders::oklab::Shader::with_decode(from_xyz_matrix))
+            }
+        }
+    }
+}
+
+impl ChromaticAdaptation {
+    pub(crate) fn into_matrix(&self) -> Result<[f32; 9], CompileError> {
+        use palette::{
+            chromatic_adaptation::{Method, TransformMatrix},
+            white_point as wp,
+        };
+
+        // FIXME: when you adjust the value-to-type translation, also adjust it within `method`.
+        macro_rules! translate_matrix {
+            ($source:expr, $target:expr, $($lhs:ident => $lhsty:ty)|*) => {
+                $(
+                    translate_matrix!(
+                        @$source, $target, $lhs => $lhsty :
+                        A => wp::A | B => wp::B | C => wp::C
+                        | D50 => wp::D50 | D55 => wp::D55 | D65 => wp::D65
+                        | D75 => wp::D75 | E => wp::E | F2 => wp::F2
+                        | F7 => wp::F7 | F11 => wp::F11
+                    );
+                )*
+            };
+            (@$source:expr, $target:expr, $lhs:ident => $lhsty:ty : $($rhs:ident => $ty:ty)|*) => {
+                $(
+                    if let (Whitepoint::$lhs, Whitepoint::$rhs) = ($source, $target) {
+                        return Ok(<Method as TransformMatrix<$lhsty, $ty, f32>>::generate_transform_matrix
+                                  as fn(&Method) -> [f32;9]);
+                    }
+                )*
+            };
+        }
+
+        // FIXME: when you adjust the value-to-type translation, also adjust it within
+        // `translate_matrix!`
+        let method = (|| {
+            translate_matrix! {
+                self.source, self.target,
+                A => wp::A | B => wp::B | C => wp::C
+                | D50 => wp::D50 | D55 => wp::D55 | D65 => wp::D65
+                | D75 => wp::D75 | E => wp::E | F2 => wp::F2
+                | F7 => wp::F7 | F11 => wp::F11
+            };
+
+            Err(CompileError::NotYetImplemented)
+        })()?;
+
+        let matrices = method(match self.method {
+            // Bradford's original method that does slight blue non-linearity is not yet supported.
+            // Please implement the paper if you feel compelled to.
+            ChromaticAdaptationMethod::BradfordNonLinear => {
+                return Err(CompileError::NotYetImplemented)
+            }
+            ChromaticAdaptationMethod::BradfordVonKries => &Method::Bradford,
+            ChromaticAdaptationMethod::VonKries => &Method::VonKries,
+            ChromaticAdaptationMethod::Xyz => &Method::XyzScaling,
+        });
+
+        Ok(matrices)
+    }
+}
+
+#[rustfmt::skip]
+impl DerivativeMethod {
+    /// The smoothing across the derivative, combined with the difference `[0.5, 0, -0.5]`.
+    fn smoothing(&self) -> Result<[f32; 3], CompileError> {
+        use DerivativeMethod::*;
+        match self {
+            Prewitt => Ok([1./3., 1./3., 1./3.]),
+            Sobel => Ok([1./4., 1./2., 1./4.]),
+            Scharr3 => Ok([46.84/256., 162.32/256., 46.84/256.]),
+            Scharr3To4Bit => Ok([3./16., 10./16., 3./16.]),
+            Scharr3To8Bit => Ok([47./256., 162./256., 47./256.]),
+            // FIXME: implement these.
+            // When you do add them to tests/blend.rs
+            | Roberts
+            | Scharr4
+            | Scharr5
+            | Scharr5Tab => Err(CompileError::NotYetImplemented)
+        }
+    }
+
+    fn into_shader(&self, direction: Direction) -> Result<FragmentShader, CompileError> {
+        use shaders::box3;
+        let matrix = RowMatrix::with_outer_product(self.smoothing()?, [0.5, 0.0, -0.5]);
+        let shader = box3::Shader::new(direction.adjust_vertical_box(matrix));
+        Ok(shaders::FragmentShader::Box3(shader))
+    }
+}
+
+impl Hough {
+    /// Find the largest local maxima of an accumulator created by [`CommandBuffer::hough`].
+    ///
+    /// A peak is a pixel with votes which is not exceeded by any of its eight neighbors. Of
+    /// several equal neighbors, only the first in row order is a peak. At most `count` peaks are
+    /// returned, ordered by descending votes.
+    ///
+    /// Returns `None` if the image is not available on the host or its texel can not be decoded.
+    pub fn peaks(accumulator: &PoolImage, count: usize) -> Option<Vec<HoughPeak>> {
+        let (width, height) = accumulator.descriptor().size();
+        let votes = decode_scalars(accumulator)?;
+
+        let at = |x: i64, y: i64| {
+            if x < 0 || y < 0 || x >= i64::from(width) || y >= i64::from(height) {
+                None
+            } else {
+                Some(votes[y as usize * width as usize + x as usize])
+            }
+        };
+
+        let mut peaks = vec![];
+        for y in 0..i64::from(height) {
+            for x in 0..i64::from(width) {
+                let value = at(x, y).unwrap();
+                if value <= 0.0 {
+                    continue;
+                }
+
+                let is_peak = (-1..=1)
+                    .flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+                    .filter(|&offset| offset != (0, 0))
+                    .all(|(dx, dy)| match at(x + dx, y + dy) {
+                        None => true,
+                        // Neighbors before this one in row order must be strictly smaller.
+                        Some(other) if (dy, dx) < (0, 0) => other < value,
+                        Some(other) => other <= value,
+                    });
+
+                if is_peak {
+                    peaks.push(HoughPeak {
+                        x: x as u32,
+                        y: y as u32,
+                        votes: value,
+                    });
+                }
+            }
+        }
+
+        peaks.sort_by(|a, b| {
+            b.votes
+                .partial_cmp(&a.votes)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        peaks.truncate(count);
+        Some(peaks)
+    }
+
+    /// Convert a peak of a line accumulator to `(ρ, θ)`.
+    ///
+    /// The distance `ρ` is in pixels from the center of an image with the given size, the angle
+    /// `θ` of the line normal in radians. Returns `None` if this does not describe lines.
+    pub fn line(&self, width: u32, height: u32, peak: &HoughPeak) -> Option<(f32, f32)> {
+        match *self {
+            Hough::Lines { thetas, rhos } => {
+                let diagonal = (width as f32).hypot(height as f32);
+                let theta = core::f32::consts::PI * peak.x as f32 / thetas as f32;
+                let rho = ((peak.y as f32 + 0.5) / rhos as f32 - 0.5) * diagonal;
+                Some((rho, theta))
+            }
+            Hough::Circles { .. } => None,
+        }
+    }
+}
+
+impl Ssim {
+    /// The mean similarity of a map created by [`CommandBuffer::ssim`].
+    ///
+    /// Returns `None` if the image is not available on the host, its texel can not be decoded or
+    /// it is empty.
+    pub fn mean(map: &PoolImage) -> Option<f32> {
+        let values = decode_scalars(map)?;
+        if values.is_empty() {
+            return None;
+        }
+
+        let sum: f64 = values.iter().map(|&v| f64::from(v)).sum();
+        Some((sum / values.len() as f64) as f32)
+    }
+}
+
+impl Statistics {
+    /// Decode the output of [`CommandBuffer::statistics`] from its pixels.
+    ///
+    /// Each row holds one of the statistics, in the order of the fields, for the four channels.
+    pub(crate) fn from_words(words: &[u32]) -> Option<Self> {
+        if words.len() != 20 {
+            return None;
+        }
+
+        let row = |idx: usize| {
+            let mut values = [0.0; 4];
+            for (value, &word) in values.iter_mut().zip(&words[4 * idx..]) {
+                *value = f32::from_bits(word);
+            }
+            values
+        };
+
+        Some(Statistics {
+            min: row(0),
+            max: row(1),
+            sum: row(2),
+            mean: row(3),
+            variance: row(4),
+        })
+    }
+}
+
+impl PerceptualHash {
+    /// Read the hash from an image created by [`CommandBuffer::perceptual_hash`].
+    ///
+    /// The bits are in row order, the first one being the most significant. This is the order of
+    /// the hexadecimal representation of the `imagehash` library.
+    ///
+    /// Returns `None` if the image is not available on the host, its texel can not be decoded or
+    /// it does not have 64 pixels.
+    pub fn bits(hash: &PoolImage) -> Option<u64> {
+        let values = decode_scalars(hash)?;
+        if values.len() != 64 {
+            return None;
+        }
+
+        Some(
+            values
+                .iter()
+                .fold(0, |bits, &value| (bits << 1) | u64::from(value > 0.5)),
+        )
+    }
+}
+
+impl Smoothing {
+    /// Weighted average using a gauss kernel with the given standard deviation, in pixels.
+    ///
+    /// The kernel is cut off after three standard deviations, where the remaining weights are
+    /// below `0.5%` of the center.
+    pub fn gaussian(sigma: f32) -> Self {
+        Smoothing {
+            method: SmoothingMethod::Gaussian,
+            radius: (3.0 * sigma).ceil() as u32,
+            sigma,
+            range_sigma: 0.0,
+            range: RangeDistance::Luma,
+        }
+    }
+
+    /// Unweighted average of a square window, reaching `radius` pixels to each side.
+    pub fn laplace(radius: u32) -> Self {
+        Smoothing {
+            method: SmoothingMethod::Laplace,
+            radius,
+            sigma: 0.0,
+            range_sigma: 0.0,
+            range: RangeDistance::Luma,
+        }
+    }
+
+    /// Choose the pixel with median luma of a square window with `2·radius + 1` pixels per side.
+    ///
+    /// For example, a radius of 1 to 4 corresponds to windows of 3×3 through 9×9 pixels.
+    pub fn median(radius: u32) -> Self {
+        Smoothing {
+            method: SmoothingMethod::Median,
+            radius,
+            sigma: 0.0,
+            range_sigma: 0.0,
+            range: RangeDistance::Luma,
+        }
+    }
+
+    /// Weighted average where weights decrease with distance and with difference in value.
+    ///
+    /// Both weights are gaussian, the spatial one with standard deviation `sigma` in pixels and
+    /// the range one with standard deviation `range_sigma` in units of the `RangeDistance`. The
+    /// kernel is cut off after three spatial standard deviations.
+    pub fn bilateral(sigma: f32, range_sigma: f32) -> Self {
+        Smoothing {
+            method: SmoothingMethod::Bilteral,
+            radius: (3.0 * sigma).ceil() as u32,
+            sigma,
+            range_sigma,
+            range: RangeDistance::Luma,
+        }
+    }
+
+    /// Mean of the least varying of four square regions with `region` pixels per side.
+    ///
+    /// The regions overlap on the axes through the pixel, such that the window has a radius of
+    /// `region - 1` pixels. A region size of 3 corresponds to the example in `Kuwahara`.
+    pub fn kuwahara(region: u32) -> Self {
+        Smoothing {
+            method: SmoothingMethod::Kuwahara,
+            radius: region.saturating_sub(1),
+            sigma: 0.0,
+            range_sigma: 0.0,
+            range: RangeDistance::Luma,
+        }
+    }
+
+    /// Change how the difference in value between two pixels is measured.
+    pub fn with_range_distance(self, range: RangeDistance) -> Self {
+        Smoothing { range, ..self }
+    }
+
+    /// Change the number of pixels considered on each side of the center pixel.
+    pub fn with_radius(self, radius: u32) -> Self {
+        Smoothing { radius, ..self }
+    }
+
+    fn bilateral_shader(&self, color: &Color) -> shaders::bilateral::Shader {
+        let xyz_transform = match color {
+            Color::Rgb {
+                primary,
+                whitepoint,
+                ..
+            } => primary.to_xyz(*whitepoint),
+            _ => RowMatrix::diag(1.0, 1.0, 1.0),
+        };
+
+        // Must be kept in sync with the constants in the shader source.
+        let distance = match (self.range, color) {
+            (RangeDistance::Luma, _) => 0,
+            (RangeDistance::Linear, _) => 1,
+            // The linear representation of Oklab already is Lab.
+            (RangeDistance::Oklab, Color::Oklab) => 1,
+            (RangeDistance::Oklab, _) => 2,
+        };
+
+        shaders::bilateral::Shader {
+            xyz_transform,
+            luma: color.luma_weights(),
+            sigma: self.sigma,
+            range_sigma: self.range_sigma,
+            radius: self.radius,
+            distance,
+        }
+    }
+
+    /// The render passes of a separable method, along the width and then along the height.
+    fn separable_passes(&self) -> Result<Vec<FragmentShader>, CompileError> {
+        let weights = self.separable_weights()?;
+        let pass = |direction, weights| {
+            FragmentShader::Separable(shaders::separable::Shader {
+                direction,
+                weights,
+                center: self.radius,
+                border: BorderMode::Clamp.shader_parameters().0,
+                border_color: [0.0; 4],
+                premultiplied: true,
+            })
+        };
+
+        Ok(vec![pass([1, 0], weights.clone()), pass([0, 1], weights)])
+    }
+
+    /// The normalized weights of a one-dimensional kernel, if the method is separable.
+    fn separable_weights(&self) -> Result<Vec<f32>, CompileError> {
+        let radius = self.radius as i32;
+        let weights: Vec<f32> = match self.method {
+            SmoothingMethod::Gaussian => {
+                let scale = -0.5 / (self.sigma * self.sigma);
+                (-radius..=radius)
+                    .map(|x| (scale * (x * x) as f32).exp())
+                    .collect()
+            }
+            SmoothingMethod::Laplace => vec![1.0; 2 * self.radius as usize + 1],
+            _ => return Err(CompileError::NotYetImplemented),
+        };
+
+        let sum: f32 = weights.iter().sum();
+        Ok(weights.into_iter().map(|w| w / sum).collect())
+    }
+}
 
-        Ok(Program {
-            ops: high_ops,
-            textures,
-        })
+impl Kernel {
+    /// A kernel with `width` by `height` weights, given in row-major order.
+    pub fn dense(width: u32, height: u32, weights: &[f32]) -> Self {
+        Kernel {
+            weights: KernelWeights::Dense {
+                width,
+                height,
+                weights: weights.to_vec(),
+            },
+            border: BorderMode::Clamp,
+        }
     }
 
-    /// Get the descriptor for a register.
-    fn describe_reg(&self, Register(reg): Register) -> Result<&Descriptor, CommandError> {
-        match self.ops.get(reg) {
-            None | Some(Op::Output { .. }) => Err(CommandError::BAD_REGISTER),
-            Some(Op::Input { desc })
-            | Some(Op::Construct { desc, .. })
-            | Some(Op::Unary { desc, .. })
-            | Some(Op::Binary { desc, .. }) => Ok(desc),
+    /// A kernel that is the outer product of a row of weights and a column of weights.
+    pub fn separable(width: &[f32], height: &[f32]) -> Self {
+        Kernel {
+            weights: KernelWeights::Separable {
+                width: width.to_vec(),
+                height: height.to_vec(),
+            },
+            border: BorderMode::Clamp,
         }
     }
 
-    fn push(&mut self, op: Op) -> Register {
-        let reg = Register(self.ops.len());
-        self.ops.push(op);
-        reg
+    /// Change how pixels outside the image are sampled.
+    pub fn with_border(self, border: BorderMode) -> Self {
+        Kernel { border, ..self }
     }
-}
 
-impl ColorConversion {
-    pub(crate) fn into_shader(&self) -> FragmentShader {
-        match self {
-            ColorConversion::Xyz {
-                to_xyz_matrix,
-                from_xyz_matrix,
+    fn is_valid(&self) -> bool {
+        let finite = |weights: &[f32]| weights.iter().all(|w| w.is_finite());
+        let border = match self.border {
+            BorderMode::Constant(color) => finite(&color),
+            _ => true,
+        };
+
+        let weights = match &self.weights {
+            KernelWeights::Dense {
+                width,
+                height,
+                weights,
             } => {
-                let from = from_xyz_matrix.inv().into();
-                let matrix = to_xyz_matrix.multiply_right(from).into();
+                let len = (*width as usize).checked_mul(*height as usize);
+                !weights.is_empty() && len == Some(weights.len()) && finite(weights)
+            }
+            KernelWeights::Separable { width, height } => {
+                !width.is_empty() && !height.is_empty() && finite(width) && finite(height)
+            }
+        };
 
-                FragmentShader::LinearColorMatrix(shaders::LinearColorTransform { matrix })
+        border && weights
+    }
+
+    /// Plan the render passes of this kernel, or `None` if it is too large.
+    ///
+    /// Separable kernels are rendered in two passes if possible. The intermediate result is
+    /// stored in the texel of the image, so this requires a first pass that can not leave the
+    /// range of the image's values.
+    fn passes(&self) -> Option<Vec<FragmentShader>> {
+        use shaders::{convolve, separable};
+
+        let (border, border_color) = self.border.shader_parameters();
+        let dense = |size: [usize; 2], weights: Vec<f32>| {
+            if weights.len() > convolve::MAX_WEIGHTS {
+                return None;
             }
-            ColorConversion::XyzToOklab { to_xyz_matrix } => {
-                FragmentShader::Oklab(shaders::oklab::Shader::with_encode(*to_xyz_matrix))
+
+            let [width, height] = size;
+            Some(FragmentShader::Convolve(convolve::Shader {
+                size: [width as u32, height as u32],
+                center: [width as u32 / 2, height as u32 / 2],
+                weights,
+                border,
+                border_color,
+            }))
+        };
+
+        let pass = |direction, weights: Vec<f32>, border_color| {
+            if weights.len() > separable::MAX_TAPS {
+                return None;
             }
-            ColorConversion::OklabToXyz { from_xyz_matrix } => {
-                let from_xyz_matrix = from_xyz_matrix.inv().into();
-                FragmentShader::Oklab(shaders::oklab::Shader::with_decode(from_xyz_matrix))
+
+            Some(FragmentShader::Separable(separable::Shader {
+                direction,
+                center: weights.len() as u32 / 2,
+                weights,
+                border,
+                border_color,
+                premultiplied: false,
+            }))
+        };
+
+        let (width, height) = match &self.weights {
+            KernelWeights::Dense {
+                width,
+                height,
+                weights,
+            } => {
+                let size = [*width as usize, *height as usize];
+                return Some(vec![dense(size, weights.clone())?]);
+            }
+            KernelWeights::Separable { width, height } => (width, height),
+        };
+
+        // A single row or column is a single pass in any case.
+        if let [scale] = height[..] {
+            let weights = width.iter().map(|w| w * scale).collect();
+            return Some(vec![pass([1, 0], weights, border_color)?]);
+        }
+
+        if let [scale] = width[..] {
+            let weights = height.iter().map(|w| w * scale).collect();
+            return Some(vec![pass([0, 1], weights, border_color)?]);
+        }
+
+        let sum: f32 = width.iter().sum();
+        let in_range = width.iter().all(|&w| w >= 0.0) && sum <= 1.0 + 1e-5;
+
+        if in_range {
+            // Outside the image, every tap of the first pass sees the border color.
+            let [r, g, b, a] = border_color;
+            let outer_color = [r * sum, g * sum, b * sum, a * sum];
+
+            if let (Some(first), Some(second)) = (
+                pass([1, 0], width.clone(), border_color),
+                pass([0, 1], height.clone(), outer_color),
+            ) {
+                return Some(vec![first, second]);
             }
         }
+
+        let weights = height
+            .iter()
+            .flat_map(|h| width.iter().map(move |w| h * w))
+            .collect();
+        Some(vec![dense([width.len(), height.len()], weights)?])
     }
 }
 
-impl ChromaticAdaptation {
-    pub(crate) fn into_matrix(&self) -> Result<[f32; 9], CompileError> {
-        use palette::{
-            chromatic_adaptation::{Method, TransformMatrix},
-            white_point as wp,
-        };
+impl Noise {
+    /// A single octave of noise, with one lattice cell every `1 / frequency` pixels.
+    ///
+    /// Further octaves double the frequency and halve the amplitude.
+    pub fn new(kind: NoiseKind, seed: u32, frequency: f32) -> Self {
+        Noise {
+            kind,
+            seed,
+            frequency,
+            octaves: 1,
+            lacunarity: 2.0,
+            gain: 0.5,
+        }
+    }
 
-        // FIXME: when you adjust the value-to-type translation, also adjust it within `method`.
-        macro_rules! translate_matrix {
-            ($source:expr, $target:expr, $($lhs:ident => $lhsty:ty)|*) => {
-                $(
-                    translate_matrix!(
-                        @$source, $target, $lhs => $lhsty :
-                        A => wp::A | B => wp::B | C => wp::C
-                        | D50 => wp::D50 | D55 => wp::D55 | D65 => wp::D65
-                        | D75 => wp::D75 | E => wp::E | F2 => wp::F2
-                        | F7 => wp::F7 | F11 => wp::F11
-                    );
-                )*
-            };
-            (@$source:expr, $target:expr, $lhs:ident => $lhsty:ty : $($rhs:ident => $ty:ty)|*) => {
-                $(
-                    if let (Whitepoint::$lhs, Whitepoint::$rhs) = ($source, $target) {
-                        return Ok(<Method as TransformMatrix<$lhsty, $ty, f32>>::generate_transform_matrix
-                                  as fn(&Method) -> [f32;9]);
-                    }
-                )*
-            };
+    /// Change the number of octaves.
+    pub fn with_octaves(self, octaves: u32) -> Self {
+        Noise { octaves, ..self }
+    }
+
+    /// Change the factors of frequency and amplitude from one octave to the next.
+    pub fn with_fbm(self, lacunarity: f32, gain: f32) -> Self {
+        Noise {
+            lacunarity,
+            gain,
+            ..self
         }
+    }
 
-        // FIXME: when you adjust the value-to-type translation, also adjust it within
-        // `translate_matrix!`
-        let method = (|| {
-            translate_matrix! {
-                self.source, self.target,
-                A => wp::A | B => wp::B | C => wp::C
-                | D50 => wp::D50 | D55 => wp::D55 | D65 => wp::D65
-                | D75 => wp::D75 | E => wp::E | F2 => wp::F2
-                | F7 => wp::F7 | F11 => wp::F11
-            };
+    fn is_valid(&self) -> bool {
+        let positive = |value: f32| value.is_finite() && value > 0.0;
+        positive(self.frequency)
+            && positive(self.lacunarity)
+            && positive(self.gain)
+            && (1..=shaders::noise::MAX_OCTAVES).contains(&self.octaves)
+    }
+}
 
-            Err(CompileError::NotYetImplemented)
-        })()?;
+impl Warp {
+    /// Create warp parameters with the factor from displacement values to pixels.
+    ///
+    /// Chooses the nearest pixel and repeats the edge of the image.
+    pub fn new(scale: f32) -> Self {
+        Warp {
+            scale,
+            neutral: 0.0,
+            sampling: AffineSample::Nearest,
+            border: BorderMode::Clamp,
+        }
+    }
 
-        let matrices = method(match self.method {
-            // Bradford's original method that does slight blue non-linearity is not yet supported.
-            // Please implement the paper if you feel compelled to.
-            ChromaticAdaptationMethod::BradfordNonLinear => {
-                return Err(CompileError::NotYetImplemented)
-            }
-            ChromaticAdaptationMethod::BradfordVonKries => &Method::Bradford,
-            ChromaticAdaptationMethod::VonKries => &Method::VonKries,
-            ChromaticAdaptationMethod::Xyz => &Method::XyzScaling,
-        });
+    /// Change the displacement value that corresponds to no offset.
+    pub fn with_neutral(self, neutral: f32) -> Self {
+        Warp { neutral, ..self }
+    }
 
-        Ok(matrices)
+    /// Change how pixels are resolved from the warped image.
+    pub fn with_sampling(self, sampling: AffineSample) -> Self {
+        Warp { sampling, ..self }
+    }
+
+    /// Change how pixels outside the image are sampled.
+    pub fn with_border(self, border: BorderMode) -> Self {
+        Warp { border, ..self }
     }
 }
 
-#[rustfmt::skip]
-impl DerivativeMethod {
-    fn into_shader(&self, direction: Direction) -> Result<FragmentShader, CompileError> {
-        use DerivativeMethod::*;
-        use shaders::box3;
+impl BorderMode {
+    /// The mode and the constant color, as defined in the shader sources.
+    fn shader_parameters(self) -> (u32, [f32; 4]) {
         match self {
-            Prewitt => {
-                let matrix = RowMatrix::with_outer_product(
-                    [1./3., 1./3., 1./3.],
-                    [0.5, 0.0, -0.5],
-                );
+            BorderMode::Clamp => (0, [0.0; 4]),
+            BorderMode::Mirror => (1, [0.0; 4]),
+            BorderMode::Wrap => (2, [0.0; 4]),
+            BorderMode::Constant(color) => (3, color),
+            BorderMode::Reflect101 => (4, [0.0; 4]),
+        }
+    }
+}
 
-                let shader = box3::Shader::new(direction.adjust_vertical_box(matrix));
-                Ok(shaders::FragmentShader::Box3(shader))
-            }
-            Sobel => {
-                let matrix = RowMatrix::with_outer_product(
-                    [1./4., 1./2., 1./4.],
-                    [0.5, 0.0, -0.5],
-                );
+impl ResizeFilter {
+    /// The filter and its support, as defined in the shader source.
+    fn shader_parameters(self) -> (u32, f32) {
+        match self {
+            ResizeFilter::Triangle => (0, 1.0),
+            ResizeFilter::CatmullRom => (1, 2.0),
+            ResizeFilter::Mitchell => (2, 2.0),
+            ResizeFilter::Lanczos2 => (3, 2.0),
+            ResizeFilter::Lanczos3 => (4, 3.0),
+            ResizeFilter::Area => (5, 0.5),
+        }
+    }
 
-                let shader = box3::Shader::new(direction.adjust_vertical_box(matrix));
-                Ok(shaders::FragmentShader::Box3(shader))
-            }
-            Scharr3 => {
-                let matrix = RowMatrix::with_outer_product(
-                    [46.84/256., 162.32/256., 46.84/256.],
-                    [0.5, 0.0, -0.5],
-                );
+    /// One pass of resampling, where `scale` is the number of source pixels per target pixel.
+    fn shader(self, scale: f32, along_height: bool) -> FragmentShader {
+        let (filter, support) = self.shader_parameters();
+        FragmentShader::Resample(shaders::resample::Shader {
+            filter,
+            support,
+            scale,
+            along_height,
+        })
+    }
+}
 
-                let shader = box3::Shader::new(direction.adjust_vertical_box(matrix));
-                Ok(shaders::FragmentShader::Box3(shader))
-            }
-            Scharr3To4Bit => {
-                let matrix = RowMatrix::with_outer_product(
-                    [3./16., 10./16., 3./16.],
-                    [0.5, 0.0, -0.5],
-                );
+/// Check for a texel that holds the values of noise constructors.
+///
+/// These are single gray channels, and scalars where each component but alpha holds a value.
+fn can_hold_noise(texel: &Texel) -> bool {
+    let gray = matches!(texel.samples.parts, SampleParts::Luma | SampleParts::LumaA);
+    gray || matches!(texel.color, Color::Scalars { .. })
+}
 
-                let shader = box3::Shader::new(direction.adjust_vertical_box(matrix));
-                Ok(shaders::FragmentShader::Box3(shader))
-            }
-            Scharr3To8Bit => {
-                let matrix = RowMatrix::with_outer_product(
-                    [47./256., 162./256., 47./256.],
-                    [0.5, 0.0, -0.5],
-                );
+/// Check for a texel whose linear representation can be interpolated meaningfully.
+///
+/// These are RGB-ish colors (except `Yuv` samples), scalars, and Oklab whose linear
+/// representation is Lab.
+fn can_interpolate(texel: &Texel) -> bool {
+    match texel.color {
+        Color::Rgb { .. } => texel.samples.parts != SampleParts::Yuv,
+        Color::Oklab | Color::Scalars { .. } => true,
+    }
+}
 
-                let shader = box3::Shader::new(direction.adjust_vertical_box(matrix));
-                Ok(shaders::FragmentShader::Box3(shader))
-            }
-            // FIXME: implement these.
-            // When you do add them to tests/blend.rs
-            | Roberts
-            | Scharr4
-            | Scharr5
-            | Scharr5Tab => Err(CompileError::NotYetImplemented)
+/// Decode the first component of the linear representation of each pixel, in row order.
+///
+/// Returns `None` if the image is not available on the host or its texel can not be decoded.
+fn decode_scalars(image: &PoolImage) -> Option<Vec<f32>> {
+    let desc = image.descriptor();
+    let bytes = image.as_bytes()?;
+
+    let transfer = match desc.texel.color {
+        Color::Rgb { transfer, .. } | Color::Scalars { transfer } => transfer.into(),
+        Color::Oklab => shaders::stage::Transfer::Oklab,
+    };
+
+    let parameter = shaders::stage::XyzParameter {
+        bits: desc.texel.samples.bits,
+        parts: desc.texel.samples.parts,
+        transfer,
+    };
+
+    let (width, height) = desc.size();
+    let texel_stride = usize::from(desc.layout.bytes_per_texel);
+    let row_stride = desc.layout.bytes_per_row as usize;
+
+    let mut values = Vec::with_capacity(width as usize * height as usize);
+    for y in 0..height as usize {
+        let row = bytes.get(y * row_stride..)?;
+        for x in 0..width as usize {
+            let texel = row.get(x * texel_stride..(x + 1) * texel_stride)?;
+            values.push(parameter.decode_texel(texel)?[0]);
         }
     }
+
+    Some(values)
+}
+
+/// The texel of images holding numbers rather than colors, such as a histogram.
+///
+/// Each pixel is a `u32` in host byte order, spread over the four 8-bit channels. It is either a
+/// count, see [`Retire::counts`](crate::run::Retire::counts), or the bits of an `f32`.
+pub const COUNT_TEXEL: Texel = Texel {
+    block: buffer::Block::Pixel,
+    samples: buffer::Samples {
+        bits: buffer::SampleBits::Int8x4,
+        parts: SampleParts::Rgba,
+    },
+    color: Color::Scalars {
+        transfer: buffer::Transfer::Linear,
+    },
+};
+
+/// Render a sequence of passes, each one reading the result of the previous one.
+///
+/// Intermediate results are allocated with the given descriptor.
+fn push_passes(
+    high_ops: &mut Vec<High>,
+    textures: &mut ImageBufferPlan,
+    descriptor: &Descriptor,
+    src: Texture,
+    dst: Texture,
+    passes: Vec<FragmentShader>,
+) {
+    let last = passes.len() - 1;
+    let mut operand = src;
+
+    for (pass, shader) in passes.into_iter().enumerate() {
+        let target = if pass == last {
+            dst
+        } else {
+            textures.allocate_intermediate(descriptor)
+        };
+
+        high_ops.push(High::PushOperand(operand));
+        high_ops.push(High::Construct {
+            dst: Target::Discard(target),
+            fn_: Function::PaintFullScreen { shader },
+        });
+
+        operand = target;
+    }
 }
 
 impl Direction {
@@ -1491,50 +4258,281 @@ impl Affine {
         }
     }
 
-    /// After the transformation, rotate everything clockwise.
-    ///
-    /// This corresponds to a left-side multiplication of the transformation matrix.
-    pub fn rotate(self, rad: f32) -> Self {
-        let post = RowMatrix::new([
-            rad.cos(), rad.sin(), 0.,
-            -rad.sin(), rad.cos(), 0.,
-            0., 0., 1.,
-        ]);
+    /// After the transformation, rotate everything clockwise.
+    ///
+    /// This corresponds to a left-side multiplication of the transformation matrix.
+    pub fn rotate(self, rad: f32) -> Self {
+        let post = RowMatrix::new([
+            rad.cos(), rad.sin(), 0.,
+            -rad.sin(), rad.cos(), 0.,
+            0., 0., 1.,
+        ]);
+
+        let post = post.multiply_right(RowMatrix::new(self.transformation).into());
+        let transformation = RowMatrix::from(post).into_inner();
+
+        Affine {
+            transformation,
+            ..self
+        }
+    }
+
+    /// After the transformation, shift by an x and y offset.
+    ///
+    /// This corresponds to a left-side multiplication of the transformation matrix.
+    pub fn shift(self, x: f32, y: f32) -> Self {
+        let post = RowMatrix::new([
+            1., 0., x,
+            0., 1., y,
+            0., 0., 1.,
+        ]);
+
+        let post = post.multiply_right(RowMatrix::new(self.transformation).into());
+        let transformation = RowMatrix::from(post).into_inner();
+
+        Affine {
+            transformation,
+            ..self
+        }
+    }
+
+    /// The smallest factor by which any direction of the image is scaled.
+    ///
+    /// This is the smaller singular value of the linear part of the transformation.
+    fn min_scale(&self) -> f32 {
+        let [a, b, _, c, d, _, _, _, _] = self.transformation;
+        min_singular_value([a, b, c, d])
+    }
+}
+
+impl Perspective {
+    /// Create perspective parameters with identity transformation.
+    pub fn new(sampling: AffineSample) -> Self {
+        Perspective::from(Affine::new(sampling))
+    }
+
+    /// Create the transformation mapping four points onto four other points.
+    ///
+    /// The points are pixel coordinates, usually the corners of the transformed image and where
+    /// they should appear in the image below. Returns `None` if no unique transformation exists,
+    /// for example when three of the points are on a common line.
+    pub fn with_correspondences(
+        sampling: AffineSample,
+        source: [[f32; 2]; 4],
+        target: [[f32; 2]; 4],
+    ) -> Option<Self> {
+        // The direct linear transformation with the last matrix entry fixed to 1. Each pair of
+        // points contributes the equations:
+        //   h0·x + h1·y + h2 - h6·x·u - h7·y·u = u
+        //   h3·x + h4·y + h5 - h6·x·v - h7·y·v = v
+        let mut system = [[0.0f64; 9]; 8];
+        for (i, (&[x, y], &[u, v])) in source.iter().zip(target.iter()).enumerate() {
+            let (x, y, u, v) = (f64::from(x), f64::from(y), f64::from(u), f64::from(v));
+            system[2 * i] = [x, y, 1.0, 0.0, 0.0, 0.0, -x * u, -y * u, u];
+            system[2 * i + 1] = [0.0, 0.0, 0.0, x, y, 1.0, -x * v, -y * v, v];
+        }
+
+        let [h0, h1, h2, h3, h4, h5, h6, h7] = solve_linear_system(system)?;
+        let transformation = [
+            h0 as f32, h1 as f32, h2 as f32, h3 as f32, h4 as f32, h5 as f32, h6 as f32, h7 as f32,
+            1.0,
+        ];
+
+        if !transformation.iter().all(|h| h.is_finite()) {
+            return None;
+        }
+
+        Some(Perspective {
+            transformation,
+            sampling,
+        })
+    }
+
+    /// The smallest factor by which any direction of the image is scaled.
+    ///
+    /// The scale of a projective transformation depends on the position, its extremes are found
+    /// at the corners of the image.
+    fn min_scale(&self, region: Rectangle) -> f32 {
+        let [h0, h1, h2, h3, h4, h5, h6, h7, h8] = self.transformation;
+        let corners = [
+            (region.x, region.y),
+            (region.max_x, region.y),
+            (region.max_x, region.max_y),
+            (region.x, region.max_y),
+        ];
+
+        corners
+            .iter()
+            .map(|&(u, v)| {
+                let (u, v) = (u as f32, v as f32);
+                let w = h6 * u + h7 * v + h8;
+                let x = (h0 * u + h1 * v + h2) / w;
+                let y = (h3 * u + h4 * v + h5) / w;
+                // The jacobian of the projection at this point.
+                min_singular_value([
+                    (h0 - x * h6) / w,
+                    (h1 - x * h7) / w,
+                    (h3 - y * h6) / w,
+                    (h4 - y * h7) / w,
+                ])
+            })
+            .fold(f32::INFINITY, f32::min)
+    }
+}
+
+impl From<Affine> for Perspective {
+    fn from(affine: Affine) -> Self {
+        Perspective {
+            transformation: affine.transformation,
+            sampling: affine.sampling,
+        }
+    }
+}
+
+/// The smaller singular value of a row-major 2×2 matrix.
+fn min_singular_value([a, b, c, d]: [f32; 4]) -> f32 {
+    let sum = a * a + b * b + c * c + d * d;
+    let det = a * d - b * c;
+    let discriminant = (sum * sum - 4.0 * det * det).max(0.0);
+    ((sum - discriminant.sqrt()) / 2.0).max(0.0).sqrt()
+}
+
+/// Solve a linear system of 8 equations, given as rows of coefficients and the constant.
+///
+/// Uses Gaussian elimination with partial pivoting. Returns `None` if the system is singular.
+fn solve_linear_system(mut system: [[f64; 9]; 8]) -> Option<[f64; 8]> {
+    const N: usize = 8;
+
+    for col in 0..N {
+        let pivot = (col..N).max_by(|&a, &b| {
+            system[a][col]
+                .abs()
+                .partial_cmp(&system[b][col].abs())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })?;
+
+        if !(system[pivot][col].abs() > 1e-12) {
+            return None;
+        }
+
+        system.swap(col, pivot);
+
+        for row in col + 1..N {
+            let factor = system[row][col] / system[col][col];
+            for k in col..=N {
+                system[row][k] -= factor * system[col][k];
+            }
+        }
+    }
+
+    let mut solution = [0.0; N];
+    for row in (0..N).rev() {
+        let known: f64 = (row + 1..N).map(|k| system[row][k] * solution[k]).sum();
+        solution[row] = (system[row][N] - known) / system[row][row];
+    }
 
-        let post = post.multiply_right(RowMatrix::new(self.transformation).into());
-        let transformation = RowMatrix::from(post).into_inner();
+    Some(solution)
+}
 
-        Affine {
-            transformation,
-            ..self
+impl Blend {
+    /// The usual alpha blending, the same as [`Blend::SourceOver`].
+    #[allow(non_upper_case_globals)]
+    pub const Alpha: Blend = Blend::SourceOver;
+
+    /// Check if this is one of the Porter-Duff operators.
+    fn is_porter_duff(self) -> bool {
+        self.porter_duff_weights().is_some()
+    }
+
+    /// Choose the shader performing the blend, given the placement in uv coordinates.
+    fn into_shader(self, placement: [f32; 4]) -> FragmentShader {
+        if let Some(weights) = self.porter_duff_weights() {
+            return FragmentShader::Blend(shaders::blend::Shader { weights, placement });
         }
+
+        let (mode, _) = self.blend_mode();
+        FragmentShader::BlendMode(shaders::blend_mode::Shader { mode, placement })
     }
 
-    /// After the transformation, shift by an x and y offset.
-    ///
-    /// This corresponds to a left-side multiplication of the transformation matrix.
-    pub fn shift(self, x: f32, y: f32) -> Self {
-        let post = RowMatrix::new([
-            1., 0., x,
-            0., 1., y,
-            0., 0., 1.,
-        ]);
+    /// Check if this mode is evaluated on the encoded values of an image.
+    fn is_encoded(self) -> bool {
+        !self.is_porter_duff() && self.blend_mode().1
+    }
 
-        let post = post.multiply_right(RowMatrix::new(self.transformation).into());
-        let transformation = RowMatrix::from(post).into_inner();
+    /// The factors of source and destination, as affine functions in the other's alpha.
+    ///
+    /// Returns `[a, b, c, d]` where the source is weighted with `Fa = a + b·αb` and the
+    /// destination with `Fb = c + d·αa`.
+    #[rustfmt::skip]
+    fn porter_duff_weights(self) -> Option<[f32; 4]> {
+        Some(match self {
+            Blend::Clear =>           [0.0,  0.0, 0.0,  0.0],
+            Blend::SourceOver =>      [1.0,  0.0, 1.0, -1.0],
+            Blend::DestinationOver => [1.0, -1.0, 1.0,  0.0],
+            Blend::In =>              [0.0,  1.0, 0.0,  0.0],
+            Blend::Out =>             [1.0, -1.0, 0.0,  0.0],
+            Blend::Atop =>            [0.0,  1.0, 1.0, -1.0],
+            Blend::Xor =>             [1.0, -1.0, 1.0, -1.0],
+            Blend::Plus =>            [1.0,  0.0, 1.0,  0.0],
+            _ => return None,
+        })
+    }
 
-        Affine {
-            transformation,
-            ..self
+    /// The mode index in `blend_mode.frag`, and whether it's evaluated on encoded values.
+    #[rustfmt::skip]
+    fn blend_mode(self) -> (u32, bool) {
+        match self {
+            Blend::Multiply =>   (0, false),
+            Blend::Screen =>     (1, false),
+            Blend::Overlay =>    (2, true),
+            Blend::Darken =>     (3, false),
+            Blend::Lighten =>    (4, false),
+            Blend::ColorDodge => (5, false),
+            Blend::ColorBurn =>  (6, false),
+            Blend::HardLight =>  (7, true),
+            Blend::SoftLight =>  (8, true),
+            Blend::Difference => (9, false),
+            Blend::Exclusion =>  (10, false),
+            Blend::Hue =>        (11, true),
+            Blend::Saturation => (12, true),
+            Blend::Color =>      (13, true),
+            Blend::Luminosity => (14, true),
+            Blend::Clear
+            | Blend::SourceOver
+            | Blend::DestinationOver
+            | Blend::In
+            | Blend::Out
+            | Blend::Atop
+            | Blend::Xor
+            | Blend::Plus => unreachable!("Porter-Duff operators use their own shader"),
         }
     }
 }
 
 impl AffineSample {
+    /// Check for a color which we can sample with this method.
+    fn check_color(self, desc: &Descriptor) -> Result<(), CommandError> {
+        match self {
+            AffineSample::Nearest => Ok(()),
+            AffineSample::BiLinear if can_interpolate(&desc.texel) => Ok(()),
+            AffineSample::BiLinear => Err(CommandError {
+                inner: CommandErrorKind::BadDescriptor(desc.clone()),
+            }),
+        }
+    }
+
+    /// The number of source pixels read along each axis for one target pixel.
+    fn footprint(self) -> f32 {
+        match self {
+            AffineSample::Nearest => 1.0,
+            AffineSample::BiLinear => 2.0,
+        }
+    }
+
     fn as_paint_on_top(self) -> Result<PaintOnTopKind, CompileError> {
         match self {
             AffineSample::Nearest => Ok(PaintOnTopKind::Copy),
-            _ => Err(CompileError::NotYetImplemented),
+            AffineSample::BiLinear => Ok(PaintOnTopKind::CopyLinear),
         }
     }
 }
@@ -1730,3 +4728,745 @@ fn simple_program() {
     let _ = commands.compile().expect("Could build command buffer");
     assert_eq!(outformat.layout, expected);
 }
+
+#[test]
+fn blend_modes_require_rgb() {
+    let image = image::DynamicImage::new_rgba8(4, 4);
+    let rgb = Descriptor::with_srgb_image(&image);
+    let scalars = Descriptor {
+        texel: Texel {
+            color: Color::Scalars {
+                transfer: buffer::Transfer::Linear,
+            },
+            ..rgb.texel.clone()
+        },
+        ..rgb.clone()
+    };
+
+    let placement = Rectangle::with_width_height(4, 4);
+    let mut commands = CommandBuffer::default();
+
+    let below = commands.input(rgb.clone()).unwrap();
+    let above = commands.input(rgb.clone()).unwrap();
+    commands
+        .blend(below, placement, above, Blend::Multiply)
+        .expect("Valid to blend rgb");
+
+    // Overlay mixes the stored sRGB values, by transmuting them to linear ones and back.
+    let overlay = commands
+        .blend(below, placement, above, Blend::Overlay)
+        .expect("Valid to blend rgb");
+    assert_eq!(commands.describe_reg(overlay).unwrap(), &rgb);
+    let Register(overlay) = overlay;
+    assert!(matches!(
+        commands
+            .describe_reg(Register(overlay - 1))
+            .unwrap()
+            .texel
+            .color,
+        Color::Rgb {
+            transfer: buffer::Transfer::Linear,
+            ..
+        }
+    ));
+
+    let below = commands.input(scalars.clone()).unwrap();
+    let above = commands.input(scalars).unwrap();
+    commands
+        .blend(below, placement, above, Blend::SourceOver)
+        .expect("Porter-Duff works on any color");
+    let err = commands
+        .blend(below, placement, above, Blend::Hue)
+        .expect_err("Blend modes need rgb");
+    assert!(err.is_type_err());
+}
+
+#[test]
+fn inscribe_and_blend_share_placements() {
+    let small = Descriptor::with_srgb_image(&image::DynamicImage::new_rgba8(4, 2));
+    let large = Descriptor::with_srgb_image(&image::DynamicImage::new_rgba8(8, 8));
+
+    let mut commands = CommandBuffer::default();
+    let below = commands.input(large).unwrap();
+    let above = commands.input(small).unwrap();
+
+    let offset = Rectangle {
+        x: 2,
+        y: 3,
+        max_x: 6,
+        max_y: 5,
+    };
+    let stretched = Rectangle::with_width_height(4, 4);
+    let outside = Rectangle {
+        x: 6,
+        y: 0,
+        max_x: 10,
+        max_y: 2,
+    };
+
+    assert!(commands.inscribe(below, offset, above).is_ok());
+    assert!(commands.blend(below, offset, above, Blend::Alpha).is_ok());
+
+    for &rect in [stretched, outside].iter() {
+        assert!(commands.inscribe(below, rect, above).is_err());
+        assert!(commands.blend(below, rect, above, Blend::Alpha).is_err());
+    }
+
+    assert_eq!(Blend::Alpha, Blend::SourceOver);
+}
+
+#[test]
+fn smoothing_weights() {
+    let gaussian = Smoothing::gaussian(1.5);
+    assert_eq!(gaussian.radius, 5);
+
+    let weights = gaussian.separable_weights().expect("Gaussian is separable");
+    assert_eq!(weights.len(), 11);
+    assert!((weights.iter().sum::<f32>() - 1.0).abs() < 1e-6);
+    assert!(weights.windows(2).take(5).all(|w| w[0] < w[1]));
+    assert_eq!(
+        weights[..5].iter().rev().collect::<Vec<_>>(),
+        weights[6..].iter().collect::<Vec<_>>()
+    );
+
+    let image = image::DynamicImage::new_rgba8(4, 4);
+    let mut commands = CommandBuffer::default();
+    let input = commands.input(Descriptor::with_srgb_image(&image)).unwrap();
+
+    commands
+        .smooth(input, Smoothing::gaussian(0.0))
+        .expect_err("Gaussian requires a positive sigma");
+    commands
+        .smooth(input, Smoothing::laplace(128))
+        .expect_err("Radius exceeds the shader");
+    commands
+        .smooth(input, Smoothing::median(6))
+        .expect_err("Window exceeds the shader");
+    commands
+        .smooth(input, Smoothing::kuwahara(1))
+        .expect_err("Kuwahara requires regions with more than one pixel");
+    commands
+        .smooth(input, Smoothing::bilateral(1.0, 0.0))
+        .expect_err("Bilateral requires a positive range sigma");
+
+    let scalars = Descriptor {
+        texel: Texel {
+            color: Color::Scalars {
+                transfer: buffer::Transfer::Linear,
+            },
+            ..Texel::with_srgb_image(&image)
+        },
+        ..Descriptor::with_srgb_image(&image)
+    };
+
+    let values = commands.input(scalars).unwrap();
+    commands
+        .smooth(values, Smoothing::bilateral(1.0, 0.1))
+        .expect("Luma distance works on any color");
+    let err = commands
+        .smooth(
+            values,
+            Smoothing::bilateral(1.0, 0.1).with_range_distance(RangeDistance::Oklab),
+        )
+        .expect_err("Oklab distance needs a compatible color");
+    assert!(err.is_type_err());
+    let smooth = commands
+        .smooth(input, Smoothing::laplace(2))
+        .expect("Valid to smooth");
+    commands.output(smooth).expect("Valid for output");
+
+    let _ = commands.compile().expect("Could build command buffer");
+}
+
+#[test]
+fn kernel_passes() {
+    let gaussian = [0.25, 0.5, 0.25];
+    let derivative = [0.5, 0.0, -0.5];
+
+    let passes = |kernel: Kernel| kernel.passes().map(|passes| passes.len());
+
+    assert_eq!(passes(Kernel::separable(&gaussian, &gaussian)), Some(2));
+    assert_eq!(passes(Kernel::separable(&gaussian, &derivative)), Some(2));
+    // The first pass would produce negative values.
+    assert_eq!(passes(Kernel::separable(&derivative, &gaussian)), Some(1));
+    assert_eq!(passes(Kernel::separable(&derivative, &[1.0])), Some(1));
+    assert_eq!(passes(Kernel::dense(3, 1, &derivative)), Some(1));
+    assert_eq!(passes(Kernel::dense(17, 17, &[0.0; 17 * 17])), None);
+
+    let image = image::DynamicImage::new_rgba8(4, 4);
+    let mut commands = CommandBuffer::default();
+    let input = commands.input(Descriptor::with_srgb_image(&image)).unwrap();
+
+    commands
+        .convolve(input, Kernel::dense(2, 2, &derivative))
+        .expect_err("Weights do not match the size");
+    commands
+        .convolve(input, Kernel::separable(&[], &gaussian))
+        .expect_err("Kernel must not be empty");
+    commands
+        .convolve(input, Kernel::separable(&[1.0; 17], &[1.0; 17]))
+        .expect_err("Dense expansion exceeds the shader");
+    let result = commands
+        .convolve(
+            input,
+            Kernel::separable(&gaussian, &derivative).with_border(BorderMode::Mirror),
+        )
+        .expect("Valid to convolve");
+    commands.output(result).expect("Valid for output");
+
+    let _ = commands.compile().expect("Could build command buffer");
+}
+
+#[test]
+fn bilinear_requires_mixable_color() {
+    let image = image::DynamicImage::new_rgba8(4, 4);
+    let rgb = Descriptor::with_srgb_image(&image);
+    let yuv = Descriptor {
+        texel: Texel {
+            samples: buffer::Samples {
+                parts: SampleParts::Yuv,
+                ..rgb.texel.samples
+            },
+            ..rgb.texel.clone()
+        },
+        ..rgb.clone()
+    };
+
+    let affine = Affine::new(AffineSample::BiLinear).scale(2.0, 2.0);
+    let mut commands = CommandBuffer::default();
+
+    let below = commands.input(rgb.clone()).unwrap();
+    let above = commands.input(rgb).unwrap();
+    commands
+        .affine(below, affine, above)
+        .expect("Valid to sample rgb bi-linearly");
+
+    let below = commands.input(yuv.clone()).unwrap();
+    let above = commands.input(yuv).unwrap();
+    let err = commands
+        .affine(below, affine, above)
+        .expect_err("Yuv can not be mixed");
+    assert!(err.is_type_err());
+}
+
+#[test]
+fn resize_changes_layout() {
+    let image = image::DynamicImage::new_rgba8(16, 8);
+    let descriptor = Descriptor::with_srgb_image(&image);
+
+    let mut commands = CommandBuffer::default();
+    let input = commands.input(descriptor).unwrap();
+    let result = commands
+        .resize(input, 5, 12, ResizeFilter::Lanczos3)
+        .expect("Valid to resize");
+    let (_, outformat) = commands.output(result).expect("Valid for output");
+    assert_eq!(outformat.size(), (5, 12));
+
+    commands
+        .resize(input, 0, 12, ResizeFilter::CatmullRom)
+        .expect_err("Can not resize to an empty image");
+
+    let _ = commands.compile().expect("Could build command buffer");
+}
+
+#[test]
+fn refuse_sparse_sampling() {
+    let image = image::DynamicImage::new_rgba8(16, 16);
+    let descriptor = Descriptor::with_srgb_image(&image);
+
+    let mut commands = CommandBuffer::default();
+    commands.refuse_sparse_sampling(1.0);
+
+    let below = commands.input(descriptor.clone()).unwrap();
+    let above = commands.input(descriptor.clone()).unwrap();
+
+    let rotated = Affine::new(AffineSample::BiLinear)
+        .rotate(0.5)
+        .scale(0.6, 1.0);
+    commands
+        .affine(below, rotated, above)
+        .expect("Samples every pixel");
+
+    let nearest = Affine::new(AffineSample::Nearest).scale(0.6, 1.0);
+    commands
+        .affine(below, nearest, above)
+        .expect_err("Skips pixels along the width");
+
+    let shrunk = Affine::new(AffineSample::BiLinear).scale(1.0, 0.25);
+    commands
+        .affine(below, shrunk, above)
+        .expect_err("Skips pixels along the height");
+
+    commands
+        .resize(above, 4, 4, ResizeFilter::Area)
+        .expect("Resizing is never sparse");
+
+    let scalars = Descriptor {
+        texel: Texel {
+            color: Color::Scalars {
+                transfer: buffer::Transfer::Linear,
+            },
+            ..descriptor.texel.clone()
+        },
+        ..descriptor.clone()
+    };
+    let displacement = commands.input(scalars).unwrap();
+    commands
+        .warp(above, displacement, Warp::new(4.0))
+        .expect_err("Displacements may shrink arbitrarily");
+    commands
+        .warp(above, displacement, Warp::new(0.0))
+        .expect("Displaces nothing");
+}
+
+#[test]
+fn perspective_correspondences() {
+    let source = [[0.0, 0.0], [8.0, 0.0], [8.0, 8.0], [0.0, 8.0]];
+    let target = [[8.0, 4.0], [56.0, 12.0], [60.0, 60.0], [4.0, 52.0]];
+
+    let perspective = Perspective::with_correspondences(AffineSample::Nearest, source, target)
+        .expect("A unique homography");
+    let matrix = RowMatrix::new(perspective.transformation);
+
+    for (&from, &to) in source.iter().zip(target.iter()) {
+        let [x, y] = matrix.multiply_point(from);
+        assert!((x - to[0]).abs() < 1e-3 && (y - to[1]).abs() < 1e-3);
+    }
+
+    // Three points on a line do not determine a transformation.
+    let degenerate = [[0.0, 0.0], [4.0, 4.0], [8.0, 8.0], [0.0, 8.0]];
+    assert!(Perspective::with_correspondences(AffineSample::Nearest, degenerate, target).is_none());
+
+    let image = image::DynamicImage::new_rgba8(8, 8);
+    let descriptor = Descriptor::with_srgb_image(&image);
+
+    let mut commands = CommandBuffer::default();
+    let below = commands.input(descriptor.clone()).unwrap();
+    let above = commands.input(descriptor).unwrap();
+    commands
+        .perspective(below, perspective, above)
+        .expect("Valid to transform");
+
+    // A corner behind the horizon.
+    let mut horizon = Perspective::new(AffineSample::Nearest);
+    horizon.transformation[6] = -0.25;
+    commands
+        .perspective(below, horizon, above)
+        .expect_err("Can not be rendered as a quad");
+}
+
+#[test]
+fn warp_requires_scalar_displacement() {
+    let image = image::DynamicImage::new_rgba8(8, 8);
+    let rgb = Descriptor::with_srgb_image(&image);
+    let scalars = Descriptor {
+        texel: Texel {
+            color: Color::Scalars {
+                transfer: buffer::Transfer::Linear,
+            },
+            ..rgb.texel.clone()
+        },
+        ..rgb.clone()
+    };
+
+    let mut commands = CommandBuffer::default();
+    let src = commands.input(rgb.clone()).unwrap();
+    let displacement = commands.input(scalars).unwrap();
+    let warped = commands
+        .warp(src, displacement, Warp::new(4.0).with_neutral(0.5))
+        .expect("Valid to warp");
+    commands.output(warped).expect("Valid for output");
+
+    commands
+        .warp(src, src, Warp::new(4.0))
+        .expect_err("Colors are not displacements");
+
+    let _ = commands.compile().expect("Could build command buffer");
+}
+
+#[test]
+fn orientation_swaps_layout() {
+    let image = image::DynamicImage::new_rgba8(5, 3);
+    let descriptor = Descriptor::with_srgb_image(&image);
+
+    let mut commands = CommandBuffer::default();
+    let input = commands.input(descriptor).unwrap();
+
+    let rotated = commands.rotate90(input).unwrap();
+    assert_eq!(commands.describe_reg(rotated).unwrap().size(), (3, 5));
+    let transposed = commands.transpose(input).unwrap();
+    assert_eq!(commands.describe_reg(transposed).unwrap().size(), (3, 5));
+    let flipped = commands.rotate180(input).unwrap();
+    assert_eq!(commands.describe_reg(flipped).unwrap().size(), (5, 3));
+
+    commands.output(rotated).expect("Valid for output");
+    let _ = commands.compile().expect("Could build command buffer");
+}
+
+#[test]
+fn crop_and_pad_layouts() {
+    let image = image::DynamicImage::new_rgba8(8, 4);
+    let descriptor = Descriptor::with_srgb_image(&image);
+
+    let mut commands = CommandBuffer::default();
+    let input = commands.input(descriptor).unwrap();
+
+    let inside = commands
+        .crop(input, Rectangle::with_width_height(3, 2))
+        .unwrap();
+    assert_eq!(commands.describe_reg(inside).unwrap().size(), (3, 2));
+
+    let outside = Rectangle {
+        x: 6,
+        y: 2,
+        max_x: 10,
+        max_y: 7,
+    };
+    let outside = commands.crop(input, outside).unwrap();
+    assert_eq!(commands.describe_reg(outside).unwrap().size(), (4, 5));
+
+    let padded = commands
+        .pad(input, 1, 2, 3, 4, BorderMode::Reflect101)
+        .unwrap();
+    assert_eq!(commands.describe_reg(padded).unwrap().size(), (12, 10));
+
+    commands
+        .crop_with_border(input, -2, -2, 0, 4, BorderMode::Clamp)
+        .expect_err("Can not crop an empty rectangle");
+
+    commands.output(padded).expect("Valid for output");
+    let _ = commands.compile().expect("Could build command buffer");
+}
+
+#[test]
+fn gradient_has_two_channels() {
+    let image = image::DynamicImage::new_rgba8(8, 4);
+    let descriptor = Descriptor::with_srgb_image(&image);
+
+    let mut commands = CommandBuffer::default();
+    let input = commands.input(descriptor).unwrap();
+
+    commands
+        .gradient(input, DerivativeMethod::Roberts, GradientKind::Polar)
+        .expect_err("Roberts is not yet implemented");
+
+    let gradient = commands
+        .gradient(input, DerivativeMethod::Sobel, GradientKind::Cartesian)
+        .expect("Valid to calculate a gradient");
+    let desc = commands.describe_reg(gradient).unwrap();
+    assert_eq!(desc.size(), (8, 4));
+    assert_eq!(desc.texel.samples.parts, SampleParts::LumaA);
+    assert!(matches!(desc.texel.color, Color::Scalars { .. }));
+
+    commands.output(gradient).expect("Valid for output");
+    let _ = commands.compile().expect("Could build command buffer");
+}
+
+#[test]
+fn canny_is_binary_luma() {
+    let image = image::DynamicImage::new_rgba8(8, 4);
+    let descriptor = Descriptor::with_srgb_image(&image);
+
+    let mut commands = CommandBuffer::default();
+    let input = commands.input(descriptor).unwrap();
+
+    commands
+        .canny(input, 0.2, 0.1, DerivativeMethod::Sobel)
+        .expect_err("Thresholds are not ordered");
+    commands
+        .canny(input, 0.1, 0.2, DerivativeMethod::Scharr5)
+        .expect_err("Scharr5 is not yet implemented");
+
+    let edges = commands
+        .canny(input, 0.1, 0.2, DerivativeMethod::Sobel)
+        .expect("Valid to detect edges");
+    let desc = commands.describe_reg(edges).unwrap();
+    assert_eq!(desc.size(), (8, 4));
+    assert_eq!(desc.texel.samples.parts, SampleParts::Luma);
+
+    commands.output(edges).expect("Valid for output");
+    let _ = commands.compile().expect("Could build command buffer");
+}
+
+#[test]
+fn hough_accumulator_layouts() {
+    let image = image::DynamicImage::new_rgba8(16, 12);
+    let descriptor = Descriptor::with_srgb_image(&image);
+
+    let mut commands = CommandBuffer::default();
+    let input = commands.input(descriptor).unwrap();
+
+    commands
+        .hough(input, Hough::Lines { thetas: 0, rhos: 8 })
+        .expect_err("Accumulator must not be empty");
+    commands
+        .hough(input, Hough::Circles { radius: -1.0 })
+        .expect_err("Radius must be positive");
+
+    let lines = Hough::Lines {
+        thetas: 90,
+        rhos: 40,
+    };
+    let accumulator = commands.hough(input, lines).unwrap();
+    assert_eq!(commands.describe_reg(accumulator).unwrap().size(), (90, 40));
+
+    let circles = commands
+        .hough(input, Hough::Circles { radius: 3.0 })
+        .unwrap();
+    assert_eq!(commands.describe_reg(circles).unwrap().size(), (16, 12));
+
+    let peak = HoughPeak {
+        x: 45,
+        y: 19,
+        votes: 1.0,
+    };
+    let (rho, theta) = lines.line(16, 12, &peak).unwrap();
+    assert!((rho + 0.25).abs() < 1e-5);
+    assert!((theta - core::f32::consts::FRAC_PI_2).abs() < 1e-5);
+
+    commands.output(accumulator).expect("Valid for output");
+    let _ = commands.compile().expect("Could build command buffer");
+}
+
+#[test]
+fn ssim_requires_matching_images() {
+    let image = image::DynamicImage::new_rgba8(32, 16);
+    let small = image::DynamicImage::new_rgba8(8, 8);
+
+    let mut commands = CommandBuffer::default();
+    let lhs = commands.input(Descriptor::with_srgb_image(&image)).unwrap();
+    let rhs = commands.input(Descriptor::with_srgb_image(&image)).unwrap();
+    let small = commands.input(Descriptor::with_srgb_image(&small)).unwrap();
+
+    let err = commands
+        .ssim(lhs, small, Ssim::Single)
+        .expect_err("Sizes must match");
+    assert!(err.is_type_err());
+    commands
+        .ssim(small, small, Ssim::MultiScale)
+        .expect_err("Coarsest scale would be empty");
+
+    let single = commands.ssim(lhs, rhs, Ssim::Single).unwrap();
+    let multi = commands.ssim(lhs, rhs, Ssim::MultiScale).unwrap();
+    let desc = commands.describe_reg(multi).unwrap();
+    assert_eq!(desc.size(), (32, 16));
+    assert_eq!(desc.texel.samples.parts, SampleParts::Luma);
+
+    commands.output(single).expect("Valid for output");
+    commands.output(multi).expect("Valid for output");
+    let _ = commands.compile().expect("Could build command buffer");
+}
+
+#[test]
+fn perceptual_hash_is_8x8() {
+    let image = image::DynamicImage::new_rgba8(40, 30);
+    let descriptor = Descriptor::with_srgb_image(&image);
+
+    let mut commands = CommandBuffer::default();
+    let input = commands.input(descriptor).unwrap();
+
+    for &hash in [
+        PerceptualHash::Average,
+        PerceptualHash::Difference,
+        PerceptualHash::Dct,
+    ]
+    .iter()
+    {
+        let bits = commands.perceptual_hash(input, hash).unwrap();
+        let desc = commands.describe_reg(bits).unwrap();
+        assert_eq!(desc.size(), (8, 8));
+        assert_eq!(desc.texel.samples.parts, SampleParts::Luma);
+        commands.output(bits).expect("Valid for output");
+    }
+
+    let _ = commands.compile().expect("Could build command buffer");
+}
+
+#[test]
+fn histogram_is_a_row_of_counts() {
+    let image = image::DynamicImage::new_rgba8(40, 30);
+    let descriptor = Descriptor::with_srgb_image(&image);
+
+    let mut commands = CommandBuffer::default();
+    let input = commands.input(descriptor).unwrap();
+
+    for &channel in [ColorChannel::R, ColorChannel::Alpha, ColorChannel::Luma].iter() {
+        let counts = commands.histogram(input, channel, 256).unwrap();
+        let desc = commands.describe_reg(counts).unwrap();
+        assert_eq!(desc.size(), (256, 1));
+        assert_eq!(desc.texel, COUNT_TEXEL);
+        commands.output(counts).expect("Valid for output");
+    }
+
+    assert!(commands.histogram(input, ColorChannel::L, 256).is_err());
+    assert!(commands.histogram(input, ColorChannel::R, 0).is_err());
+    assert!(commands
+        .histogram(input, ColorChannel::R, shaders::histogram::MAX_BINS + 1)
+        .is_err());
+
+    let _ = commands.compile().expect("Could build command buffer");
+}
+
+#[test]
+fn equalize_keeps_the_descriptor() {
+    let image = image::DynamicImage::new_rgba8(40, 30);
+    let descriptor = Descriptor::with_srgb_image(&image);
+
+    let mut commands = CommandBuffer::default();
+    let input = commands.input(descriptor.clone()).unwrap();
+
+    for &equalization in [
+        Equalization::Global,
+        Equalization::Adaptive {
+            tiles: [8, 8],
+            clip_limit: 2.0,
+        },
+    ]
+    .iter()
+    {
+        let equalized = commands.equalize(input, equalization).unwrap();
+        assert_eq!(*commands.describe_reg(equalized).unwrap(), descriptor);
+        commands.output(equalized).expect("Valid for output");
+    }
+
+    for &(tiles, clip_limit) in
+        [([0, 8], 2.0), ([8, 33], 2.0), ([41, 8], 2.0), ([8, 8], 0.0)].iter()
+    {
+        let adaptive = Equalization::Adaptive { tiles, clip_limit };
+        assert!(commands.equalize(input, adaptive).is_err());
+    }
+
+    let _ = commands.compile().expect("Could build command buffer");
+}
+
+#[test]
+fn statistics_in_a_region() {
+    let image = image::DynamicImage::new_rgba8(40, 30);
+    let descriptor = Descriptor::with_srgb_image(&image);
+
+    let mut commands = CommandBuffer::default();
+    let input = commands.input(descriptor).unwrap();
+
+    let whole = commands.statistics(input, None).unwrap();
+    let region = Rectangle::with_width_height(20, 10);
+    let part = commands.statistics(input, Some(region)).unwrap();
+
+    for &reg in [whole, part].iter() {
+        let desc = commands.describe_reg(reg).unwrap();
+        assert_eq!(desc.size(), (4, 5));
+        assert_eq!(desc.texel, COUNT_TEXEL);
+        commands.output(reg).expect("Valid for output");
+    }
+
+    let outside = Rectangle::with_width_height(41, 10);
+    assert!(commands.statistics(input, Some(outside)).is_err());
+
+    let _ = commands.compile().expect("Could build command buffer");
+}
+
+#[test]
+fn noise_requires_gray_or_scalars() {
+    let gray = Descriptor::with_srgb_image(&image::DynamicImage::new_luma8(32, 32));
+    let rgba = Descriptor::with_srgb_image(&image::DynamicImage::new_rgba8(32, 32));
+    let scalars = Descriptor {
+        texel: Texel {
+            color: Color::Scalars {
+                transfer: buffer::Transfer::Linear,
+            },
+            ..rgba.texel.clone()
+        },
+        ..rgba.clone()
+    };
+
+    let noise = Noise::new(NoiseKind::Perlin, 42, 1.0 / 8.0).with_octaves(4);
+    let mut commands = CommandBuffer::default();
+
+    for desc in [gray.clone(), scalars].iter() {
+        let generated = commands.noise(desc.clone(), noise).unwrap();
+        assert_eq!(commands.describe_reg(generated).unwrap(), desc);
+        commands.output(generated).expect("Valid for output");
+    }
+
+    assert!(commands.noise(rgba, noise).is_err());
+    assert!(commands.noise(gray.clone(), noise.with_octaves(0)).is_err());
+    assert!(commands.noise(gray, noise.with_fbm(2.0, 0.0)).is_err());
+
+    let _ = commands.compile().expect("Could build command buffer");
+}
+
+#[test]
+fn white_and_blue_noise() {
+    let gray = Descriptor::with_srgb_image(&image::DynamicImage::new_luma8(32, 32));
+    let rgba = Descriptor::with_srgb_image(&image::DynamicImage::new_rgba8(32, 32));
+
+    let mut commands = CommandBuffer::default();
+    let uniform = commands
+        .white_noise(gray.clone(), WhiteNoise::Uniform, 0)
+        .unwrap();
+    let gaussian = WhiteNoise::Gaussian {
+        mean: 0.5,
+        std_dev: 0.1,
+    };
+    let normal = commands.white_noise(gray.clone(), gaussian, 1).unwrap();
+    let blue = commands.blue_noise(gray.clone(), 2).unwrap();
+
+    for &reg in [uniform, normal, blue].iter() {
+        assert_eq!(commands.describe_reg(reg).unwrap(), &gray);
+        commands.output(reg).expect("Valid for output");
+    }
+
+    // The noise is constructed as linear values and transmuted into the stored sRGB values.
+    let Register(blue) = blue;
+    assert!(matches!(
+        commands
+            .describe_reg(Register(blue - 1))
+            .unwrap()
+            .texel
+            .color,
+        Color::Rgb {
+            transfer: buffer::Transfer::Linear,
+            ..
+        }
+    ));
+
+    let negative = WhiteNoise::Gaussian {
+        mean: 0.5,
+        std_dev: -0.1,
+    };
+    assert!(commands.white_noise(gray, negative, 0).is_err());
+    assert!(commands.white_noise(rgba.clone(), gaussian, 0).is_err());
+    assert!(commands.blue_noise(rgba, 0).is_err());
+
+    let _ = commands.compile().expect("Could build command buffer");
+}
+
+#[test]
+fn blue_noise_mask_is_spread() {
+    use shaders::blue_noise::{Shader, SIZE};
+
+    let ranks = Shader::with_seed(0).ranks;
+    assert_eq!(ranks, Shader::with_seed(0).ranks);
+
+    let mut sorted = ranks.to_vec();
+    sorted.sort_unstable();
+    assert!(sorted
+        .iter()
+        .enumerate()
+        .all(|(idx, &rank)| usize::from(rank) == idx));
+
+    // The lowest ranks never touch each other, not even diagonally, unlike white noise.
+    let lowest: Vec<_> = (0..SIZE * SIZE)
+        .filter(|&idx| ranks[idx] < 256)
+        .map(|idx| (idx % SIZE, idx / SIZE))
+        .collect();
+    let wrapped = |a: usize, b: usize| {
+        let d = (a + SIZE - b) % SIZE;
+        d.min(SIZE - d)
+    };
+
+    for (i, &(ax, ay)) in lowest.iter().enumerate() {
+        for &(bx, by) in &lowest[i + 1..] {
+            assert!(wrapped(ax, bx).max(wrapped(ay, by)) > 1);
+        }
+    }
+}
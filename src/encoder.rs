@@ -183,6 +183,8 @@ struct SimpleRenderPipelineDescriptor<'data> {
     vertex_bind_data: BufferBind<'data>,
     /// Texture for (set 1, binding 0)
     fragment_texture: TextureBind,
+    /// The filter of the sampler bound with `fragment_texture` textures.
+    fragment_filter: wgpu::FilterMode,
     /// Texture for (set 2, binding 0)
     fragment_bind_data: BufferBind<'data>,
     /// The vertex shader to use.
@@ -911,7 +913,7 @@ impl<I: ExtendOne<Low>> Encoder<I> {
                     visibility: wgpu::ShaderStages::VERTEX,
                     ty: wgpu::BindingType::Buffer {
                         has_dynamic_offset: false,
-                        min_binding_size: NonZeroU64::new(64),
+                        min_binding_size: NonZeroU64::new(80),
                         ty: wgpu::BufferBindingType::Uniform,
                     },
                     count: None,
@@ -1251,7 +1253,11 @@ impl<I: ExtendOne<Low>> Encoder<I> {
             })
     }
 
-    fn make_bind_group_sampled_texture(&mut self, count: usize) -> Result<usize, LaunchError> {
+    fn make_bind_group_sampled_texture(
+        &mut self,
+        count: usize,
+        filter: wgpu::FilterMode,
+    ) -> Result<usize, LaunchError> {
         let start_of_operands = match self.operands.len().checked_sub(count) {
             None => return Err(LaunchError::InternalCommandError(line!())),
             Some(i) => i,
@@ -1260,7 +1266,7 @@ impl<I: ExtendOne<Low>> Encoder<I> {
         let sampler = self.make_sampler(SamplerDescriptor {
             address_mode: wgpu::AddressMode::default(),
             border_color: Some(wgpu::SamplerBorderColor::TransparentBlack),
-            resize_filter: wgpu::FilterMode::Nearest,
+            resize_filter: filter,
         });
 
         let mut entries = vec![BindingResource::Sampler(sampler)];
@@ -1399,7 +1405,8 @@ impl<I: ExtendOne<Low>> Encoder<I> {
         let group = match &descriptor.fragment_texture {
             TextureBind::Textures(0) => None,
             &TextureBind::Textures(count) => {
-                let group = self.make_bind_group_sampled_texture(count)?;
+                let group =
+                    self.make_bind_group_sampled_texture(count, descriptor.fragment_filter)?;
                 // eprintln!("Using Texture {:?} as group {:?}", texture, group);
                 Some(group)
             }
@@ -1427,7 +1434,7 @@ impl<I: ExtendOne<Low>> Encoder<I> {
     }
 
     #[rustfmt::skip]
-    pub const FULL_VERTEX_BUFFER: [[f32; 2]; 8] = [
+    pub const FULL_VERTEX_BUFFER: [[f32; 2]; 10] = [
         // [min_u, min_v], [0.0, 0.0],
         [0.0, 0.0],
         // [max_u, 0.0], [0.0, 0.0],
@@ -1441,6 +1448,10 @@ impl<I: ExtendOne<Low>> Encoder<I> {
         [1.0, 0.0],
         [1.0, 1.0],
         [0.0, 1.0],
+
+        // The homogeneous weights of all corners.
+        [1.0, 1.0],
+        [1.0, 1.0],
     ];
 
     pub(crate) fn render(&mut self, pipeline: SimpleRenderPipeline) -> Result<(), LaunchError> {
@@ -1514,7 +1525,7 @@ impl<I: ExtendOne<Low>> Encoder<I> {
 
                 let fragment = self.fragment_shader(key, shader_include_to_spirv_static(spirv))?;
 
-                let buffer: [[f32; 2]; 8];
+                let buffer: [[f32; 2]; 10];
                 // FIXME: there seems to be two floats padding after each vec2.
                 let min_u = (selection.x as f32) / (tex_width.get() as f32);
                 let max_u = (selection.max_x as f32) / (tex_width.get() as f32);
@@ -1522,6 +1533,7 @@ impl<I: ExtendOne<Low>> Encoder<I> {
                 let max_v = (selection.max_y as f32) / (tex_height.get() as f32);
 
                 let coords = target_coords.to_screenspace_coords(viewport);
+                let [w0, w1, w2, w3] = target_coords.homogeneous_weights();
 
                 // std430
                 buffer = [
@@ -1534,6 +1546,9 @@ impl<I: ExtendOne<Low>> Encoder<I> {
                     coords[1],
                     coords[2],
                     coords[3],
+
+                    [w0, w1],
+                    [w2, w3],
                 ];
 
                 self.prepare_simple_pipeline(SimpleRenderPipelineDescriptor{
@@ -1542,6 +1557,7 @@ impl<I: ExtendOne<Low>> Encoder<I> {
                         data: bytemuck::cast_slice(&buffer[..]),
                     },
                     fragment_texture: TextureBind::Textures(1),
+                    fragment_filter: shader.sampler_filter(),
                     fragment_bind_data: BufferBind::None,
                     vertex: ShaderBind::ShaderMain(vertex),
                     fragment: ShaderBind::ShaderMain(fragment),
@@ -1569,6 +1585,7 @@ impl<I: ExtendOne<Low>> Encoder<I> {
                         data: bytemuck::cast_slice(&Self::FULL_VERTEX_BUFFER[..]),
                     },
                     fragment_texture: TextureBind::Textures(arguments as usize),
+                    fragment_filter: shader.sampler_filter(),
                     fragment_bind_data,
                     vertex: ShaderBind::ShaderMain(vertex),
                     fragment: ShaderBind::ShaderMain(fragment),
@@ -1606,6 +1623,8 @@ impl<I: ExtendOne<Low>> Encoder<I> {
                         group,
                         layout,
                     },
+                    // Unused, the group has its own sampler.
+                    fragment_filter: wgpu::FilterMode::Nearest,
                     fragment_bind_data: BufferBind::Set {
                         data: bytemuck::cast_slice(&buffer[..]),
                     },
@@ -1653,6 +1672,8 @@ impl<I: ExtendOne<Low>> Encoder<I> {
                         group,
                         layout,
                     },
+                    // Unused, the group has its own sampler.
+                    fragment_filter: wgpu::FilterMode::Nearest,
                     fragment_bind_data: BufferBind::Set {
                         data: bytemuck::cast_slice(&buffer[..]),
                     },
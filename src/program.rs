@@ -108,6 +108,10 @@ pub(crate) enum Function {
 pub enum QuadTarget {
     Rect(Rectangle),
     Absolute([[f32; 2]; 4]),
+    /// Corners in homogeneous coordinates `[x, y, w]`, the position is `[x/w, y/w]`.
+    ///
+    /// All weights must be positive, i.e. the quad must lie entirely in front of the horizon.
+    Projective([[f32; 3]; 4]),
 }
 
 #[derive(Clone, Debug, Default)]
@@ -572,6 +576,17 @@ impl ImageBufferPlan {
         assigned
     }
 
+    /// Allocate a texture for a temporary result, which does not back any register.
+    ///
+    /// This is used by operations that render in multiple passes.
+    pub(crate) fn allocate_intermediate(&mut self, desc: &Descriptor) -> Texture {
+        // Keep textures and buffers in lock-step, their indices are used interchangeably.
+        let texture = Texture(self.texture.len());
+        self.texture.push(desc.clone());
+        self.buffer.push(desc.layout.clone());
+        texture
+    }
+
     pub(crate) fn get(&self, idx: Register) -> Result<ImageBufferAssignment, LaunchError> {
         self.by_register
             .get(idx.0)
@@ -934,6 +949,7 @@ impl Launcher<'_> {
         // Unbalanced operands shouldn't happen.
         // This is part of validation layer but cheap and we always do it.
         encoder.finalize()?;
+        let io_map = encoder.io_map();
 
         let init = run::InitialState {
             instructions: encoder.instructions.into(),
@@ -941,6 +957,7 @@ impl Launcher<'_> {
             queue,
             buffers,
             binary_data: encoder.binary_data,
+            io_map,
         };
 
         Ok(run::Execution::new(init))
@@ -982,6 +999,38 @@ impl QuadTarget {
         ])
     }
 
+    /// Transform the quad by a projective matrix, keeping the homogeneous coordinates.
+    ///
+    /// Returns `None` if the quad would be mapped across the horizon.
+    pub(crate) fn projective(&self, transform: &RowMatrix) -> Option<Self> {
+        let [a, b, c, d] = self.to_screenspace_coords(&Rectangle::with_width_height(1, 1));
+        let corner = |[x, y]: [f32; 2]| transform.multiply_column([x, y, 1.0]);
+        let mut corners = [corner(a), corner(b), corner(c), corner(d)];
+
+        // The matrix is only defined up to scaling, pick the sign with positive weights.
+        if corners.iter().all(|&[_, _, w]| w < 0.0) {
+            for [x, y, w] in corners.iter_mut() {
+                *x = -*x;
+                *y = -*y;
+                *w = -*w;
+            }
+        }
+
+        if corners.iter().all(|&[_, _, w]| w > 0.0) {
+            Some(QuadTarget::Projective(corners))
+        } else {
+            None
+        }
+    }
+
+    /// The homogeneous weight of each corner, in the same order as the coordinates.
+    pub(crate) fn homogeneous_weights(&self) -> [f32; 4] {
+        match self {
+            QuadTarget::Rect(_) | QuadTarget::Absolute(_) => [1.0; 4],
+            QuadTarget::Projective(coord) => [coord[0][2], coord[1][2], coord[2][2], coord[3][2]],
+        }
+    }
+
     pub(crate) fn to_screenspace_coords(&self, viewport: &Rectangle) -> [[f32; 2]; 4] {
         match self {
             QuadTarget::Rect(target) => {
@@ -1005,6 +1054,16 @@ impl QuadTarget {
                     ]
                 };
 
+                [xy(coord[0]), xy(coord[1]), xy(coord[2]), xy(coord[3])]
+            }
+            QuadTarget::Projective(coord) => {
+                let xy = |[cx, cy, cw]: [f32; 3]| {
+                    [
+                        (cx / cw - viewport.x as f32) / viewport.width() as f32,
+                        (cy / cw - viewport.y as f32) / viewport.height() as f32,
+                    ]
+                };
+
                 [xy(coord[0]), xy(coord[1]), xy(coord[2]), xy(coord[3])]
             }
         }
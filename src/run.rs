@@ -3,7 +3,7 @@ use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 use crate::buffer::{BufferLayout, Descriptor, Texel};
-use crate::command::Register;
+use crate::command::{self, Register};
 use crate::pool::{ImageData, Pool, PoolImage, PoolKey};
 use crate::program::{self, Capabilities, DeviceBuffer, DeviceTexture, Low};
 
@@ -60,6 +60,8 @@ pub struct Execution {
     pub(crate) command_encoder: Option<wgpu::CommandEncoder>,
     pub(crate) buffers: Vec<Image>,
     pub(crate) binary_data: Vec<u8>,
+    /// The map from registers to the index in image data.
+    pub(crate) io_map: IoMap,
 }
 
 pub(crate) struct InitialState {
@@ -68,6 +70,7 @@ pub(crate) struct InitialState {
     pub(crate) queue: Queue,
     pub(crate) buffers: Vec<Image>,
     pub(crate) binary_data: Vec<u8>,
+    pub(crate) io_map: IoMap,
 }
 
 /// An image owned by the execution state but compatible with extracting it.
@@ -82,7 +85,7 @@ pub(crate) struct Image {
     pub(crate) key: Option<PoolKey>,
 }
 
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub struct IoMap {
     pub(crate) inputs: HashMap<Register, usize>,
     pub(crate) outputs: HashMap<Register, usize>,
@@ -168,7 +171,19 @@ pub struct BadInstruction {
 }
 
 #[derive(Debug)]
-pub enum RetireError {}
+pub struct RetireError {
+    inner: RetireErrorKind,
+}
+
+#[derive(Debug)]
+enum RetireErrorKind {
+    /// The register does not name an output, or it had already been taken.
+    NoSuchOutput(Register),
+    /// The output is not accessible on the host.
+    NotOnHost(Register),
+    /// The output does not hold the expected numbers.
+    NotNumbers(Register),
+}
 
 impl Image {
     /// Create an image without binary data, promising to set it up later.
@@ -211,6 +226,7 @@ impl Executable {
             command_encoder: None,
             buffers: env.buffers,
             binary_data: self.binary_data.clone(),
+            io_map: self.io_map.clone(),
         })
     }
 
@@ -224,6 +240,7 @@ impl Executable {
             command_encoder: None,
             buffers: env.buffers,
             binary_data: self.binary_data,
+            io_map: self.io_map,
         })
     }
 
@@ -332,6 +349,7 @@ impl Execution {
             buffers: init.buffers,
             command_encoder: None,
             binary_data: init.binary_data,
+            io_map: init.io_map,
         }
     }
 
@@ -1228,22 +1246,90 @@ impl Retire<'_> {
     ///
     /// Return the image as viewed inside the pool. This is not arbitrary. See [`output_key`] for
     /// more details (WIP).
-    pub fn output(&mut self, _: Register) -> Result<PoolImage<'_>, RetireError> {
-        // FIXME: don't take some random image, use the right one..
-        // Also: should we leave the actual image? This would allow restarting the pipeline.
-        let image = self.execution.buffers.pop().unwrap();
+    pub fn output(&mut self, reg: Register) -> Result<PoolImage<'_>, RetireError> {
+        // FIXME: should we leave the actual image? This would allow restarting the pipeline.
+        let image = self.output_image(reg)?;
+        let layout = image.data.layout().clone();
+        let data = core::mem::replace(&mut image.data, ImageData::LateBound(layout.clone()));
 
         let descriptor = Descriptor {
-            layout: image.data.layout().clone(),
+            layout,
             texel: image.texel.clone(),
         };
 
         let mut pool_image = self.pool.declare(descriptor);
-        pool_image.replace(image.data);
+        pool_image.replace(data);
 
         Ok(pool_image.into())
     }
 
+    /// View an output holding counts, one `u32` for each of its pixels.
+    ///
+    /// This is the output kind of commands such as [`CommandBuffer::histogram`] which produce
+    /// numbers rather than an image. The counts stay with the execution, they are not moved into
+    /// the pool. Fails if the output had already been moved with [`output`].
+    ///
+    /// [`CommandBuffer::histogram`]: crate::command::CommandBuffer::histogram
+    pub fn counts(&mut self, reg: Register) -> Result<&[u32], RetireError> {
+        self.words(reg)
+    }
+
+    /// Read an output created by [`CommandBuffer::statistics`].
+    ///
+    /// Like [`counts`], this leaves the output with the execution.
+    ///
+    /// [`CommandBuffer::statistics`]: crate::command::CommandBuffer::statistics
+    pub fn statistics(&mut self, reg: Register) -> Result<command::Statistics, RetireError> {
+        let words = self.words(reg)?;
+        command::Statistics::from_words(words).ok_or(RetireError {
+            inner: RetireErrorKind::NotNumbers(reg),
+        })
+    }
+
+    /// View an output of [`command::COUNT_TEXEL`] as its `u32` words.
+    fn words(&mut self, reg: Register) -> Result<&[u32], RetireError> {
+        let image = self.output_image(reg)?;
+
+        if image.texel != command::COUNT_TEXEL {
+            return Err(RetireError {
+                inner: RetireErrorKind::NotNumbers(reg),
+            });
+        }
+
+        let not_on_host = || RetireError {
+            inner: RetireErrorKind::NotOnHost(reg),
+        };
+
+        let len = image.data.layout().width as usize * image.data.layout().height as usize;
+        let bytes = image.data.as_bytes().ok_or_else(not_on_host)?;
+        let words: &[u32] = bytemuck::try_cast_slice(bytes).map_err(|_| not_on_host())?;
+        words.get(..len).ok_or_else(not_on_host)
+    }
+
+    fn output_image(&mut self, reg: Register) -> Result<&mut Image, RetireError> {
+        let no_such_output = || RetireError {
+            inner: RetireErrorKind::NoSuchOutput(reg),
+        };
+
+        let &idx = self
+            .execution
+            .io_map
+            .outputs
+            .get(&reg)
+            .ok_or_else(no_such_output)?;
+        let image = self
+            .execution
+            .buffers
+            .get_mut(idx)
+            .ok_or_else(no_such_output)?;
+
+        if matches!(image.data, ImageData::LateBound(_)) {
+            return Err(no_such_output());
+        }
+
+        Ok(image)
+    }
+
     /// Determine the pool key that will be preferred when calling `output`.
     pub fn output_key(&self, _: Register) -> Result<PoolKey, RetireError> {
         todo!()
@@ -0,0 +1,94 @@
+#version 450
+#extension GL_GOOGLE_include_directive : require
+layout (location = 0) in vec2 uv;
+layout (location = 0) out vec4 f_color;
+
+layout (set = 1, binding = 0) uniform sampler texture_sampler;
+layout (set = 1, binding = 1) uniform texture2D in_texture;
+
+layout (set = 2, binding = 0) uniform Bilateral {
+    // Conversion of linear rgb to CIE XYZ, used for the Oklab distance.
+    mat3x3 xyz_transform;
+    // xyz: the weights of the luma in the linear color representation.
+    vec4 luma;
+    // x: the exponent factor of spatial weights, -1/(2·sigma²).
+    // y: the exponent factor of range weights, -1/(2·sigma_range²).
+    vec4 sigma;
+    // x: the radius of the window, at most MAX_RADIUS.
+    // y: how to measure the range distance, see the constants below.
+    uvec4 params;
+} u_bilateral;
+
+#include "border.glsl"
+
+// Must be kept in sync with `bilateral::MAX_RADIUS`.
+#define MAX_RADIUS 16
+
+// Must be kept in sync with `command::RangeDistance`.
+#define DISTANCE_LUMA 0u
+#define DISTANCE_LINEAR 1u
+#define DISTANCE_OKLAB 2u
+
+// The canonical Oklab matrices, but GLSL constructs matrices column-wise.
+const mat3x3 M1 = mat3x3(
+        +0.8189330101, +0.0329845436, +0.0482003018,
+        +0.3618667424, +0.9293118715, +0.2643662691,
+        -0.1288597137, +0.0361456387, +0.6338517070
+    );
+
+const mat3x3 M2 = mat3x3(
+        +0.2104542553, +1.9779984951, +0.0259040371,
+        +0.7936177850, -2.4285922050, +0.7827717662,
+        -0.0040720468, +0.4505937099, -0.8086757660
+    );
+
+// The coordinates in which the range distance is euclidean.
+vec3 range_coordinates(vec3 color) {
+    uint distance = u_bilateral.params.y;
+
+    if (distance == DISTANCE_LINEAR) {
+        return color;
+    } else if (distance == DISTANCE_OKLAB) {
+        vec3 lms = M1 * (u_bilateral.xyz_transform * color);
+        // We can't use pow outright for negative components.
+        return M2 * (pow(abs(lms), vec3(1.0 / 3.0)) * sign(lms));
+    } else {
+        return vec3(dot(color, u_bilateral.luma.xyz));
+    }
+}
+
+void main() {
+    ivec2 size = textureSize(sampler2D(in_texture, texture_sampler), 0);
+    ivec2 center = ivec2(floor(uv * vec2(size)));
+    int radius = min(int(u_bilateral.params.x), MAX_RADIUS);
+
+    vec4 center_texel = texelFetch(sampler2D(in_texture, texture_sampler), center, 0);
+    vec3 center_range = range_coordinates(center_texel.rgb);
+
+    vec4 sum = vec4(0.0);
+    float weights = 0.0;
+
+    for (int y = -radius; y <= radius; y++) {
+        for (int x = -radius; x <= radius; x++) {
+            ivec2 at = center + ivec2(x, y);
+            vec4 texel = fetch_border(in_texture, at, size, BORDER_CLAMP, vec4(0.0));
+
+            vec3 range = range_coordinates(texel.rgb) - center_range;
+            float exponent = u_bilateral.sigma.x * float(x * x + y * y)
+                + u_bilateral.sigma.y * dot(range, range);
+            float weight = exp(exponent);
+
+            sum += weight * vec4(texel.rgb * texel.a, texel.a);
+            weights += weight;
+        }
+    }
+
+    // The center pixel always contributes, with weight one.
+    float alpha = sum.a / weights;
+
+    if (sum.a > 0.0) {
+        f_color = vec4(sum.rgb / sum.a, alpha);
+    } else {
+        f_color = vec4(0.0);
+    }
+}
@@ -0,0 +1,59 @@
+use std::borrow::Cow;
+
+use super::{BufferInitContent, FragmentShaderData, FragmentShaderKey};
+use crate::buffer::RowMatrix;
+
+/// Smooth an image while preserving edges, weighting pixels by distance and similarity.
+pub const SHADER: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/spirv/bilateral.frag.v"));
+
+/// The largest radius of the window supported by the shader.
+pub(crate) const MAX_RADIUS: u32 = 16;
+
+/// The bilateral filter shader.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Shader {
+    /// Conversion from the linear color representation to CIE XYZ, for Oklab distances.
+    pub(crate) xyz_transform: RowMatrix,
+    /// The weights computing luma from the linear color representation.
+    pub(crate) luma: [f32; 3],
+    /// The standard deviation of spatial weights, in pixels.
+    pub(crate) sigma: f32,
+    /// The standard deviation of range weights.
+    pub(crate) range_sigma: f32,
+    /// The number of pixels considered on each side of the center, at most `MAX_RADIUS`.
+    pub(crate) radius: u32,
+    /// How to measure range distances, as defined in the shader source.
+    pub(crate) distance: u32,
+}
+
+impl FragmentShaderData for Shader {
+    fn key(&self) -> Option<FragmentShaderKey> {
+        Some(FragmentShaderKey::Bilateral)
+    }
+
+    fn spirv_source(&self) -> Cow<'static, [u8]> {
+        Cow::Borrowed(SHADER)
+    }
+
+    fn binary_data(&self, buffer: &mut Vec<u8>) -> Option<BufferInitContent> {
+        let matrix: [f32; 12] = self.xyz_transform.into_mat3x3_std140();
+        let [r, g, b] = self.luma;
+        let spatial = -0.5 / (self.sigma * self.sigma);
+        let range = -0.5 / (self.range_sigma * self.range_sigma);
+
+        let mut data = [0u32; 24];
+        for (slot, value) in data.iter_mut().zip(&matrix) {
+            *slot = value.to_bits();
+        }
+
+        data[12..16].copy_from_slice(&[r.to_bits(), g.to_bits(), b.to_bits(), 0]);
+        data[16..20].copy_from_slice(&[spatial.to_bits(), range.to_bits(), 0, 0]);
+        data[20..24].copy_from_slice(&[self.radius, self.distance, 0, 0]);
+
+        Some(BufferInitContent::new(buffer, &data))
+    }
+
+    fn num_args(&self) -> u32 {
+        1
+    }
+}
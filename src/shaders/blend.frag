@@ -0,0 +1,46 @@
+#version 450
+layout (location = 0) in vec2 uv;
+layout (location = 0) out vec4 f_color;
+
+// The image below (destination) and the one above (source).
+layout (set = 1, binding = 0) uniform sampler texture_sampler;
+layout (set = 1, binding = 1) uniform texture2D lhs;
+layout (set = 1, binding = 2) uniform texture2D rhs;
+
+layout (set = 2, binding = 0) uniform FragmentPushConstants {
+    // The Porter-Duff weights of source and destination, as affine functions
+    // of the respective other alpha:
+    //   Fa = weights.x + weights.y * alpha_below
+    //   Fb = weights.z + weights.w * alpha_above
+    vec4 weights;
+    // The rectangle covered by the image above, in uv coordinates of below.
+    // This is (min_u, min_v, max_u, max_v).
+    vec4 placement;
+} u_blend;
+
+void main() {
+    vec4 below = texture(sampler2D(lhs, texture_sampler), uv);
+    vec4 above = vec4(0.0);
+
+    vec4 p = u_blend.placement;
+    // Outside the placement the source is fully transparent.
+    if (all(greaterThanEqual(uv, p.xy)) && all(lessThan(uv, p.zw))) {
+        vec2 above_uv = (uv - p.xy) / (p.zw - p.xy);
+        above = texture(sampler2D(rhs, texture_sampler), above_uv);
+    }
+
+    float fa = u_blend.weights.x + u_blend.weights.y * below.a;
+    float fb = u_blend.weights.z + u_blend.weights.w * above.a;
+
+    // Our textures hold straight alpha, compositing works on premultiplied.
+    vec3 color = above.rgb * above.a * fa + below.rgb * below.a * fb;
+    float alpha = clamp(above.a * fa + below.a * fb, 0.0, 1.0);
+
+    if (alpha > 0.0) {
+        color = color / alpha;
+    } else {
+        color = vec3(0.0);
+    }
+
+    f_color = vec4(color, alpha);
+}
@@ -0,0 +1,35 @@
+use std::borrow::Cow;
+
+use super::{BufferInitContent, FragmentShaderData, FragmentShaderKey};
+
+/// Composite two images with one of the Porter-Duff operators.
+pub const SHADER: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/spirv/blend.frag.v"));
+
+/// The blend shader, mixing an image into the region of another.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Shader {
+    /// The Porter-Duff weights `[a, b, c, d]` where the source is multiplied by `a + b·αb` and
+    /// the destination by `c + d·αa`.
+    pub(crate) weights: [f32; 4],
+    /// Where the upper image is placed, as `[min_u, min_v, max_u, max_v]` of the lower image.
+    pub(crate) placement: [f32; 4],
+}
+
+impl FragmentShaderData for Shader {
+    fn key(&self) -> Option<FragmentShaderKey> {
+        Some(FragmentShaderKey::Blend)
+    }
+
+    fn spirv_source(&self) -> Cow<'static, [u8]> {
+        Cow::Borrowed(SHADER)
+    }
+
+    fn binary_data(&self, buffer: &mut Vec<u8>) -> Option<BufferInitContent> {
+        let data = [self.weights, self.placement];
+        Some(BufferInitContent::new(buffer, &data))
+    }
+
+    fn num_args(&self) -> u32 {
+        2
+    }
+}
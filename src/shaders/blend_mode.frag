@@ -0,0 +1,193 @@
+#version 450
+layout (location = 0) in vec2 uv;
+layout (location = 0) out vec4 f_color;
+
+// The image below (backdrop) and the one above (source).
+layout (set = 1, binding = 0) uniform sampler texture_sampler;
+layout (set = 1, binding = 1) uniform texture2D lhs;
+layout (set = 1, binding = 2) uniform texture2D rhs;
+
+layout (set = 2, binding = 0) uniform FragmentPushConstants {
+    // The rectangle covered by the image above, in uv coordinates of below.
+    // This is (min_u, min_v, max_u, max_v).
+    vec4 placement;
+    // x: the blend mode, see the constants below.
+    uvec4 mode;
+} u_blend;
+
+// Must be kept in sync with `command::Blend`.
+#define MULTIPLY 0u
+#define SCREEN 1u
+#define OVERLAY 2u
+#define DARKEN 3u
+#define LIGHTEN 4u
+#define COLOR_DODGE 5u
+#define COLOR_BURN 6u
+#define HARD_LIGHT 7u
+#define SOFT_LIGHT 8u
+#define DIFFERENCE 9u
+#define EXCLUSION 10u
+#define HUE 11u
+#define SATURATION 12u
+#define COLOR 13u
+#define LUMINOSITY 14u
+
+vec3 multiply(vec3 cb, vec3 cs) {
+    return cb * cs;
+}
+
+vec3 screen(vec3 cb, vec3 cs) {
+    return cb + cs - cb * cs;
+}
+
+vec3 hard_light(vec3 cb, vec3 cs) {
+    return mix(
+        screen(cb, 2.0 * cs - 1.0),
+        multiply(cb, 2.0 * cs),
+        lessThanEqual(cs, vec3(0.5)));
+}
+
+float color_dodge(float cb, float cs) {
+    if (cb <= 0.0) {
+        return 0.0;
+    } else if (cs >= 1.0) {
+        return 1.0;
+    } else {
+        return min(1.0, cb / (1.0 - cs));
+    }
+}
+
+float color_burn(float cb, float cs) {
+    if (cb >= 1.0) {
+        return 1.0;
+    } else if (cs <= 0.0) {
+        return 0.0;
+    } else {
+        return 1.0 - min(1.0, (1.0 - cb) / cs);
+    }
+}
+
+float soft_light(float cb, float cs) {
+    if (cs <= 0.5) {
+        return cb - (1.0 - 2.0 * cs) * cb * (1.0 - cb);
+    }
+
+    float d = cb <= 0.25 ? ((16.0 * cb - 12.0) * cb + 4.0) * cb : sqrt(cb);
+    return cb + (2.0 * cs - 1.0) * (d - cb);
+}
+
+float lum(vec3 c) {
+    return dot(c, vec3(0.3, 0.59, 0.11));
+}
+
+vec3 clip_color(vec3 c) {
+    float l = lum(c);
+    float n = min(c.r, min(c.g, c.b));
+    float x = max(c.r, max(c.g, c.b));
+
+    if (n < 0.0 && l > n) {
+        c = l + (c - l) * l / (l - n);
+    }
+
+    if (x > 1.0 && x > l) {
+        c = l + (c - l) * (1.0 - l) / (x - l);
+    }
+
+    return c;
+}
+
+vec3 set_lum(vec3 c, float l) {
+    return clip_color(c + (l - lum(c)));
+}
+
+float sat(vec3 c) {
+    return max(c.r, max(c.g, c.b)) - min(c.r, min(c.g, c.b));
+}
+
+vec3 set_sat(vec3 c, float s) {
+    float cmax = max(c.r, max(c.g, c.b));
+    float cmin = min(c.r, min(c.g, c.b));
+
+    // Maps the minimum to 0, the maximum to s and the middle proportionally.
+    if (cmax > cmin) {
+        return (c - cmin) * s / (cmax - cmin);
+    } else {
+        return vec3(0.0);
+    }
+}
+
+vec3 blend(uint mode, vec3 cb, vec3 cs) {
+    switch (mode) {
+    case MULTIPLY:
+        return multiply(cb, cs);
+    case SCREEN:
+        return screen(cb, cs);
+    case OVERLAY:
+        return hard_light(cs, cb);
+    case DARKEN:
+        return min(cb, cs);
+    case LIGHTEN:
+        return max(cb, cs);
+    case COLOR_DODGE:
+        return vec3(
+            color_dodge(cb.r, cs.r),
+            color_dodge(cb.g, cs.g),
+            color_dodge(cb.b, cs.b));
+    case COLOR_BURN:
+        return vec3(
+            color_burn(cb.r, cs.r),
+            color_burn(cb.g, cs.g),
+            color_burn(cb.b, cs.b));
+    case HARD_LIGHT:
+        return hard_light(cb, cs);
+    case SOFT_LIGHT:
+        return vec3(
+            soft_light(cb.r, cs.r),
+            soft_light(cb.g, cs.g),
+            soft_light(cb.b, cs.b));
+    case DIFFERENCE:
+        return abs(cb - cs);
+    case EXCLUSION:
+        return cb + cs - 2.0 * cb * cs;
+    case HUE:
+        return set_lum(set_sat(cs, sat(cb)), lum(cb));
+    case SATURATION:
+        return set_lum(set_sat(cb, sat(cs)), lum(cb));
+    case COLOR:
+        return set_lum(cs, lum(cb));
+    case LUMINOSITY:
+        return set_lum(cb, lum(cs));
+    default:
+        return cs;
+    }
+}
+
+void main() {
+    vec4 below = texture(sampler2D(lhs, texture_sampler), uv);
+    vec4 above = vec4(0.0);
+
+    vec4 p = u_blend.placement;
+    // Outside the placement the source is fully transparent.
+    if (all(greaterThanEqual(uv, p.xy)) && all(lessThan(uv, p.zw))) {
+        vec2 above_uv = (uv - p.xy) / (p.zw - p.xy);
+        above = texture(sampler2D(rhs, texture_sampler), above_uv);
+    }
+
+    vec3 cb = below.rgb;
+    vec3 cs = above.rgb;
+    vec3 mixed = blend(u_blend.mode.x, cb, cs);
+
+    // The W3C compositing model: the blend result replaces the source where
+    // the backdrop is opaque, and then we composite with source-over.
+    vec3 source = mix(cs, mixed, below.a);
+    vec3 color = source * above.a + cb * below.a * (1.0 - above.a);
+    float alpha = above.a + below.a * (1.0 - above.a);
+
+    if (alpha > 0.0) {
+        color = color / alpha;
+    } else {
+        color = vec3(0.0);
+    }
+
+    f_color = vec4(color, alpha);
+}
@@ -0,0 +1,45 @@
+use std::borrow::Cow;
+
+use super::{BufferInitContent, FragmentShaderData, FragmentShaderKey};
+
+/// Blend two images with one of the W3C separable or non-separable blend modes.
+pub const SHADER: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/spirv/blend_mode.frag.v"));
+
+/// The blend mode shader, mixing an RGB image into the region of another.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Shader {
+    /// The index of the mode, as defined in the shader source.
+    pub(crate) mode: u32,
+    /// Where the upper image is placed, as `[min_u, min_v, max_u, max_v]` of the lower image.
+    pub(crate) placement: [f32; 4],
+}
+
+impl FragmentShaderData for Shader {
+    fn key(&self) -> Option<FragmentShaderKey> {
+        Some(FragmentShaderKey::BlendMode)
+    }
+
+    fn spirv_source(&self) -> Cow<'static, [u8]> {
+        Cow::Borrowed(SHADER)
+    }
+
+    fn binary_data(&self, buffer: &mut Vec<u8>) -> Option<BufferInitContent> {
+        let [x, y, max_x, max_y] = self.placement;
+        let data: [u32; 8] = [
+            x.to_bits(),
+            y.to_bits(),
+            max_x.to_bits(),
+            max_y.to_bits(),
+            self.mode,
+            0,
+            0,
+            0,
+        ];
+
+        Some(BufferInitContent::new(buffer, &data))
+    }
+
+    fn num_args(&self) -> u32 {
+        2
+    }
+}
@@ -0,0 +1,24 @@
+#version 450
+layout (location = 0) in vec2 uv;
+layout (location = 0) out vec4 f_color;
+
+// Must be kept in sync with `shaders::blue_noise::SIZE`.
+#define SIZE 64u
+
+layout (set = 1, binding = 0) uniform BlueNoise {
+    // The rank of each pixel of the mask in row-major order, two in each word with the first in
+    // the lower half.
+    uvec4 ranks[SIZE * SIZE / 8u];
+} u_blueNoise;
+
+void main() {
+    uvec2 coord = uvec2(gl_FragCoord.xy) % SIZE;
+    uint idx = coord.y * SIZE + coord.x;
+
+    uint word = u_blueNoise.ranks[idx / 8u][(idx / 2u) % 4u];
+    uint rank = (word >> (16u * (idx % 2u))) & 0xffffu;
+
+    // The ranks are uniformly distributed, each value is the center of its own interval.
+    float value = (float(rank) + 0.5) / float(SIZE * SIZE);
+    f_color = vec4(vec3(value), 1.0);
+}
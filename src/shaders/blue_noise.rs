@@ -0,0 +1,173 @@
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use super::{BufferInitContent, FragmentShaderData, FragmentShaderKey};
+
+/// Repeat a mask of ranks over the image.
+pub const SHADER: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/spirv/blue_noise.frag.v"));
+
+/// The width and height of the mask, it is repeated periodically.
+pub(crate) const SIZE: usize = 64;
+
+const PIXELS: usize = SIZE * SIZE;
+
+/// The standard deviation of the energy filter, in pixels, as recommended by Ulichney.
+const SIGMA: f64 = 1.5;
+
+/// The blue noise shader, with a precomputed mask.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Shader {
+    /// The rank of each pixel of the mask, in row-major order.
+    pub(crate) ranks: Arc<[u16]>,
+}
+
+impl Shader {
+    pub(crate) fn with_seed(seed: u32) -> Self {
+        Shader {
+            ranks: void_and_cluster(seed).into(),
+        }
+    }
+}
+
+/// A binary pattern and the filtered energy of its set pixels, on a torus.
+#[derive(Clone)]
+struct Pattern<'kernel> {
+    kernel: &'kernel [f64],
+    set: Vec<bool>,
+    energy: Vec<f64>,
+}
+
+impl Pattern<'_> {
+    fn toggle(&mut self, idx: usize) {
+        let sign = if self.set[idx] { -1.0 } else { 1.0 };
+        self.set[idx] = !self.set[idx];
+
+        let (px, py) = (idx % SIZE, idx / SIZE);
+        for y in 0..SIZE {
+            let ky = (y + SIZE - py) % SIZE;
+            for x in 0..SIZE {
+                let kx = (x + SIZE - px) % SIZE;
+                self.energy[y * SIZE + x] += sign * self.kernel[ky * SIZE + kx];
+            }
+        }
+    }
+
+    /// The set pixel with the highest energy, the first one on ties.
+    fn tightest_cluster(&self) -> usize {
+        self.extreme(true, |a, b| a > b)
+    }
+
+    /// The unset pixel with the lowest energy, the first one on ties.
+    fn largest_void(&self) -> usize {
+        self.extreme(false, |a, b| a < b)
+    }
+
+    fn extreme(&self, set: bool, better: impl Fn(f64, f64) -> bool) -> usize {
+        let mut candidates = (0..PIXELS).filter(|&idx| self.set[idx] == set);
+        let first = candidates
+            .next()
+            .expect("Pattern is neither empty nor full");
+        candidates.fold(first, |best, idx| {
+            if better(self.energy[idx], self.energy[best]) {
+                idx
+            } else {
+                best
+            }
+        })
+    }
+}
+
+/// The PCG hash, the same as in the noise shaders.
+fn pcg(v: u32) -> u32 {
+    let state = v.wrapping_mul(747796405).wrapping_add(2891336453);
+    let word = ((state >> ((state >> 28) + 4)) ^ state).wrapping_mul(277803737);
+    (word >> 22) ^ word
+}
+
+/// Rank all pixels of the mask.
+///
+/// Reference: Ulichney, The void-and-cluster method for dither array generation, 1993
+fn void_and_cluster(seed: u32) -> Vec<u16> {
+    let kernel: Vec<f64> = (0..PIXELS)
+        .map(|idx| {
+            let wrap = |d: usize| d.min(SIZE - d) as f64;
+            let (dx, dy) = (wrap(idx % SIZE), wrap(idx / SIZE));
+            (-(dx * dx + dy * dy) / (2.0 * SIGMA * SIGMA)).exp()
+        })
+        .collect();
+
+    let mut pattern = Pattern {
+        kernel: &kernel,
+        set: vec![false; PIXELS],
+        energy: vec![0.0; PIXELS],
+    };
+
+    // A random initial pattern, with a tenth of all pixels set.
+    let initial = PIXELS / 10;
+    let (mut placed, mut counter) = (0, 0);
+    while placed < initial {
+        let idx = pcg(seed.wrapping_add(pcg(counter))) as usize % PIXELS;
+        if !pattern.set[idx] {
+            pattern.toggle(idx);
+            placed += 1;
+        }
+        counter += 1;
+    }
+
+    // Move the tightest cluster into the largest void until that no longer changes anything.
+    for _ in 0..PIXELS {
+        let cluster = pattern.tightest_cluster();
+        pattern.toggle(cluster);
+        let void = pattern.largest_void();
+        pattern.toggle(void);
+
+        if void == cluster {
+            break;
+        }
+    }
+
+    let mut ranks = vec![0u16; PIXELS];
+
+    // Rank the initial pattern by removing its tightest clusters.
+    let mut removed = pattern.clone();
+    for rank in (0..initial).rev() {
+        let cluster = removed.tightest_cluster();
+        removed.toggle(cluster);
+        ranks[cluster] = rank as u16;
+    }
+
+    // Rank all other pixels by filling the largest voids. Since the kernel sums to the same
+    // energy everywhere, this is also the tightest cluster of unset pixels once they are the
+    // minority.
+    for rank in initial..PIXELS {
+        let void = pattern.largest_void();
+        pattern.toggle(void);
+        ranks[void] = rank as u16;
+    }
+
+    ranks
+}
+
+impl FragmentShaderData for Shader {
+    fn key(&self) -> Option<FragmentShaderKey> {
+        Some(FragmentShaderKey::BlueNoise)
+    }
+
+    fn spirv_source(&self) -> Cow<'static, [u8]> {
+        Cow::Borrowed(SHADER)
+    }
+
+    fn binary_data(&self, buffer: &mut Vec<u8>) -> Option<BufferInitContent> {
+        // Two ranks in each word, the first in the lower half.
+        let mut data = [0u32; PIXELS / 2];
+        for (word, pair) in data.iter_mut().zip(self.ranks.chunks(2)) {
+            *word = u32::from(pair[0]) | (u32::from(pair[1]) << 16);
+        }
+
+        Some(BufferInitContent::new(buffer, &data[..]))
+    }
+
+    fn num_args(&self) -> u32 {
+        0
+    }
+}
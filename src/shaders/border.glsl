@@ -0,0 +1,45 @@
+/* Pixels outside of an image, addressed by integer pixel indices.
+ *
+ * The including shader must declare `texture_sampler` first.
+ */
+
+// Must be kept in sync with `command::BorderMode`.
+#define BORDER_CLAMP 0u
+#define BORDER_MIRROR 1u
+#define BORDER_WRAP 2u
+#define BORDER_CONSTANT 3u
+#define BORDER_REFLECT101 4u
+
+// A modulo that is also defined for negative numbers.
+int modulo(int x, int n) {
+    return x - n * int(floor(float(x) / float(n)));
+}
+
+int border_coordinate(int x, int size, uint mode) {
+    if (mode == BORDER_WRAP) {
+        return modulo(x, size);
+    } else if (mode == BORDER_MIRROR) {
+        int m = modulo(x, 2 * size);
+        return m < size ? m : 2 * size - 1 - m;
+    } else if (mode == BORDER_REFLECT101) {
+        // The edge pixels are not repeated, which shortens the period by two.
+        int period = max(2 * size - 2, 1);
+        int m = modulo(x, period);
+        return m < size ? m : period - m;
+    } else {
+        return clamp(x, 0, size - 1);
+    }
+}
+
+/* The pixel of an image at an index, or the border color outside of a constant border. */
+vec4 fetch_border(texture2D image, ivec2 at, ivec2 size, uint mode, vec4 border_color) {
+    if (mode == BORDER_CONSTANT
+        && (any(lessThan(at, ivec2(0))) || any(greaterThanEqual(at, size)))) {
+        return border_color;
+    }
+
+    ivec2 pixel = ivec2(
+        border_coordinate(at.x, size.x, mode),
+        border_coordinate(at.y, size.y, mode));
+    return texelFetch(sampler2D(image, texture_sampler), pixel, 0);
+}
@@ -16,6 +16,8 @@ layout (set = 0, binding = 0, std430) uniform PaintCoordinates {
   vec2 rect_position1;
   vec2 rect_position2;
   vec2 rect_position3;
+  /* The homogeneous weight of each position, all 1.0 unless it is a projection. */
+  vec4 rect_weights;
 } paint_coordinates;
 
 // HACK(naga-1400)
@@ -52,8 +54,15 @@ void main() {
     mix(rect_position(3), rect_position(2), vertPosition.x),
     vertPosition.y);
 
+  vec4 w = paint_coordinates.rect_weights;
+  float innerWeight = mix(
+    mix(w.x, w.y, vertPosition.x),
+    mix(w.w, w.z, vertPosition.x),
+    vertPosition.y);
+
   vec2 glslPos = 2.0*vec2(innerPos.x, 1.0 - innerPos.y) - 1.0;
 
-  gl_Position = vec4(glslPos, 0.0, 1.0);
+  // Scaling by the weight makes the interpolation of uv perspective-correct.
+  gl_Position = vec4(glslPos * innerWeight, 0.0, innerWeight);
   uv = innerUv;
 }
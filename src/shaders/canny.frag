@@ -0,0 +1,115 @@
+#version 450
+#extension GL_GOOGLE_include_directive : require
+layout (location = 0) in vec2 uv;
+layout (location = 0) out vec4 f_color;
+
+layout (set = 1, binding = 0) uniform sampler texture_sampler;
+layout (set = 1, binding = 1) uniform texture2D in_texture;
+
+layout (set = 2, binding = 0) uniform Canny {
+    // x: the low threshold, y: the high threshold of the gradient magnitude.
+    vec4 thresholds;
+    // x: the pass, see the constants below.
+    uvec4 mode;
+} u_canny;
+
+#include "border.glsl"
+
+// Must be kept in sync with `canny::Pass`.
+#define PASS_SUPPRESS 0u
+#define PASS_PROPAGATE 1u
+#define PASS_FINISH 2u
+
+// The classification of pixels between passes.
+#define NONE 0.0
+#define WEAK 0.5
+#define STRONG 1.0
+
+#define PI 3.1415926535897932384626433832795
+
+ivec2 size;
+
+vec4 fetch(ivec2 pixel) {
+    return fetch_border(in_texture, pixel, size, BORDER_CLAMP, vec4(0.0));
+}
+
+bool is_strong(float label) {
+    return label > 0.75;
+}
+
+bool is_weak(float label) {
+    return label > 0.25 && label <= 0.75;
+}
+
+// Thin edges to the local maxima across their direction, then classify them.
+// The input is a polar gradient, the magnitude in `r` and the direction in `a`.
+float suppress(ivec2 pixel) {
+    vec4 gradient = fetch(pixel);
+    float magnitude = gradient.r;
+
+    if (magnitude < u_canny.thresholds.x || magnitude <= 0.0) {
+        return NONE;
+    }
+
+    // Quantize the direction to one of the four axes through neighboring pixels.
+    float angle = (gradient.a - 0.5) * 2.0 * PI;
+    int sector = int(round(angle * 4.0 / PI)) & 3;
+    ivec2 across = ivec2(1, 0);
+    if (sector == 1) {
+        across = ivec2(1, 1);
+    } else if (sector == 2) {
+        across = ivec2(0, 1);
+    } else if (sector == 3) {
+        across = ivec2(-1, 1);
+    }
+
+    // Ties are broken towards one side, such that plateaus still keep a line.
+    float before = fetch(pixel - across).r;
+    float after = fetch(pixel + across).r;
+    if (magnitude < before || magnitude <= after) {
+        return NONE;
+    }
+
+    return magnitude >= u_canny.thresholds.y ? STRONG : WEAK;
+}
+
+// Promote weak pixels next to a strong one, one pixel further per pass.
+float propagate(ivec2 pixel) {
+    float label = fetch(pixel).r;
+
+    if (!is_weak(label)) {
+        return label;
+    }
+
+    for (int y = -1; y <= 1; y++) {
+        for (int x = -1; x <= 1; x++) {
+            if (is_strong(fetch(pixel + ivec2(x, y)).r)) {
+                return STRONG;
+            }
+        }
+    }
+
+    return WEAK;
+}
+
+void main() {
+    size = textureSize(sampler2D(in_texture, texture_sampler), 0);
+    ivec2 pixel = ivec2(gl_FragCoord.xy);
+
+    float label;
+    switch (u_canny.mode.x) {
+    case PASS_SUPPRESS:
+        label = suppress(pixel);
+        break;
+    case PASS_PROPAGATE:
+        label = propagate(pixel);
+        break;
+    case PASS_FINISH:
+    default:
+        // Weak pixels not connected to any strong one are dropped.
+        label = is_strong(fetch(pixel).r) ? STRONG : NONE;
+        break;
+    }
+
+    f_color = vec4(vec3(label), 1.0);
+}
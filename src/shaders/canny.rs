@@ -0,0 +1,63 @@
+use std::borrow::Cow;
+
+use super::{BufferInitContent, FragmentShaderData, FragmentShaderKey};
+
+/// The edge thinning and hysteresis passes of the Canny edge detector.
+pub const SHADER: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/spirv/canny.frag.v"));
+
+/// The number of passes propagating strong edges into connected weak ones.
+///
+/// Each pass extends edges by one pixel, weak pixels further away from any strong one than this
+/// are not detected.
+pub(crate) const HYSTERESIS_PASSES: u32 = 32;
+
+/// One of the passes after the gradient has been computed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Pass {
+    /// Non-maximum suppression of a polar gradient and classification by the thresholds.
+    Suppress = 0,
+    /// Promote weak pixels neighboring a strong pixel.
+    Propagate = 1,
+    /// Output strong pixels as `1.0`, all others as `0.0`.
+    Finish = 2,
+}
+
+/// The Canny shader, a single pass of the detector.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Shader {
+    /// The pass to perform.
+    pub(crate) pass: Pass,
+    /// The gradient magnitude below which a pixel is never an edge.
+    pub(crate) low: f32,
+    /// The gradient magnitude above which a pixel is always an edge.
+    pub(crate) high: f32,
+}
+
+impl FragmentShaderData for Shader {
+    fn key(&self) -> Option<FragmentShaderKey> {
+        Some(FragmentShaderKey::Canny)
+    }
+
+    fn spirv_source(&self) -> Cow<'static, [u8]> {
+        Cow::Borrowed(SHADER)
+    }
+
+    fn binary_data(&self, buffer: &mut Vec<u8>) -> Option<BufferInitContent> {
+        let data: [u32; 8] = [
+            self.low.to_bits(),
+            self.high.to_bits(),
+            0,
+            0,
+            self.pass as u32,
+            0,
+            0,
+            0,
+        ];
+
+        Some(BufferInitContent::new(buffer, &data))
+    }
+
+    fn num_args(&self) -> u32 {
+        1
+    }
+}
@@ -0,0 +1,44 @@
+#version 450
+#extension GL_GOOGLE_include_directive : require
+layout (location = 0) in vec2 uv;
+layout (location = 0) out vec4 f_color;
+
+layout (set = 1, binding = 0) uniform sampler texture_sampler;
+layout (set = 1, binding = 1) uniform texture2D in_texture;
+
+layout (set = 2, binding = 0) uniform Convolve {
+    // x: the width, y: the height of the kernel.
+    // zw: the column and row of the weight on the center pixel.
+    uvec4 size;
+    // x: the border mode, see `border.glsl`.
+    uvec4 border;
+    // The color of pixels outside the image, with a constant border.
+    vec4 border_color;
+    // The weights in row-major order, packed four per entry.
+    vec4 weights[64];
+} u_kernel;
+
+#include "border.glsl"
+
+vec4 fetch(ivec2 at, ivec2 size) {
+    return fetch_border(in_texture, at, size, u_kernel.border.x, u_kernel.border_color);
+}
+
+void main() {
+    ivec2 size = textureSize(sampler2D(in_texture, texture_sampler), 0);
+    ivec2 center = ivec2(floor(uv * vec2(size)));
+    ivec2 origin = ivec2(u_kernel.size.zw);
+
+    // All channels, including alpha, are convolved independently.
+    vec4 sum = vec4(0.0);
+    for (uint y = 0u; y < u_kernel.size.y; y++) {
+        for (uint x = 0u; x < u_kernel.size.x; x++) {
+            uint i = y * u_kernel.size.x + x;
+            ivec2 offset = ivec2(x, y) - origin;
+            float weight = u_kernel.weights[i / 4u][i % 4u];
+            sum += weight * fetch(center + offset, size);
+        }
+    }
+
+    f_color = sum;
+}
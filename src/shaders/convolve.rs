@@ -0,0 +1,57 @@
+use std::borrow::Cow;
+
+use super::{BufferInitContent, FragmentShaderData, FragmentShaderKey};
+
+/// Convolve an image with a dense two-dimensional kernel.
+pub const SHADER: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/spirv/convolve.frag.v"));
+
+/// The maximum number of weights supported by the shader, for example 16×16.
+pub(crate) const MAX_WEIGHTS: usize = 256;
+
+/// The dense convolution shader.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Shader {
+    /// The number of columns and rows of the kernel.
+    pub(crate) size: [u32; 2],
+    /// The weights in row-major order, at most `MAX_WEIGHTS`.
+    pub(crate) weights: Vec<f32>,
+    /// The column and row of the weight applied to the center pixel.
+    pub(crate) center: [u32; 2],
+    /// How pixels outside the image are sampled, as defined in the shader source.
+    pub(crate) border: u32,
+    /// The color of pixels outside the image, for a constant border.
+    pub(crate) border_color: [f32; 4],
+}
+
+impl FragmentShaderData for Shader {
+    fn key(&self) -> Option<FragmentShaderKey> {
+        Some(FragmentShaderKey::Convolve)
+    }
+
+    fn spirv_source(&self) -> Cow<'static, [u8]> {
+        Cow::Borrowed(SHADER)
+    }
+
+    fn binary_data(&self, buffer: &mut Vec<u8>) -> Option<BufferInitContent> {
+        let [width, height] = self.size;
+        let [x, y] = self.center;
+        let mut data = [0u32; 12 + MAX_WEIGHTS];
+
+        data[..4].copy_from_slice(&[width, height, x, y]);
+        data[4] = self.border;
+
+        for (slot, value) in data[8..12].iter_mut().zip(&self.border_color) {
+            *slot = value.to_bits();
+        }
+
+        for (slot, weight) in data[12..].iter_mut().zip(&self.weights) {
+            *slot = weight.to_bits();
+        }
+
+        Some(BufferInitContent::new(buffer, &data[..]))
+    }
+
+    fn num_args(&self) -> u32 {
+        1
+    }
+}
@@ -0,0 +1,183 @@
+#version 450
+#extension GL_GOOGLE_include_directive : require
+layout (location = 0) in vec2 uv;
+layout (location = 0) out vec4 f_color;
+
+layout (set = 1, binding = 0) uniform sampler texture_sampler;
+layout (set = 1, binding = 1) uniform texture2D in_texture;
+// Only used when applying, the mapping curves of all tiles.
+layout (set = 1, binding = 2) uniform texture2D in_mapping;
+
+layout (set = 2, binding = 0) uniform Equalize {
+    // The transformations between the linear RGB and CIE XYZ.
+    mat3x3 to_xyz;
+    mat3x3 from_xyz;
+    // x: the pass, see the constants below.
+    // y: how the lightness is defined, see the constants below.
+    // zw: the number of tiles along width and height.
+    uvec4 config;
+    // x: the clip limit, as a multiple of the average count of a bin, or 0.0.
+    vec4 clip;
+} u_equalize;
+
+// Must be kept in sync with `shaders::equalize::Pass`.
+#define PASS_ROWS 0u
+#define PASS_TILES 1u
+#define PASS_MAP 2u
+
+// Must be kept in sync with `shaders::equalize::Mode`.
+#define MODE_FIRST 0u
+#define MODE_OKLAB 1u
+
+// Must be kept in sync with `shaders::equalize::BINS`.
+#define BINS 256
+
+const mat3x3 M1 = mat3x3(
+        +0.8189330101, +0.0329845436, +0.0482003018,
+        +0.3618667424, +0.9293118715, +0.2643662691,
+        -0.1288597137, +0.0361456387, +0.6338517070
+    );
+
+const mat3x3 M2 = mat3x3(
+        +0.2104542553, +1.9779984951, +0.0259040371,
+        +0.7936177850, -2.4285922050, +0.7827717662,
+        -0.0040720468, +0.4505937099, -0.8086757660
+    );
+
+/* The representation in which only the first component is equalized. For RGB
+ * this is Oklab, such that the chroma is left intact.
+ */
+vec3 to_lightness(vec3 color) {
+    if (u_equalize.config.y == MODE_OKLAB) {
+        vec3 lms = M1 * (u_equalize.to_xyz * color);
+        return M2 * (pow(abs(lms), vec3(1.0 / 3.0)) * sign(lms));
+    }
+
+    return color;
+}
+
+vec3 from_lightness(vec3 color) {
+    if (u_equalize.config.y == MODE_OKLAB) {
+        vec3 lms_star = inverse(M2) * color;
+        vec3 lms = lms_star * lms_star * lms_star;
+        return clamp(u_equalize.from_xyz * (inverse(M1) * lms), 0.0, 1.0);
+    }
+
+    return color;
+}
+
+int bin_of(vec4 texel) {
+    float lightness = clamp(to_lightness(texel.rgb).x, 0.0, 1.0);
+    return min(int(lightness * float(BINS)), BINS - 1);
+}
+
+#include "words.glsl"
+
+/* The pixels covered by a tile along one axis. */
+ivec2 tile_range(int tile, int tiles, int size) {
+    return ivec2(tile * size / tiles, (tile + 1) * size / tiles);
+}
+
+#ifndef EQUALIZE_COUNT_AS_MAIN
+#define EQUALIZE_COUNT_AS_MAIN equalize_count
+#endif
+#ifndef EQUALIZE_APPLY_AS_MAIN
+#define EQUALIZE_APPLY_AS_MAIN equalize_apply
+#endif
+
+void EQUALIZE_COUNT_AS_MAIN() {
+    ivec2 size = textureSize(sampler2D(in_texture, texture_sampler), 0);
+    ivec2 coord = ivec2(gl_FragCoord.xy);
+    ivec2 tiles = ivec2(u_equalize.config.zw);
+    // Each tile along the width has its own row of bins.
+    int tile_x = coord.x / BINS;
+    int bin = coord.x % BINS;
+
+    if (u_equalize.config.x == PASS_ROWS) {
+        // Count the pixels of one row of the tile, the input is the image.
+        ivec2 range = tile_range(tile_x, tiles.x, size.x);
+        uint count = 0u;
+
+        for (int x = range.x; x < range.y; x++) {
+            vec4 texel = texelFetch(sampler2D(in_texture, texture_sampler), ivec2(x, coord.y), 0);
+            count += uint(bin_of(texel) == bin);
+        }
+
+        f_color = encode_word(count, false);
+    } else if (u_equalize.config.x == PASS_TILES) {
+        // Sum the rows of each tile, the input are the row counts with the image height.
+        ivec2 range = tile_range(coord.y, tiles.y, size.y);
+        uint count = 0u;
+
+        for (int y = range.x; y < range.y; y++) {
+            vec4 encoded = texelFetch(sampler2D(in_texture, texture_sampler), ivec2(coord.x, y), 0);
+            count += decode_word(encoded, false);
+        }
+
+        f_color = encode_word(count, false);
+    } else {
+        // The cumulative distribution of the clipped histogram of the tile, up to our bin.
+        // The input are the tile counts, with one row for each tile along the height.
+        int first = tile_x * BINS;
+        float counts[BINS];
+        float total = 0.0;
+
+        for (int i = 0; i < BINS; i++) {
+            vec4 encoded = texelFetch(sampler2D(in_texture, texture_sampler), ivec2(first + i, coord.y), 0);
+            counts[i] = float(decode_word(encoded, false));
+            total += counts[i];
+        }
+
+        // Without a clip limit, no bin is ever clipped.
+        float limit = total;
+        if (u_equalize.clip.x > 0.0) {
+            limit = max(floor(u_equalize.clip.x * total / float(BINS)), 1.0);
+        }
+
+        float excess = 0.0;
+        float below = 0.0;
+        for (int i = 0; i < BINS; i++) {
+            float clipped = min(counts[i], limit);
+            excess += counts[i] - clipped;
+            if (i <= bin) {
+                below += clipped;
+            }
+        }
+
+        // The clipped counts are redistributed evenly into all bins.
+        below += excess * float(bin + 1) / float(BINS);
+        f_color = vec4(total > 0.0 ? below / total : 0.0, 0.0, 0.0, 1.0);
+    }
+}
+
+/* The mapping curve of one tile, evaluated for a bin. */
+float map_lightness(ivec2 tile, int bin) {
+    return texelFetch(sampler2D(in_mapping, texture_sampler), ivec2(tile.x * BINS + bin, tile.y), 0).x;
+}
+
+void EQUALIZE_APPLY_AS_MAIN() {
+    ivec2 size = textureSize(sampler2D(in_texture, texture_sampler), 0);
+    ivec2 tiles = ivec2(u_equalize.config.zw);
+    vec4 texel = texelFetch(sampler2D(in_texture, texture_sampler), ivec2(gl_FragCoord.xy), 0);
+
+    int bin = bin_of(texel);
+
+    // Interpolate bilinearly between the curves of the four closest tile centers.
+    vec2 tile = gl_FragCoord.xy * vec2(tiles) / vec2(size) - 0.5;
+    ivec2 low = clamp(ivec2(floor(tile)), ivec2(0), tiles - 1);
+    ivec2 high = min(low + 1, tiles - 1);
+    vec2 weight = clamp(tile - vec2(low), 0.0, 1.0);
+
+    float top = mix(
+        map_lightness(low, bin),
+        map_lightness(ivec2(high.x, low.y), bin),
+        weight.x);
+    float bottom = mix(
+        map_lightness(ivec2(low.x, high.y), bin),
+        map_lightness(high, bin),
+        weight.x);
+
+    vec3 lightness = to_lightness(texel.rgb);
+    lightness.x = mix(top, bottom, weight.y);
+    f_color = vec4(from_lightness(lightness), texel.a);
+}
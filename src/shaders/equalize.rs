@@ -0,0 +1,99 @@
+use std::borrow::Cow;
+
+use super::{BufferInitContent, FragmentShaderData, FragmentShaderKey};
+use crate::buffer::RowMatrix;
+
+/// Count the lightness of tiles and derive their mapping curves.
+pub const SHADER_COUNT: &[u8] =
+    include_bytes!(concat!(env!("OUT_DIR"), "/spirv/equalize_count.frag.v"));
+/// Remap the lightness with the interpolated curves of the closest tiles.
+pub const SHADER_APPLY: &[u8] =
+    include_bytes!(concat!(env!("OUT_DIR"), "/spirv/equalize_apply.frag.v"));
+
+/// The number of bins of each tile histogram.
+pub(crate) const BINS: u32 = 256;
+
+/// The largest number of tiles along each axis.
+///
+/// The bins of all tiles along the width are laid out side by side in a single texture.
+pub(crate) const MAX_TILES: u32 = 32;
+
+/// One of the passes of the equalization.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Pass {
+    /// Count each row of each tile, with one row of bins per image row.
+    Rows = 0,
+    /// Sum the rows of each tile, with one row of bins per tile along the height.
+    Tiles = 1,
+    /// Clip the histograms and accumulate them into mapping curves.
+    Map = 2,
+    /// Remap the image, the second argument are the curves.
+    Apply = 3,
+}
+
+/// The representation whose first component is equalized.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Mode {
+    /// The linear representation itself, for scalars and Oklab.
+    First = 0,
+    /// Oklab converted from linear RGB, through CIE XYZ.
+    Oklab = 1,
+}
+
+/// The equalization shader, a single pass of it.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Shader {
+    /// The pass to perform.
+    pub(crate) pass: Pass,
+    /// How the lightness is derived.
+    pub(crate) mode: Mode,
+    /// The matrix from linear RGB to CIE XYZ, only used for `Mode::Oklab`.
+    pub(crate) to_xyz: RowMatrix,
+    /// The number of tiles along width and height.
+    pub(crate) tiles: [u32; 2],
+    /// The clip limit relative to the average count of a bin, or `0.0` for none.
+    pub(crate) clip_limit: f32,
+}
+
+impl FragmentShaderData for Shader {
+    fn key(&self) -> Option<FragmentShaderKey> {
+        Some(match self.pass {
+            Pass::Apply => FragmentShaderKey::EqualizeApply,
+            _ => FragmentShaderKey::EqualizeCount,
+        })
+    }
+
+    fn spirv_source(&self) -> Cow<'static, [u8]> {
+        Cow::Borrowed(match self.pass {
+            Pass::Apply => SHADER_APPLY,
+            _ => SHADER_COUNT,
+        })
+    }
+
+    fn binary_data(&self, buffer: &mut Vec<u8>) -> Option<BufferInitContent> {
+        let to_xyz = self.to_xyz.into_mat3x3_std140();
+        let from_xyz = self.to_xyz.inv().into_mat3x3_std140();
+
+        let mut data = [0u32; 32];
+        for (dst, src) in data.iter_mut().zip(to_xyz.iter().chain(&from_xyz)) {
+            *dst = src.to_bits();
+        }
+
+        data[24..29].copy_from_slice(&[
+            self.pass as u32,
+            self.mode as u32,
+            self.tiles[0],
+            self.tiles[1],
+            self.clip_limit.to_bits(),
+        ]);
+
+        Some(BufferInitContent::new(buffer, &data))
+    }
+
+    fn num_args(&self) -> u32 {
+        match self.pass {
+            Pass::Apply => 2,
+            _ => 1,
+        }
+    }
+}
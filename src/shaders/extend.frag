@@ -0,0 +1,25 @@
+#version 450
+#extension GL_GOOGLE_include_directive : require
+layout (location = 0) in vec2 uv;
+layout (location = 0) out vec4 f_color;
+
+layout (set = 1, binding = 0) uniform sampler texture_sampler;
+layout (set = 1, binding = 1) uniform texture2D in_texture;
+
+layout (set = 2, binding = 0) uniform Extend {
+    // xy: the source pixel of the top-left target pixel, may be outside.
+    ivec4 origin;
+    // x: the border mode, see `border.glsl`.
+    uvec4 border;
+    // The color of pixels outside the image, with a constant border.
+    vec4 border_color;
+} u_extend;
+
+#include "border.glsl"
+
+void main() {
+    ivec2 size = textureSize(sampler2D(in_texture, texture_sampler), 0);
+    // Integer pixel indices only, such that copied pixels are exact.
+    ivec2 at = ivec2(gl_FragCoord.xy) + u_extend.origin.xy;
+    f_color = fetch_border(in_texture, at, size, u_extend.border.x, u_extend.border_color);
+}
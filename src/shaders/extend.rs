@@ -0,0 +1,52 @@
+use std::borrow::Cow;
+
+use super::{BufferInitContent, FragmentShaderData, FragmentShaderKey};
+
+/// Copy a region of an image that may extend past its edges.
+pub const SHADER: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/spirv/extend.frag.v"));
+
+/// The extension shader, filling pixels outside the source by a border mode.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Shader {
+    /// The source pixel of the top-left target pixel, may be outside the source.
+    pub(crate) origin: [i32; 2],
+    /// How pixels outside the image are sampled, as defined in the shader source.
+    pub(crate) border: u32,
+    /// The color of pixels outside the image, for a constant border.
+    pub(crate) border_color: [f32; 4],
+}
+
+impl FragmentShaderData for Shader {
+    fn key(&self) -> Option<FragmentShaderKey> {
+        Some(FragmentShaderKey::Extend)
+    }
+
+    fn spirv_source(&self) -> Cow<'static, [u8]> {
+        Cow::Borrowed(SHADER)
+    }
+
+    fn binary_data(&self, buffer: &mut Vec<u8>) -> Option<BufferInitContent> {
+        let [x, y] = self.origin;
+        let [r, g, b, a] = self.border_color;
+        let data: [u32; 12] = [
+            x as u32,
+            y as u32,
+            0,
+            0,
+            self.border,
+            0,
+            0,
+            0,
+            r.to_bits(),
+            g.to_bits(),
+            b.to_bits(),
+            a.to_bits(),
+        ];
+
+        Some(BufferInitContent::new(buffer, &data))
+    }
+
+    fn num_args(&self) -> u32 {
+        1
+    }
+}
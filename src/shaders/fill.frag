@@ -2,7 +2,7 @@
 layout (location = 0) in vec2 uv;
 layout (location = 0) out vec4 f_color;
 
-layout (set = 2, binding = 0) uniform FragmentColor {
+layout (set = 1, binding = 0) uniform FragmentColor {
     vec4 color;
 } u_fragmentColor;
 
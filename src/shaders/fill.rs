@@ -0,0 +1,31 @@
+use std::borrow::Cow;
+
+use super::{BufferInitContent, FragmentShaderData, FragmentShaderKey};
+
+/// Paint a single color, without any texture inputs.
+pub const SHADER: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/spirv/fill.frag.v"));
+
+/// The fill shader, painting a solid color in linear representation.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Shader {
+    pub(crate) color: [f32; 4],
+}
+
+impl FragmentShaderData for Shader {
+    fn key(&self) -> Option<FragmentShaderKey> {
+        Some(FragmentShaderKey::Fill)
+    }
+
+    fn spirv_source(&self) -> Cow<'static, [u8]> {
+        Cow::Borrowed(SHADER)
+    }
+
+    fn binary_data(&self, buffer: &mut Vec<u8>) -> Option<BufferInitContent> {
+        Some(BufferInitContent::new(buffer, &self.color))
+    }
+
+    /// There are no textures, the data is bound to `set = 1`.
+    fn num_args(&self) -> u32 {
+        0
+    }
+}
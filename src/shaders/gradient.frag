@@ -0,0 +1,54 @@
+#version 450
+#extension GL_GOOGLE_include_directive : require
+layout (location = 0) in vec2 uv;
+layout (location = 0) out vec4 f_color;
+
+layout (set = 1, binding = 0) uniform sampler texture_sampler;
+layout (set = 1, binding = 1) uniform texture2D in_texture;
+
+layout (set = 2, binding = 0) uniform Gradient {
+    // xyz: the weights of the luma in the linear color representation.
+    vec4 luma;
+    // xyz: the smoothing applied across the direction of each derivative.
+    vec4 smoothing;
+    // x: the output kind, see the constants below.
+    uvec4 kind;
+} u_gradient;
+
+#include "border.glsl"
+
+// Must be kept in sync with `command::GradientKind`.
+#define KIND_CARTESIAN 0u
+#define KIND_POLAR 1u
+
+#define PI 3.1415926535897932384626433832795
+
+ivec2 size;
+
+float luma_at(ivec2 pixel) {
+    vec4 texel = fetch_border(in_texture, pixel, size, BORDER_CLAMP, vec4(0.0));
+    return dot(texel.rgb, u_gradient.luma.xyz);
+}
+
+void main() {
+    size = textureSize(sampler2D(in_texture, texture_sampler), 0);
+    ivec2 center = ivec2(gl_FragCoord.xy);
+
+    // The central difference, positive where luma increases along the axis.
+    vec2 derivative = vec2(0.0);
+    for (int i = -1; i <= 1; i++) {
+        float weight = 0.5 * u_gradient.smoothing[i + 1];
+        derivative.x += weight * (luma_at(center + ivec2(1, i)) - luma_at(center + ivec2(-1, i)));
+        derivative.y += weight * (luma_at(center + ivec2(i, 1)) - luma_at(center + ivec2(i, -1)));
+    }
+
+    if (u_gradient.kind.x == KIND_POLAR) {
+        float magnitude = length(derivative);
+        // The direction is a fraction of a full turn, where zero points along the width.
+        float angle = magnitude > 0.0 ? atan(derivative.y, derivative.x) / (2.0 * PI) : 0.0;
+        f_color = vec4(vec3(magnitude), angle + 0.5);
+    } else {
+        // Signed values are stored with an offset, to fit into normalized texels.
+        f_color = vec4(vec3(derivative.x + 0.5), derivative.y + 0.5);
+    }
+}
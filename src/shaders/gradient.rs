@@ -0,0 +1,52 @@
+use std::borrow::Cow;
+
+use super::{BufferInitContent, FragmentShaderData, FragmentShaderKey};
+
+/// Calculate both first derivatives of the luma and combine them into a gradient.
+pub const SHADER: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/spirv/gradient.frag.v"));
+
+/// The gradient shader, differentiating along both axes in one pass.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Shader {
+    /// The weights computing luma from the linear color representation.
+    pub(crate) luma: [f32; 3],
+    /// The smoothing across the direction of each derivative, of a `DerivativeMethod`.
+    pub(crate) smoothing: [f32; 3],
+    /// The index of the output kind, as defined in the shader source.
+    pub(crate) kind: u32,
+}
+
+impl FragmentShaderData for Shader {
+    fn key(&self) -> Option<FragmentShaderKey> {
+        Some(FragmentShaderKey::Gradient)
+    }
+
+    fn spirv_source(&self) -> Cow<'static, [u8]> {
+        Cow::Borrowed(SHADER)
+    }
+
+    fn binary_data(&self, buffer: &mut Vec<u8>) -> Option<BufferInitContent> {
+        let [r, g, b] = self.luma;
+        let [s0, s1, s2] = self.smoothing;
+        let data: [u32; 12] = [
+            r.to_bits(),
+            g.to_bits(),
+            b.to_bits(),
+            0,
+            s0.to_bits(),
+            s1.to_bits(),
+            s2.to_bits(),
+            0,
+            self.kind,
+            0,
+            0,
+            0,
+        ];
+
+        Some(BufferInitContent::new(buffer, &data))
+    }
+
+    fn num_args(&self) -> u32 {
+        1
+    }
+}
@@ -0,0 +1,135 @@
+#version 450
+layout (location = 0) in vec2 uv;
+layout (location = 0) out vec4 f_color;
+
+layout (set = 1, binding = 0) uniform sampler texture_sampler;
+layout (set = 1, binding = 1) uniform texture2D in_texture;
+
+layout (set = 2, binding = 0) uniform Hash {
+    // xyz: the weights of the luma in the linear color representation.
+    vec4 luma;
+    // x: the kind of hash, see the constants below.
+    // y: the pass, see the constants below.
+    uvec4 kind;
+} u_hash;
+
+// Must be kept in sync with `command::PerceptualHash`.
+#define KIND_AVERAGE 0u
+#define KIND_DIFFERENCE 1u
+#define KIND_DCT 2u
+
+// Must be kept in sync with `shaders::hash::Pass`.
+#define PASS_LUMA 0u
+#define PASS_BITS 1u
+
+// The hash has 8×8 bits, the DCT is computed from 32×32 samples.
+#define SIDE 8
+#define DCT_SIDE 32
+
+#define PI 3.1415926535897932384626433832795
+
+// The luma of the thumbnail, as painted by the luma pass.
+float gray(int x, int y) {
+    return texelFetch(sampler2D(in_texture, texture_sampler), ivec2(x, y), 0).r;
+}
+
+// Compare with the mean of all 8×8 samples.
+bool average_bit(ivec2 bit) {
+    float mean = 0.0;
+    for (int y = 0; y < SIDE; y++) {
+        for (int x = 0; x < SIDE; x++) {
+            mean += gray(x, y);
+        }
+    }
+
+    mean /= float(SIDE * SIDE);
+    return gray(bit.x, bit.y) > mean;
+}
+
+// Compare with the left neighbor, of 9×8 samples.
+bool difference_bit(ivec2 bit) {
+    return gray(bit.x + 1, bit.y) > gray(bit.x, bit.y);
+}
+
+// The DCT-II basis, without any normalization which does not change the comparisons.
+float basis(int frequency, int position) {
+    return cos(PI * float((2 * position + 1) * frequency) / float(2 * DCT_SIDE));
+}
+
+// Compare the low frequencies of the DCT of 32×32 samples with their median.
+bool dct_bit(ivec2 bit) {
+    // Transform along the width first, only keeping the low frequencies.
+    float rows[SIDE * DCT_SIDE];
+    for (int y = 0; y < DCT_SIDE; y++) {
+        float samples[DCT_SIDE];
+        for (int x = 0; x < DCT_SIDE; x++) {
+            samples[x] = gray(x, y);
+        }
+
+        for (int u = 0; u < SIDE; u++) {
+            float sum = 0.0;
+            for (int x = 0; x < DCT_SIDE; x++) {
+                sum += basis(u, x) * samples[x];
+            }
+
+            rows[u * DCT_SIDE + y] = sum;
+        }
+    }
+
+    // Then along the height, where `v` is the row of the coefficient.
+    float coefficients[SIDE * SIDE];
+    for (int v = 0; v < SIDE; v++) {
+        for (int u = 0; u < SIDE; u++) {
+            float sum = 0.0;
+            for (int y = 0; y < DCT_SIDE; y++) {
+                sum += basis(v, y) * rows[u * DCT_SIDE + y];
+            }
+
+            coefficients[v * SIDE + u] = sum;
+        }
+    }
+
+    float own = coefficients[bit.y * SIDE + bit.x];
+
+    // Insertion sort, there are only a few coefficients.
+    for (int i = 1; i < SIDE * SIDE; i++) {
+        float value = coefficients[i];
+        int j = i - 1;
+        while (j >= 0 && coefficients[j] > value) {
+            coefficients[j + 1] = coefficients[j];
+            j--;
+        }
+        coefficients[j + 1] = value;
+    }
+
+    const int half_count = SIDE * SIDE / 2;
+    float median = 0.5 * (coefficients[half_count - 1] + coefficients[half_count]);
+    return own > median;
+}
+
+void main() {
+    // Like the reference, the luma ignores alpha and is taken before scaling.
+    if (u_hash.kind.y == PASS_LUMA) {
+        vec4 texel = texelFetch(sampler2D(in_texture, texture_sampler), ivec2(gl_FragCoord.xy), 0);
+        f_color = vec4(vec3(dot(texel.rgb, u_hash.luma.xyz)), 1.0);
+        return;
+    }
+
+    ivec2 bit = ivec2(gl_FragCoord.xy);
+
+    bool set;
+    switch (u_hash.kind.x) {
+    case KIND_AVERAGE:
+        set = average_bit(bit);
+        break;
+    case KIND_DIFFERENCE:
+        set = difference_bit(bit);
+        break;
+    case KIND_DCT:
+    default:
+        set = dct_bit(bit);
+        break;
+    }
+
+    f_color = vec4(vec3(set ? 1.0 : 0.0), 1.0);
+}
@@ -0,0 +1,56 @@
+use std::borrow::Cow;
+
+use super::{BufferInitContent, FragmentShaderData, FragmentShaderKey};
+
+/// Compute the bits of a perceptual hash from a downscaled image.
+pub const SHADER: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/spirv/hash.frag.v"));
+
+/// One of the passes of the perceptual hash.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Pass {
+    /// Compute the opaque luma of each pixel, before the image is scaled to a thumbnail.
+    Luma = 0,
+    /// Compute the bits from the thumbnail of the luma, each pixel of the output is one bit.
+    Bits = 1,
+}
+
+/// The perceptual hash shader, a single pass of it.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Shader {
+    /// The pass to perform.
+    pub(crate) pass: Pass,
+    /// The weights computing luma from the linear color representation.
+    pub(crate) luma: [f32; 3],
+    /// The index of the hash kind, as defined in the shader source.
+    pub(crate) kind: u32,
+}
+
+impl FragmentShaderData for Shader {
+    fn key(&self) -> Option<FragmentShaderKey> {
+        Some(FragmentShaderKey::Hash)
+    }
+
+    fn spirv_source(&self) -> Cow<'static, [u8]> {
+        Cow::Borrowed(SHADER)
+    }
+
+    fn binary_data(&self, buffer: &mut Vec<u8>) -> Option<BufferInitContent> {
+        let [r, g, b] = self.luma;
+        let data: [u32; 8] = [
+            r.to_bits(),
+            g.to_bits(),
+            b.to_bits(),
+            0,
+            self.kind,
+            self.pass as u32,
+            0,
+            0,
+        ];
+
+        Some(BufferInitContent::new(buffer, &data))
+    }
+
+    fn num_args(&self) -> u32 {
+        1
+    }
+}
@@ -0,0 +1,53 @@
+#version 450
+#extension GL_GOOGLE_include_directive : require
+layout (location = 0) in vec2 uv;
+layout (location = 0) out vec4 f_color;
+
+layout (set = 1, binding = 0) uniform sampler texture_sampler;
+layout (set = 1, binding = 1) uniform texture2D in_texture;
+
+layout (set = 2, binding = 0) uniform Histogram {
+    // The weights of the linear texel giving the counted value.
+    vec4 weights;
+    // x: the pass, see the constants below.
+    // y: the number of bins.
+    // z: if non-zero, encode counts with the most significant byte first.
+    uvec4 config;
+} u_histogram;
+
+// Must be kept in sync with `shaders::histogram::Pass`.
+#define PASS_ROWS 0u
+#define PASS_SUM 1u
+
+#include "words.glsl"
+
+bool big_endian() {
+    return u_histogram.config.z != 0u;
+}
+
+void main() {
+    ivec2 size = textureSize(sampler2D(in_texture, texture_sampler), 0);
+    ivec2 coord = ivec2(gl_FragCoord.xy);
+    uint bin = uint(coord.x);
+    uint count = 0u;
+
+    if (u_histogram.config.x == PASS_ROWS) {
+        // Count the pixels of one row falling into our bin.
+        uint bins = u_histogram.config.y;
+
+        for (int x = 0; x < size.x; x++) {
+            vec4 texel = texelFetch(sampler2D(in_texture, texture_sampler), ivec2(x, coord.y), 0);
+            float value = clamp(dot(texel, u_histogram.weights), 0.0, 1.0);
+            uint index = min(uint(value * float(bins)), bins - 1u);
+            count += uint(index == bin);
+        }
+    } else {
+        // Sum the per-row counts of the bin, exact in integer arithmetic.
+        for (int y = 0; y < size.y; y++) {
+            vec4 encoded = texelFetch(sampler2D(in_texture, texture_sampler), ivec2(coord.x, y), 0);
+            count += decode_word(encoded, big_endian());
+        }
+    }
+
+    f_color = encode_word(count, big_endian());
+}
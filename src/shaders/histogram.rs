@@ -0,0 +1,61 @@
+use std::borrow::Cow;
+
+use super::{BufferInitContent, FragmentShaderData, FragmentShaderKey};
+
+/// Count the values of one channel into bins, first per row and then summed over all rows.
+pub const SHADER: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/spirv/histogram.frag.v"));
+
+/// The largest number of bins, that is the width of the count buffer.
+pub(crate) const MAX_BINS: u32 = 4096;
+
+/// One of the passes of the histogram.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Pass {
+    /// Count the pixels of each row, into a texture with one row of bins per image row.
+    Rows = 0,
+    /// Sum the counts of all rows into a single row of bins.
+    Sum = 1,
+}
+
+/// The histogram shader, a single pass of the reduction.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Shader {
+    /// The pass to perform.
+    pub(crate) pass: Pass,
+    /// The weights of the linear texel giving the counted value.
+    pub(crate) weights: [f32; 4],
+    /// The number of bins across the range `0.0..=1.0`.
+    pub(crate) bins: u32,
+}
+
+impl FragmentShaderData for Shader {
+    fn key(&self) -> Option<FragmentShaderKey> {
+        Some(FragmentShaderKey::Histogram)
+    }
+
+    fn spirv_source(&self) -> Cow<'static, [u8]> {
+        Cow::Borrowed(SHADER)
+    }
+
+    fn binary_data(&self, buffer: &mut Vec<u8>) -> Option<BufferInitContent> {
+        // The counts are read as `u32` on the host, so we write their bytes in its order.
+        let big_endian = cfg!(target_endian = "big");
+        let [x, y, z, w] = self.weights;
+        let data: [u32; 8] = [
+            x.to_bits(),
+            y.to_bits(),
+            z.to_bits(),
+            w.to_bits(),
+            self.pass as u32,
+            self.bins,
+            big_endian as u32,
+            0,
+        ];
+
+        Some(BufferInitContent::new(buffer, &data))
+    }
+
+    fn num_args(&self) -> u32 {
+        1
+    }
+}
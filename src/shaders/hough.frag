@@ -0,0 +1,86 @@
+#version 450
+layout (location = 0) in vec2 uv;
+layout (location = 0) out vec4 f_color;
+
+layout (set = 1, binding = 0) uniform sampler texture_sampler;
+layout (set = 1, binding = 1) uniform texture2D in_texture;
+
+layout (set = 2, binding = 0) uniform Hough {
+    // xyz: the weights of the luma in the linear color representation.
+    vec4 luma;
+    // x: the radius of circles.
+    vec4 shape;
+    // x: the kind of shape, see the constants below.
+    // yz: the number of thetas and rhos of lines.
+    uvec4 mode;
+} u_hough;
+
+// Must be kept in sync with `command::Hough`.
+#define KIND_LINES 0u
+#define KIND_CIRCLES 1u
+
+#define PI 3.1415926535897932384626433832795
+
+ivec2 size;
+
+// Edge images are binary, anything brighter than half is an edge pixel.
+float vote(vec2 position) {
+    ivec2 pixel = ivec2(floor(position));
+    if (any(lessThan(pixel, ivec2(0))) || any(greaterThanEqual(pixel, size))) {
+        return 0.0;
+    }
+
+    vec4 texel = texelFetch(sampler2D(in_texture, texture_sampler), pixel, 0);
+    return dot(texel.rgb, u_hough.luma.xyz) > 0.5 ? 1.0 : 0.0;
+}
+
+// Count edge pixels along the line with normal angle theta at distance rho from the center.
+float line_votes(ivec2 cell) {
+    float diagonal = length(vec2(size));
+    float theta = PI * float(cell.x) / float(u_hough.mode.y);
+    float rho = ((float(cell.y) + 0.5) / float(u_hough.mode.z) - 0.5) * diagonal;
+
+    vec2 normal = vec2(cos(theta), sin(theta));
+    vec2 along = vec2(-normal.y, normal.x);
+    vec2 base = 0.5 * vec2(size) + rho * normal;
+
+    // One sample per pixel of length, covering the line through the whole image.
+    int samples = int(ceil(diagonal));
+    float votes = 0.0;
+    for (int i = 0; i < samples; i++) {
+        float t = float(i) + 0.5 - 0.5 * float(samples);
+        votes += vote(base + t * along);
+    }
+
+    return votes / float(samples);
+}
+
+// Count edge pixels on the circle of the given radius around the center of this pixel.
+float circle_votes(ivec2 cell) {
+    float radius = u_hough.shape.x;
+    vec2 center = vec2(cell) + 0.5;
+
+    // One sample per pixel of circumference.
+    int samples = max(int(ceil(2.0 * PI * radius)), 1);
+    float votes = 0.0;
+    for (int i = 0; i < samples; i++) {
+        float phi = 2.0 * PI * (float(i) + 0.5) / float(samples);
+        votes += vote(center + radius * vec2(cos(phi), sin(phi)));
+    }
+
+    return votes / float(samples);
+}
+
+void main() {
+    size = textureSize(sampler2D(in_texture, texture_sampler), 0);
+    ivec2 cell = ivec2(gl_FragCoord.xy);
+
+    float votes;
+    if (u_hough.mode.x == KIND_CIRCLES) {
+        votes = circle_votes(cell);
+    } else {
+        votes = line_votes(cell);
+    }
+
+    f_color = vec4(vec3(votes), 1.0);
+}
@@ -0,0 +1,54 @@
+use std::borrow::Cow;
+
+use super::{BufferInitContent, FragmentShaderData, FragmentShaderKey};
+
+/// Accumulate the votes of edge pixels for lines or circles.
+pub const SHADER: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/spirv/hough.frag.v"));
+
+/// The Hough shader, counting the edge pixels on the shape of each accumulator cell.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Shader {
+    /// The weights computing luma from the linear color representation.
+    pub(crate) luma: [f32; 3],
+    /// The index of the shape kind, as defined in the shader source.
+    pub(crate) kind: u32,
+    /// The radius of circles, in pixels.
+    pub(crate) radius: f32,
+    /// The number of thetas and rhos of lines, the size of their accumulator.
+    pub(crate) lines: [u32; 2],
+}
+
+impl FragmentShaderData for Shader {
+    fn key(&self) -> Option<FragmentShaderKey> {
+        Some(FragmentShaderKey::Hough)
+    }
+
+    fn spirv_source(&self) -> Cow<'static, [u8]> {
+        Cow::Borrowed(SHADER)
+    }
+
+    fn binary_data(&self, buffer: &mut Vec<u8>) -> Option<BufferInitContent> {
+        let [r, g, b] = self.luma;
+        let [thetas, rhos] = self.lines;
+        let data: [u32; 12] = [
+            r.to_bits(),
+            g.to_bits(),
+            b.to_bits(),
+            0,
+            self.radius.to_bits(),
+            0,
+            0,
+            0,
+            self.kind,
+            thetas,
+            rhos,
+            0,
+        ];
+
+        Some(BufferInitContent::new(buffer, &data))
+    }
+
+    fn num_args(&self) -> u32 {
+        1
+    }
+}
@@ -0,0 +1,65 @@
+#version 450
+#extension GL_GOOGLE_include_directive : require
+layout (location = 0) in vec2 uv;
+layout (location = 0) out vec4 f_color;
+
+layout (set = 1, binding = 0) uniform sampler texture_sampler;
+layout (set = 1, binding = 1) uniform texture2D in_texture;
+
+layout (set = 2, binding = 0) uniform Kuwahara {
+    // xyz: the weights of the luma in the linear color representation.
+    vec4 luma;
+    // x: the radius of the window, regions have (radius + 1) pixels per side.
+    uvec4 window;
+} u_kuwahara;
+
+#include "border.glsl"
+
+// Must be kept in sync with `kuwahara::MAX_RADIUS`.
+#define MAX_RADIUS 16
+
+void main() {
+    ivec2 size = textureSize(sampler2D(in_texture, texture_sampler), 0);
+    ivec2 center = ivec2(floor(uv * vec2(size)));
+    int radius = min(int(u_kuwahara.window.x), MAX_RADIUS);
+    float count = float((radius + 1) * (radius + 1));
+
+    vec4 best_mean = vec4(0.0);
+    float best_variance = 0.0;
+
+    // The four regions share the axes and the center pixel. These are the
+    // signs of their extent along x and y.
+    ivec2 regions[4] = ivec2[4](ivec2(-1, -1), ivec2(1, -1), ivec2(-1, 1), ivec2(1, 1));
+
+    for (int r = 0; r < 4; r++) {
+        vec4 sum = vec4(0.0);
+        float luma_sum = 0.0;
+        float luma_square = 0.0;
+
+        for (int y = 0; y <= radius; y++) {
+            for (int x = 0; x <= radius; x++) {
+                ivec2 at = center + regions[r] * ivec2(x, y);
+                vec4 texel = fetch_border(in_texture, at, size, BORDER_CLAMP, vec4(0.0));
+                float luma = dot(texel.rgb, u_kuwahara.luma.xyz);
+
+                sum += vec4(texel.rgb * texel.a, texel.a);
+                luma_sum += luma;
+                luma_square += luma * luma;
+            }
+        }
+
+        float mean_luma = luma_sum / count;
+        float variance = luma_square / count - mean_luma * mean_luma;
+
+        if (r == 0 || variance < best_variance) {
+            best_variance = variance;
+            best_mean = sum / count;
+        }
+    }
+
+    if (best_mean.a > 0.0) {
+        f_color = vec4(best_mean.rgb / best_mean.a, best_mean.a);
+    } else {
+        f_color = vec4(0.0);
+    }
+}
@@ -0,0 +1,50 @@
+use std::borrow::Cow;
+
+use super::{BufferInitContent, FragmentShaderData, FragmentShaderKey};
+
+/// Choose the mean of the region around a pixel whose luma has the least variance.
+pub const SHADER: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/spirv/kuwahara.frag.v"));
+
+/// The largest radius of the window supported by the shader, regions of 17×17 pixels.
+pub(crate) const MAX_RADIUS: u32 = 16;
+
+/// The Kuwahara shader, with four square regions overlapping on the axes.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Shader {
+    /// The weights computing luma from the linear color representation.
+    pub(crate) luma: [f32; 3],
+    /// The number of pixels considered on each side of the center, at most `MAX_RADIUS`.
+    ///
+    /// Each region is a square with `radius + 1` pixels per side.
+    pub(crate) radius: u32,
+}
+
+impl FragmentShaderData for Shader {
+    fn key(&self) -> Option<FragmentShaderKey> {
+        Some(FragmentShaderKey::Kuwahara)
+    }
+
+    fn spirv_source(&self) -> Cow<'static, [u8]> {
+        Cow::Borrowed(SHADER)
+    }
+
+    fn binary_data(&self, buffer: &mut Vec<u8>) -> Option<BufferInitContent> {
+        let [r, g, b] = self.luma;
+        let data: [u32; 8] = [
+            r.to_bits(),
+            g.to_bits(),
+            b.to_bits(),
+            0,
+            self.radius,
+            0,
+            0,
+            0,
+        ];
+
+        Some(BufferInitContent::new(buffer, &data))
+    }
+
+    fn num_args(&self) -> u32 {
+        1
+    }
+}
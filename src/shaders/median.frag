@@ -0,0 +1,67 @@
+#version 450
+#extension GL_GOOGLE_include_directive : require
+layout (location = 0) in vec2 uv;
+layout (location = 0) out vec4 f_color;
+
+layout (set = 1, binding = 0) uniform sampler texture_sampler;
+layout (set = 1, binding = 1) uniform texture2D in_texture;
+
+layout (set = 2, binding = 0) uniform Median {
+    // xyz: the weights of the luma in the linear color representation.
+    vec4 luma;
+    // x: the radius of the window, at most MAX_RADIUS.
+    uvec4 window;
+} u_median;
+
+#include "border.glsl"
+
+// Must be kept in sync with `median::MAX_RADIUS`.
+#define MAX_RADIUS 5
+#define MAX_SIDE (2 * MAX_RADIUS + 1)
+
+ivec2 size;
+ivec2 center;
+int radius;
+int side;
+
+vec4 window_texel(int i) {
+    ivec2 offset = ivec2(i % side, i / side) - radius;
+    return fetch_border(in_texture, center + offset, size, BORDER_CLAMP, vec4(0.0));
+}
+
+void main() {
+    size = textureSize(sampler2D(in_texture, texture_sampler), 0);
+    center = ivec2(floor(uv * vec2(size)));
+    radius = min(int(u_median.window.x), MAX_RADIUS);
+    side = 2 * radius + 1;
+
+    int count = side * side;
+    float lumas[MAX_SIDE * MAX_SIDE];
+
+    for (int i = 0; i < count; i++) {
+        lumas[i] = dot(window_texel(i).rgb, u_median.luma.xyz);
+    }
+
+    // Find a pixel whose rank covers the middle position. Equal lumas share
+    // a range of ranks, so there always is one.
+    int middle = count / 2;
+    int chosen = 0;
+
+    for (int i = 0; i < count; i++) {
+        int less = 0;
+        int equal = 0;
+
+        for (int j = 0; j < count; j++) {
+            less += int(lumas[j] < lumas[i]);
+            equal += int(lumas[j] == lumas[i]);
+        }
+
+        if (less <= middle && middle < less + equal) {
+            chosen = i;
+            break;
+        }
+    }
+
+    // The whole pixel, including its alpha.
+    f_color = window_texel(chosen);
+}
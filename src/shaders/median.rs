@@ -0,0 +1,48 @@
+use std::borrow::Cow;
+
+use super::{BufferInitContent, FragmentShaderData, FragmentShaderKey};
+
+/// Choose the pixel with the median luma within a square window.
+pub const SHADER: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/spirv/median.frag.v"));
+
+/// The largest radius of the window supported by the shader, an 11×11 window.
+pub(crate) const MAX_RADIUS: u32 = 5;
+
+/// The median shader, a rank filter ordering pixels by their luma.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Shader {
+    /// The weights computing luma from the linear color representation.
+    pub(crate) luma: [f32; 3],
+    /// The number of pixels considered on each side of the center, at most `MAX_RADIUS`.
+    pub(crate) radius: u32,
+}
+
+impl FragmentShaderData for Shader {
+    fn key(&self) -> Option<FragmentShaderKey> {
+        Some(FragmentShaderKey::Median)
+    }
+
+    fn spirv_source(&self) -> Cow<'static, [u8]> {
+        Cow::Borrowed(SHADER)
+    }
+
+    fn binary_data(&self, buffer: &mut Vec<u8>) -> Option<BufferInitContent> {
+        let [r, g, b] = self.luma;
+        let data: [u32; 8] = [
+            r.to_bits(),
+            g.to_bits(),
+            b.to_bits(),
+            0,
+            self.radius,
+            0,
+            0,
+            0,
+        ];
+
+        Some(BufferInitContent::new(buffer, &data))
+    }
+
+    fn num_args(&self) -> u32 {
+        1
+    }
+}
@@ -2,13 +2,36 @@ use crate::buffer::RowMatrix;
 use crate::program::BufferInitContent;
 use std::borrow::Cow;
 
+pub mod bilateral;
 pub mod bilinear;
+pub mod blend;
+pub mod blend_mode;
+pub mod blue_noise;
 pub mod box3;
+pub mod canny;
+pub mod convolve;
 pub mod distribution_normal2d;
+pub mod equalize;
+pub mod extend;
+pub mod fill;
+pub mod gradient;
+pub mod hash;
+pub mod histogram;
+pub mod hough;
 pub mod inject;
+pub mod kuwahara;
+pub mod median;
+pub mod noise;
 pub mod oklab;
+pub mod orient;
 pub mod palette;
+pub mod resample;
+pub mod separable;
+pub mod ssim;
 pub mod stage;
+pub mod statistics;
+pub mod warp;
+pub mod white_noise;
 
 /// A vertex box shader, rendering a sole quad with given vertex and uv coordinate system.
 pub const VERT_NOOP: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/spirv/box.vert.v"));
@@ -33,6 +56,10 @@ pub(crate) trait FragmentShaderData: core::fmt::Debug {
     fn num_args(&self) -> u32 {
         1
     }
+    /// The filter of the sampler that is bound with the texture arguments.
+    fn sampler_filter(&self) -> wgpu::FilterMode {
+        wgpu::FilterMode::Nearest
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -55,6 +82,56 @@ pub(crate) enum FragmentShaderKey {
     OklabTransform(bool),
     /// A convolution with a 3-by-3 box function.
     Box3,
+    /// Porter-Duff compositing of two images.
+    Blend,
+    /// The W3C blend modes, mixing two images before compositing.
+    BlendMode,
+    /// A solid color.
+    Fill,
+    /// A convolution with a one-dimensional kernel along one axis.
+    Separable,
+    /// A convolution with a dense two-dimensional kernel.
+    Convolve,
+    /// A rank filter choosing the pixel with median luma.
+    Median,
+    /// An edge preserving smoothing, weighting by distance and similarity.
+    Bilateral,
+    /// The mean of the least varying region around a pixel.
+    Kuwahara,
+    /// Resampling along one axis with a reconstruction filter.
+    Resample,
+    /// Sampling at positions offset by a displacement image.
+    Warp,
+    /// A lossless flip, transposition or rotation of the pixel grid.
+    Orient,
+    /// A region of an image, extended past its edges by a border mode.
+    Extend,
+    /// Both first derivatives of the luma, as components or magnitude and angle.
+    Gradient,
+    /// Non-maximum suppression and hysteresis of the Canny edge detector.
+    Canny,
+    /// An accumulator of edge votes for lines or circles.
+    Hough,
+    /// The structural similarity of two images at a single scale.
+    Ssim,
+    /// The combination of structural similarity at several scales.
+    MultiScaleSsim,
+    /// The bits of a perceptual hash.
+    Hash,
+    /// Counts of a channel's values in bins, encoded as bytes.
+    Histogram,
+    /// Tile histograms of the lightness and their mapping curves.
+    EqualizeCount,
+    /// The lightness remapped with interpolated tile curves.
+    EqualizeApply,
+    /// Per channel minimum, maximum, sum, mean and variance.
+    Statistics,
+    /// Coherent noise with several octaves.
+    Noise,
+    /// Independent random values, hashed from the pixel coordinate.
+    WhiteNoise,
+    /// A repeated mask of ranks with blue noise characteristics.
+    BlueNoise,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -67,6 +144,30 @@ pub(crate) enum FragmentShader {
     Inject(self::inject::Shader),
     Oklab(self::oklab::Shader),
     Box3(self::box3::Shader),
+    Blend(self::blend::Shader),
+    BlendMode(self::blend_mode::Shader),
+    Fill(self::fill::Shader),
+    Separable(self::separable::Shader),
+    Convolve(self::convolve::Shader),
+    Median(self::median::Shader),
+    Bilateral(self::bilateral::Shader),
+    Kuwahara(self::kuwahara::Shader),
+    Resample(self::resample::Shader),
+    Warp(self::warp::Shader),
+    Orient(self::orient::Shader),
+    Extend(self::extend::Shader),
+    Gradient(self::gradient::Shader),
+    Canny(self::canny::Shader),
+    Hough(self::hough::Shader),
+    Ssim(self::ssim::Shader),
+    MultiScaleSsim(self::ssim::MultiScale),
+    Hash(self::hash::Shader),
+    Histogram(self::histogram::Shader),
+    Equalize(self::equalize::Shader),
+    Statistics(self::statistics::Shader),
+    Noise(self::noise::Shader),
+    WhiteNoise(self::white_noise::Shader),
+    BlueNoise(self::blue_noise::Shader),
 }
 
 impl FragmentShader {
@@ -80,6 +181,30 @@ impl FragmentShader {
             FragmentShader::Inject(inject) => inject,
             FragmentShader::Oklab(oklab) => oklab,
             FragmentShader::Box3(box3) => box3,
+            FragmentShader::Blend(blend) => blend,
+            FragmentShader::BlendMode(blend) => blend,
+            FragmentShader::Fill(fill) => fill,
+            FragmentShader::Separable(separable) => separable,
+            FragmentShader::Convolve(convolve) => convolve,
+            FragmentShader::Median(median) => median,
+            FragmentShader::Bilateral(bilateral) => bilateral,
+            FragmentShader::Kuwahara(kuwahara) => kuwahara,
+            FragmentShader::Resample(resample) => resample,
+            FragmentShader::Warp(warp) => warp,
+            FragmentShader::Orient(orient) => orient,
+            FragmentShader::Extend(extend) => extend,
+            FragmentShader::Gradient(gradient) => gradient,
+            FragmentShader::Canny(canny) => canny,
+            FragmentShader::Hough(hough) => hough,
+            FragmentShader::Ssim(ssim) => ssim,
+            FragmentShader::MultiScaleSsim(ssim) => ssim,
+            FragmentShader::Hash(hash) => hash,
+            FragmentShader::Histogram(histogram) => histogram,
+            FragmentShader::Equalize(equalize) => equalize,
+            FragmentShader::Statistics(statistics) => statistics,
+            FragmentShader::Noise(noise) => noise,
+            FragmentShader::WhiteNoise(white_noise) => white_noise,
+            FragmentShader::BlueNoise(blue_noise) => blue_noise,
         }
     }
 }
@@ -87,12 +212,14 @@ impl FragmentShader {
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub(crate) enum PaintOnTopKind {
     Copy,
+    /// Copy, but interpolate bi-linearly between the nearest pixels.
+    CopyLinear,
 }
 
 impl PaintOnTopKind {
     pub(crate) fn fragment_shader(&self) -> &'static [u8] {
         match self {
-            PaintOnTopKind::Copy => FRAG_COPY,
+            PaintOnTopKind::Copy | PaintOnTopKind::CopyLinear => FRAG_COPY,
         }
     }
 }
@@ -105,6 +232,13 @@ impl FragmentShaderData for PaintOnTopKind {
     fn spirv_source(&self) -> Cow<'static, [u8]> {
         Cow::Borrowed(self.fragment_shader())
     }
+
+    fn sampler_filter(&self) -> wgpu::FilterMode {
+        match self {
+            PaintOnTopKind::Copy => wgpu::FilterMode::Nearest,
+            PaintOnTopKind::CopyLinear => wgpu::FilterMode::Linear,
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
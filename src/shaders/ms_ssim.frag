@@ -0,0 +1,38 @@
+#version 450
+layout (location = 0) in vec2 uv;
+layout (location = 0) out vec4 f_color;
+
+// The components of SSIM at each scale, from the finest to the coarsest.
+layout (set = 1, binding = 0) uniform sampler texture_sampler;
+layout (set = 1, binding = 1) uniform texture2D scale0;
+layout (set = 1, binding = 2) uniform texture2D scale1;
+layout (set = 1, binding = 3) uniform texture2D scale2;
+layout (set = 1, binding = 4) uniform texture2D scale3;
+layout (set = 1, binding = 5) uniform texture2D scale4;
+
+// The exponents of each scale, from Wang, Simoncelli and Bovik.
+// Must be kept in sync with `ssim::SCALES`.
+const float WEIGHTS[5] = float[5](0.0448, 0.2856, 0.3001, 0.2363, 0.1333);
+
+// The components are `y` luminance and `z` contrast-structure, negative values are clamped.
+float contrast_structure(vec4 components, float weight) {
+    return pow(max(components.z, 0.0), weight);
+}
+
+void main() {
+    vec4 s0 = texture(sampler2D(scale0, texture_sampler), uv);
+    vec4 s1 = texture(sampler2D(scale1, texture_sampler), uv);
+    vec4 s2 = texture(sampler2D(scale2, texture_sampler), uv);
+    vec4 s3 = texture(sampler2D(scale3, texture_sampler), uv);
+    vec4 s4 = texture(sampler2D(scale4, texture_sampler), uv);
+
+    float value = contrast_structure(s0, WEIGHTS[0])
+        * contrast_structure(s1, WEIGHTS[1])
+        * contrast_structure(s2, WEIGHTS[2])
+        * contrast_structure(s3, WEIGHTS[3])
+        * contrast_structure(s4, WEIGHTS[4])
+        // Luminance only contributes at the coarsest scale.
+        * pow(max(s4.y, 0.0), WEIGHTS[4]);
+
+    f_color = vec4(vec3(value), 1.0);
+}
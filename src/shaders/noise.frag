@@ -0,0 +1,157 @@
+#version 450
+layout (location = 0) in vec2 uv;
+layout (location = 0) out vec4 f_color;
+
+layout (set = 1, binding = 0) uniform Noise {
+    // x: the kind of noise, see the constants below.
+    // y: the seed.
+    // z: the number of octaves.
+    uvec4 config;
+    // x: the frequency of the first octave, in lattice cells per pixel.
+    // y: the factor of the frequency from one octave to the next, the lacunarity.
+    // z: the factor of the amplitude from one octave to the next, the gain.
+    vec4 fbm;
+} u_noise;
+
+// Must be kept in sync with `shaders::noise::Kind`.
+#define KIND_VALUE 0u
+#define KIND_PERLIN 1u
+#define KIND_SIMPLEX 2u
+#define KIND_WORLEY 3u
+
+/* The PCG hash, a permuted congruential generator with a single round.
+ * Reference: Jarzynski, Olano, Hash Functions for GPU Rendering, JCGT 2020
+ */
+uint pcg(uint v) {
+    uint state = v * 747796405u + 2891336453u;
+    uint word = ((state >> ((state >> 28u) + 4u)) ^ state) * 277803737u;
+    return (word >> 22u) ^ word;
+}
+
+/* Hash a lattice cell, the result depends on nothing but the seed and the cell. */
+uint hash_cell(ivec2 cell, uint seed) {
+    return pcg(uint(cell.x) + pcg(uint(cell.y) + seed));
+}
+
+/* The upper 24 bits of a hash as a float in [0, 1), which is exact. */
+float unit_float(uint hash) {
+    return float(hash >> 8) / 16777216.0;
+}
+
+/* One of eight gradients, the axes and diagonals. */
+vec2 gradient(uint hash) {
+    const vec2 GRADIENTS[8] = vec2[8](
+        vec2(1.0, 0.0), vec2(-1.0, 0.0), vec2(0.0, 1.0), vec2(0.0, -1.0),
+        vec2(1.0, 1.0), vec2(-1.0, 1.0), vec2(1.0, -1.0), vec2(-1.0, -1.0));
+    return GRADIENTS[hash & 7u];
+}
+
+/* The quintic interpolation of improved Perlin noise, with vanishing second derivative. */
+vec2 fade(vec2 t) {
+    return t * t * t * (t * (t * 6.0 - 15.0) + 10.0);
+}
+
+/* Each result is in the range [0, 1]. */
+float value_noise(vec2 p, uint seed) {
+    ivec2 cell = ivec2(floor(p));
+    vec2 w = fade(p - floor(p));
+
+    float v00 = unit_float(hash_cell(cell, seed));
+    float v10 = unit_float(hash_cell(cell + ivec2(1, 0), seed));
+    float v01 = unit_float(hash_cell(cell + ivec2(0, 1), seed));
+    float v11 = unit_float(hash_cell(cell + ivec2(1, 1), seed));
+
+    return mix(mix(v00, v10, w.x), mix(v01, v11, w.x), w.y);
+}
+
+float perlin_noise(vec2 p, uint seed) {
+    ivec2 cell = ivec2(floor(p));
+    vec2 f = p - floor(p);
+    vec2 w = fade(f);
+
+    float n00 = dot(gradient(hash_cell(cell, seed)), f);
+    float n10 = dot(gradient(hash_cell(cell + ivec2(1, 0), seed)), f - vec2(1.0, 0.0));
+    float n01 = dot(gradient(hash_cell(cell + ivec2(0, 1), seed)), f - vec2(0.0, 1.0));
+    float n11 = dot(gradient(hash_cell(cell + ivec2(1, 1), seed)), f - vec2(1.0, 1.0));
+
+    float n = mix(mix(n00, n10, w.x), mix(n01, n11, w.x), w.y);
+    return clamp(0.5 + 0.5 * n, 0.0, 1.0);
+}
+
+/* Reference: Gustavson, Simplex noise demystified, 2005 */
+float simplex_noise(vec2 p, uint seed) {
+    const float F2 = 0.36602540378;
+    const float G2 = 0.21132486540;
+
+    // Skew the input space to find the simplex cell, and the unskewed distance to its origin.
+    ivec2 cell = ivec2(floor(p + (p.x + p.y) * F2));
+    vec2 x0 = p - (vec2(cell) - float(cell.x + cell.y) * G2);
+
+    // The middle corner depends on the triangle of the cell we are in.
+    ivec2 middle = x0.x > x0.y ? ivec2(1, 0) : ivec2(0, 1);
+    vec2 x1 = x0 - vec2(middle) + G2;
+    vec2 x2 = x0 - 1.0 + 2.0 * G2;
+
+    vec3 t = max(0.5 - vec3(dot(x0, x0), dot(x1, x1), dot(x2, x2)), 0.0);
+    t = t * t;
+    t = t * t;
+
+    vec3 n = vec3(
+        dot(gradient(hash_cell(cell, seed)), x0),
+        dot(gradient(hash_cell(cell + middle, seed)), x1),
+        dot(gradient(hash_cell(cell + ivec2(1, 1), seed)), x2));
+
+    return clamp(0.5 + 35.0 * dot(t, n), 0.0, 1.0);
+}
+
+/* The distance to the closest feature point, with one feature point in each cell.
+ * Reference: Worley, A cellular texture basis function, SIGGRAPH 1996
+ */
+float worley_noise(vec2 p, uint seed) {
+    ivec2 cell = ivec2(floor(p));
+    vec2 f = p - floor(p);
+    float closest = 1.0;
+
+    for (int y = -1; y <= 1; y++) {
+        for (int x = -1; x <= 1; x++) {
+            ivec2 offset = ivec2(x, y);
+            uint hash = hash_cell(cell + offset, seed);
+            vec2 feature = vec2(offset) + vec2(unit_float(hash), unit_float(pcg(hash)));
+            closest = min(closest, distance(feature, f));
+        }
+    }
+
+    return closest;
+}
+
+float basis(vec2 p, uint seed) {
+    switch (u_noise.config.x) {
+    case KIND_VALUE: return value_noise(p, seed);
+    case KIND_PERLIN: return perlin_noise(p, seed);
+    case KIND_SIMPLEX: return simplex_noise(p, seed);
+    case KIND_WORLEY: return worley_noise(p, seed);
+    }
+
+    return 0.0;
+}
+
+void main() {
+    // Evaluated at the pixel center, such that the noise does not depend on the image size.
+    vec2 position = gl_FragCoord.xy;
+    float frequency = u_noise.fbm.x;
+    float amplitude = 1.0;
+    float sum = 0.0;
+    float total = 0.0;
+
+    // Fractional Brownian motion, each octave is independent noise with its own seed.
+    for (uint octave = 0u; octave < u_noise.config.z; octave++) {
+        uint seed = pcg(u_noise.config.y ^ pcg(octave));
+        sum += amplitude * basis(position * frequency, seed);
+        total += amplitude;
+        frequency *= u_noise.fbm.y;
+        amplitude *= u_noise.fbm.z;
+    }
+
+    float value = total > 0.0 ? sum / total : 0.0;
+    f_color = vec4(vec3(value), 1.0);
+}
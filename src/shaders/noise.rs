@@ -0,0 +1,59 @@
+use std::borrow::Cow;
+
+use super::{BufferInitContent, FragmentShaderData, FragmentShaderKey};
+
+/// Coherent noise, summed over octaves.
+pub const SHADER: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/spirv/noise.frag.v"));
+
+/// The largest number of octaves.
+pub(crate) const MAX_OCTAVES: u32 = 16;
+
+/// The basis function of each octave.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Kind {
+    Value = 0,
+    Perlin = 1,
+    Simplex = 2,
+    Worley = 3,
+}
+
+/// The noise shader, painting fractional Brownian motion of one noise basis.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Shader {
+    pub(crate) kind: Kind,
+    pub(crate) seed: u32,
+    /// The frequency of the first octave, in lattice cells per pixel.
+    pub(crate) frequency: f32,
+    pub(crate) octaves: u32,
+    pub(crate) lacunarity: f32,
+    pub(crate) gain: f32,
+}
+
+impl FragmentShaderData for Shader {
+    fn key(&self) -> Option<FragmentShaderKey> {
+        Some(FragmentShaderKey::Noise)
+    }
+
+    fn spirv_source(&self) -> Cow<'static, [u8]> {
+        Cow::Borrowed(SHADER)
+    }
+
+    fn binary_data(&self, buffer: &mut Vec<u8>) -> Option<BufferInitContent> {
+        let data: [u32; 8] = [
+            self.kind as u32,
+            self.seed,
+            self.octaves,
+            0,
+            self.frequency.to_bits(),
+            self.lacunarity.to_bits(),
+            self.gain.to_bits(),
+            0,
+        ];
+
+        Some(BufferInitContent::new(buffer, &data))
+    }
+
+    fn num_args(&self) -> u32 {
+        0
+    }
+}
@@ -0,0 +1,33 @@
+#version 450
+layout (location = 0) in vec2 uv;
+layout (location = 0) out vec4 f_color;
+
+layout (set = 1, binding = 0) uniform sampler texture_sampler;
+layout (set = 1, binding = 1) uniform texture2D in_texture;
+
+layout (set = 2, binding = 0) uniform Orient {
+    // x: if non-zero, swap the axes.
+    // y: if non-zero, flip along the width of the source.
+    // z: if non-zero, flip along the height of the source.
+    uvec4 orientation;
+} u_orient;
+
+void main() {
+    ivec2 size = textureSize(sampler2D(in_texture, texture_sampler), 0);
+    // Integer pixel indices only, no interpolated coordinates are involved.
+    ivec2 pixel = ivec2(gl_FragCoord.xy);
+
+    if (u_orient.orientation.x != 0u) {
+        pixel = pixel.yx;
+    }
+
+    if (u_orient.orientation.y != 0u) {
+        pixel.x = size.x - 1 - pixel.x;
+    }
+
+    if (u_orient.orientation.z != 0u) {
+        pixel.y = size.y - 1 - pixel.y;
+    }
+
+    f_color = texelFetch(sampler2D(in_texture, texture_sampler), pixel, 0);
+}
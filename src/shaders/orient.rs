@@ -0,0 +1,42 @@
+use std::borrow::Cow;
+
+use super::{BufferInitContent, FragmentShaderData, FragmentShaderKey};
+
+/// Reorient the pixel grid of an image, by transposing and flipping it.
+pub const SHADER: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/spirv/orient.frag.v"));
+
+/// The orientation shader, copying each pixel from exactly one source pixel.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Shader {
+    /// Whether to swap the axes, before flipping.
+    pub(crate) transpose: bool,
+    /// Whether to flip along the width of the source.
+    pub(crate) flip_width: bool,
+    /// Whether to flip along the height of the source.
+    pub(crate) flip_height: bool,
+}
+
+impl FragmentShaderData for Shader {
+    fn key(&self) -> Option<FragmentShaderKey> {
+        Some(FragmentShaderKey::Orient)
+    }
+
+    fn spirv_source(&self) -> Cow<'static, [u8]> {
+        Cow::Borrowed(SHADER)
+    }
+
+    fn binary_data(&self, buffer: &mut Vec<u8>) -> Option<BufferInitContent> {
+        let data: [u32; 4] = [
+            self.transpose as u32,
+            self.flip_width as u32,
+            self.flip_height as u32,
+            0,
+        ];
+
+        Some(BufferInitContent::new(buffer, &data))
+    }
+
+    fn num_args(&self) -> u32 {
+        1
+    }
+}
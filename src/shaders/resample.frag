@@ -0,0 +1,138 @@
+#version 450
+layout (location = 0) in vec2 uv;
+layout (location = 0) out vec4 f_color;
+
+layout (set = 1, binding = 0) uniform sampler texture_sampler;
+layout (set = 1, binding = 1) uniform texture2D in_texture;
+
+layout (set = 2, binding = 0) uniform Resample {
+    // x: the number of source pixels per target pixel along the axis.
+    // y: the support of the filter, the radius where it is non-zero.
+    vec4 geometry;
+    // x: the filter, see the constants below.
+    // y: the axis, 0 for width and 1 for height.
+    uvec4 params;
+} u_resample;
+
+// Must be kept in sync with `command::ResizeFilter`.
+#define FILTER_TRIANGLE 0u
+#define FILTER_CATMULL_ROM 1u
+#define FILTER_MITCHELL 2u
+#define FILTER_LANCZOS2 3u
+#define FILTER_LANCZOS3 4u
+#define FILTER_AREA 5u
+
+const float PI = 3.14159265358979;
+
+// The cubic splines of Mitchell and Netravali, parameterized by B and C.
+float bc_cubic(float x, float b, float c) {
+    float a = abs(x);
+    float k = 0.0;
+
+    if (a < 1.0) {
+        k = (12.0 - 9.0 * b - 6.0 * c) * a * a * a
+            + (-18.0 + 12.0 * b + 6.0 * c) * a * a
+            + (6.0 - 2.0 * b);
+    } else if (a < 2.0) {
+        k = (-b - 6.0 * c) * a * a * a
+            + (6.0 * b + 30.0 * c) * a * a
+            + (-12.0 * b - 48.0 * c) * a
+            + (8.0 * b + 24.0 * c);
+    }
+
+    return k / 6.0;
+}
+
+float sinc(float x) {
+    if (x == 0.0) {
+        return 1.0;
+    }
+
+    float a = x * PI;
+    return sin(a) / a;
+}
+
+float lanczos(float x, float lobes) {
+    if (abs(x) < lobes) {
+        return sinc(x) * sinc(x / lobes);
+    }
+
+    return 0.0;
+}
+
+float kernel(float x) {
+    switch (u_resample.params.x) {
+    case FILTER_TRIANGLE:
+        return max(0.0, 1.0 - abs(x));
+    case FILTER_CATMULL_ROM:
+        return bc_cubic(x, 0.0, 0.5);
+    case FILTER_MITCHELL:
+        return bc_cubic(x, 1.0 / 3.0, 1.0 / 3.0);
+    case FILTER_LANCZOS2:
+        return lanczos(x, 2.0);
+    case FILTER_LANCZOS3:
+        return lanczos(x, 3.0);
+    default:
+        return 0.0;
+    }
+}
+
+void main() {
+    ivec2 size = textureSize(sampler2D(in_texture, texture_sampler), 0);
+    bool along_height = u_resample.params.y != 0u;
+
+    int axis_size = along_height ? size.y : size.x;
+    int other_size = along_height ? size.x : size.y;
+    float axis_uv = along_height ? uv.y : uv.x;
+    float other_uv = along_height ? uv.x : uv.y;
+
+    // Both textures span the same uv range, this is the target pixel center
+    // in coordinates of the source.
+    float center = axis_uv * float(axis_size);
+    int other = clamp(int(floor(other_uv * float(other_size))), 0, other_size - 1);
+
+    // When downscaling, stretch the filter to cover all source pixels.
+    float filter_scale = max(u_resample.geometry.x, 1.0);
+    float support = u_resample.geometry.y * filter_scale;
+
+    // Pixels outside the image are left out, weights are normalized below.
+    int first = max(int(floor(center - support)), 0);
+    int last = min(int(ceil(center + support)), axis_size - 1);
+
+    vec4 sum = vec4(0.0);
+    float weights = 0.0;
+
+    // The footprint of the target pixel, for averaging by area.
+    float footprint_min = center - 0.5 * u_resample.geometry.x;
+    float footprint_max = center + 0.5 * u_resample.geometry.x;
+
+    for (int i = first; i <= last; i++) {
+        float weight;
+
+        if (u_resample.params.x == FILTER_AREA) {
+            // The length of the source pixel covered by the footprint.
+            weight = max(0.0, min(float(i + 1), footprint_max) - max(float(i), footprint_min));
+        } else {
+            weight = kernel((float(i) + 0.5 - center) / filter_scale);
+        }
+
+        ivec2 at = along_height ? ivec2(other, i) : ivec2(i, other);
+        vec4 texel = texelFetch(sampler2D(in_texture, texture_sampler), at, 0);
+
+        sum += weight * vec4(texel.rgb * texel.a, texel.a);
+        weights += weight;
+    }
+
+    if (weights != 0.0) {
+        sum /= weights;
+    }
+
+    // Negative lobes may overshoot, but the alpha must stay meaningful.
+    float alpha = clamp(sum.a, 0.0, 1.0);
+
+    if (sum.a > 0.0) {
+        f_color = vec4(sum.rgb / sum.a, alpha);
+    } else {
+        f_color = vec4(0.0);
+    }
+}
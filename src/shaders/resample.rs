@@ -0,0 +1,48 @@
+use std::borrow::Cow;
+
+use super::{BufferInitContent, FragmentShaderData, FragmentShaderKey};
+
+/// Resample an image along one of its axes with a reconstruction filter.
+pub const SHADER: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/spirv/resample.frag.v"));
+
+/// The resampling shader, one pass of a two-pass resize.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Shader {
+    /// The filter, as defined in the shader source.
+    pub(crate) filter: u32,
+    /// The radius where the filter is non-zero, at a scale of one.
+    pub(crate) support: f32,
+    /// The number of source pixels per target pixel.
+    pub(crate) scale: f32,
+    /// Whether to resample along the height instead of the width.
+    pub(crate) along_height: bool,
+}
+
+impl FragmentShaderData for Shader {
+    fn key(&self) -> Option<FragmentShaderKey> {
+        Some(FragmentShaderKey::Resample)
+    }
+
+    fn spirv_source(&self) -> Cow<'static, [u8]> {
+        Cow::Borrowed(SHADER)
+    }
+
+    fn binary_data(&self, buffer: &mut Vec<u8>) -> Option<BufferInitContent> {
+        let data: [u32; 8] = [
+            self.scale.to_bits(),
+            self.support.to_bits(),
+            0,
+            0,
+            self.filter,
+            self.along_height as u32,
+            0,
+            0,
+        ];
+
+        Some(BufferInitContent::new(buffer, &data))
+    }
+
+    fn num_args(&self) -> u32 {
+        1
+    }
+}
@@ -0,0 +1,54 @@
+#version 450
+#extension GL_GOOGLE_include_directive : require
+layout (location = 0) in vec2 uv;
+layout (location = 0) out vec4 f_color;
+
+layout (set = 1, binding = 0) uniform sampler texture_sampler;
+layout (set = 1, binding = 1) uniform texture2D in_texture;
+
+layout (set = 2, binding = 0) uniform Separable {
+    // xy: the pixel step between two taps, one of them is zero.
+    vec4 direction;
+    // x: the number of taps, y: the index of the tap on the center pixel.
+    // z: the border mode, see `border.glsl`.
+    // w: if non-zero, weight colors by their alpha.
+    uvec4 taps;
+    // The color of pixels outside the image, with a constant border.
+    vec4 border_color;
+    // The tap weights, packed four per entry.
+    vec4 weights[64];
+} u_kernel;
+
+#include "border.glsl"
+
+vec4 fetch(ivec2 at, ivec2 size) {
+    return fetch_border(in_texture, at, size, u_kernel.taps.z, u_kernel.border_color);
+}
+
+void main() {
+    ivec2 size = textureSize(sampler2D(in_texture, texture_sampler), 0);
+    ivec2 center = ivec2(floor(uv * vec2(size)));
+    ivec2 stride = ivec2(u_kernel.direction.xy);
+    bool premultiplied = u_kernel.taps.w != 0u;
+
+    vec4 sum = vec4(0.0);
+    for (uint i = 0u; i < u_kernel.taps.x; i++) {
+        int offset = int(i) - int(u_kernel.taps.y);
+        vec4 texel = fetch(center + offset * stride, size);
+        float weight = u_kernel.weights[i / 4u][i % 4u];
+
+        if (premultiplied) {
+            texel.rgb *= texel.a;
+        }
+
+        sum += weight * texel;
+    }
+
+    if (!premultiplied) {
+        f_color = sum;
+    } else if (sum.a > 0.0) {
+        f_color = vec4(sum.rgb / sum.a, sum.a);
+    } else {
+        f_color = vec4(0.0);
+    }
+}
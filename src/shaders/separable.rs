@@ -0,0 +1,62 @@
+use std::borrow::Cow;
+
+use super::{BufferInitContent, FragmentShaderData, FragmentShaderKey};
+
+/// Convolve an image with a one-dimensional kernel along one of its axes.
+pub const SHADER: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/spirv/separable.frag.v"));
+
+/// The maximum number of taps supported by the shader.
+pub(crate) const MAX_TAPS: usize = 256;
+
+/// The separable convolution shader, one pass of a two-pass filter.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Shader {
+    /// The step between two taps in pixels, `[1, 0]` along the width or `[0, 1]` along the height.
+    pub(crate) direction: [i32; 2],
+    /// The weights of the taps, at most `MAX_TAPS`.
+    pub(crate) weights: Vec<f32>,
+    /// The index of the weight applied to the center pixel.
+    pub(crate) center: u32,
+    /// How pixels outside the image are sampled, as defined in the shader source.
+    pub(crate) border: u32,
+    /// The color of pixels outside the image, for a constant border.
+    pub(crate) border_color: [f32; 4],
+    /// Whether to weight colors by their alpha, instead of treating all channels alike.
+    pub(crate) premultiplied: bool,
+}
+
+impl FragmentShaderData for Shader {
+    fn key(&self) -> Option<FragmentShaderKey> {
+        Some(FragmentShaderKey::Separable)
+    }
+
+    fn spirv_source(&self) -> Cow<'static, [u8]> {
+        Cow::Borrowed(SHADER)
+    }
+
+    fn binary_data(&self, buffer: &mut Vec<u8>) -> Option<BufferInitContent> {
+        let [x, y] = self.direction;
+        let mut data = [0u32; 12 + MAX_TAPS];
+
+        data[0] = (x as f32).to_bits();
+        data[1] = (y as f32).to_bits();
+        data[4] = self.weights.len() as u32;
+        data[5] = self.center;
+        data[6] = self.border;
+        data[7] = self.premultiplied as u32;
+
+        for (slot, value) in data[8..12].iter_mut().zip(&self.border_color) {
+            *slot = value.to_bits();
+        }
+
+        for (slot, weight) in data[12..].iter_mut().zip(&self.weights) {
+            *slot = weight.to_bits();
+        }
+
+        Some(BufferInitContent::new(buffer, &data[..]))
+    }
+
+    fn num_args(&self) -> u32 {
+        1
+    }
+}
@@ -0,0 +1,69 @@
+#version 450
+#extension GL_GOOGLE_include_directive : require
+layout (location = 0) in vec2 uv;
+layout (location = 0) out vec4 f_color;
+
+// The two images being compared, of the same size.
+layout (set = 1, binding = 0) uniform sampler texture_sampler;
+layout (set = 1, binding = 1) uniform texture2D lhs;
+layout (set = 1, binding = 2) uniform texture2D rhs;
+
+layout (set = 2, binding = 0) uniform Ssim {
+    // xyz: the weights of the luma of lhs in the linear color representation.
+    vec4 luma_lhs;
+    // xyz: the weights of the luma of rhs in the linear color representation.
+    vec4 luma_rhs;
+} u_ssim;
+
+#include "border.glsl"
+
+// The Gaussian window of the reference implementation, 11×11 with a standard deviation of 1.5.
+#define RADIUS 5
+#define SIGMA 1.5
+
+// Stabilizing constants for a dynamic range of 1.0.
+#define C1 (0.01 * 0.01)
+#define C2 (0.03 * 0.03)
+
+void main() {
+    ivec2 size = textureSize(sampler2D(lhs, texture_sampler), 0);
+    ivec2 center = ivec2(gl_FragCoord.xy);
+
+    float weights = 0.0;
+    float mean_x = 0.0;
+    float mean_y = 0.0;
+    float mean_xx = 0.0;
+    float mean_yy = 0.0;
+    float mean_xy = 0.0;
+
+    for (int dy = -RADIUS; dy <= RADIUS; dy++) {
+        for (int dx = -RADIUS; dx <= RADIUS; dx++) {
+            float weight = exp(-float(dx * dx + dy * dy) / (2.0 * SIGMA * SIGMA));
+            ivec2 pixel = center + ivec2(dx, dy);
+
+            vec4 texel_x = fetch_border(lhs, pixel, size, BORDER_CLAMP, vec4(0.0));
+            vec4 texel_y = fetch_border(rhs, pixel, size, BORDER_CLAMP, vec4(0.0));
+            float x = dot(texel_x.rgb, u_ssim.luma_lhs.xyz);
+            float y = dot(texel_y.rgb, u_ssim.luma_rhs.xyz);
+
+            weights += weight;
+            mean_x += weight * x;
+            mean_y += weight * y;
+            mean_xx += weight * x * x;
+            mean_yy += weight * y * y;
+            mean_xy += weight * x * y;
+        }
+    }
+
+    mean_x /= weights;
+    mean_y /= weights;
+    float var_x = mean_xx / weights - mean_x * mean_x;
+    float var_y = mean_yy / weights - mean_y * mean_y;
+    float covar = mean_xy / weights - mean_x * mean_y;
+
+    float luminance = (2.0 * mean_x * mean_y + C1) / (mean_x * mean_x + mean_y * mean_y + C1);
+    float contrast_structure = (2.0 * covar + C2) / (var_x + var_y + C2);
+
+    // The map itself, then the components for combining multiple scales.
+    f_color = vec4(max(luminance * contrast_structure, 0.0), luminance, contrast_structure, 1.0);
+}
@@ -0,0 +1,60 @@
+use std::borrow::Cow;
+
+use super::{BufferInitContent, FragmentShaderData, FragmentShaderKey};
+
+/// Compare two images with the structural similarity index.
+pub const SHADER: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/spirv/ssim.frag.v"));
+
+/// Combine the components of SSIM at several scales into MS-SSIM.
+pub const MULTI_SCALE: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/spirv/ms_ssim.frag.v"));
+
+/// The number of scales combined into MS-SSIM, each half the size of the previous.
+pub(crate) const SCALES: u32 = 5;
+
+/// The SSIM shader, computing the map and its components for a single scale.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Shader {
+    /// The weights computing luma from the linear color representation of the lhs.
+    pub(crate) luma_lhs: [f32; 3],
+    /// The weights computing luma from the linear color representation of the rhs.
+    pub(crate) luma_rhs: [f32; 3],
+}
+
+/// The MS-SSIM shader, combining the components of all scales.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct MultiScale;
+
+impl FragmentShaderData for Shader {
+    fn key(&self) -> Option<FragmentShaderKey> {
+        Some(FragmentShaderKey::Ssim)
+    }
+
+    fn spirv_source(&self) -> Cow<'static, [u8]> {
+        Cow::Borrowed(SHADER)
+    }
+
+    fn binary_data(&self, buffer: &mut Vec<u8>) -> Option<BufferInitContent> {
+        let [r, g, b] = self.luma_lhs;
+        let [s, t, u] = self.luma_rhs;
+        let data: [f32; 8] = [r, g, b, 0.0, s, t, u, 0.0];
+        Some(BufferInitContent::new(buffer, &data))
+    }
+
+    fn num_args(&self) -> u32 {
+        2
+    }
+}
+
+impl FragmentShaderData for MultiScale {
+    fn key(&self) -> Option<FragmentShaderKey> {
+        Some(FragmentShaderKey::MultiScaleSsim)
+    }
+
+    fn spirv_source(&self) -> Cow<'static, [u8]> {
+        Cow::Borrowed(MULTI_SCALE)
+    }
+
+    fn num_args(&self) -> u32 {
+        SCALES
+    }
+}
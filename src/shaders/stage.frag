@@ -296,12 +296,12 @@ float transfer_eo_bt470m(float val) {
 
 // Used Reference: BT.601-7, Section 2.6.4
 float transfer_oe_bt601(float val) {
-  return transfer_eo_bt709(val);
+  return transfer_oe_bt709(val);
 }
 
 // Used Reference: BT.601-7, Section 2.6.4
 float transfer_eo_bt601(float val) {
-  return transfer_oe_bt709(val);
+  return transfer_eo_bt709(val);
 }
 
 // Used Reference: https://www.kernel.org/doc/html/v4.11/media/uapi/v4l/pixfmt-007.html#colorspace-smpte-240m-v4l2-colorspace-smpte240m
@@ -529,7 +529,7 @@ vec4 demux_uint(uint num, uint kind) {
   case SAMPLE_BITS_Inti444:
     return vec4(num & MASK4, (num >> 4) & MASK4, (num >> 8) & MASK4, MASK4) / BITS4;
   case SAMPLE_BITS_Int444i:
-    return vec4((num >> 4) & MASK4, (num >> 9) & MASK4, (num >> 12) & MASK4, MASK4) / BITS4;
+    return vec4((num >> 4) & MASK4, (num >> 8) & MASK4, (num >> 12) & MASK4, MASK4) / BITS4;
   case SAMPLE_BITS_Int565:
     return vec4(num & MASK5, (num >> 5) & MASK6, num >> 11, 1.0) / vec4(BITS5, BITS6, BITS5, 1.0);
   case SAMPLE_BITS_Int8x2:
@@ -658,7 +658,7 @@ vec4 parts_normalize(vec4 components, uint parts) {
   case SAMPLE_PARTS_Rgba:
     return components.xyzw;
   case SAMPLE_PARTS_Bgra:
-    return components.xyzw;
+    return components.zyxw;
   case SAMPLE_PARTS_Argb:
     return components.yzwx;
   case SAMPLE_PARTS_Abgr:
@@ -69,6 +69,146 @@ impl XyzParameter {
     pub(crate) fn linear_format(&self) -> TextureFormat {
         TextureFormat::Rgba16Float
     }
+
+    /// Decode a single texel on the host into its linear representation.
+    ///
+    /// This performs the same stages as the decoding entry points of `stage.frag`, that is
+    /// `demux_uint`, then `parts_normalize`, then `parts_untransfer`. Returns `None` if the data
+    /// is not exactly one texel or if its bits are not supported by staging.
+    pub(crate) fn decode_texel(&self, data: &[u8]) -> Option<[f32; 4]> {
+        if data.len() != self.bits.bytes() || data.len() > 4 {
+            return None;
+        }
+
+        let mut bytes = [0; 4];
+        bytes[..data.len()].copy_from_slice(data);
+        // The stage textures are little endian 32-bit unsigned integers.
+        let num = u32::from_le_bytes(bytes);
+
+        let components = demux_uint(num, self.bits)?;
+        let electrical = parts_normalize(components, self.parts)?;
+        Some(parts_untransfer(electrical, self.transfer))
+    }
+}
+
+/// Returns the parts in canonical order, see `demux_uint` in `stage.frag`.
+fn demux_uint(num: u32, bits: SampleBits) -> Option<[f32; 4]> {
+    use SampleBits::*;
+    // Extract the field of `width` bits at `shift`, as normalized value.
+    let f = |shift: u32, width: u32| {
+        let max = (1u32 << width) - 1;
+        ((num >> shift) & max) as f32 / max as f32
+    };
+
+    Some(match bits {
+        Int8 => [f(0, 8); 4],
+        Int332 => [f(0, 2), f(2, 3), f(5, 3), 1.0],
+        Int233 => [f(0, 3), f(3, 3), f(6, 2), 1.0],
+        Int16 => [f(0, 16); 4],
+        Int4x4 => [f(0, 4), f(4, 4), f(8, 4), f(12, 4)],
+        Int_444 => [f(0, 4), f(4, 4), f(8, 4), 1.0],
+        Int444_ => [f(4, 4), f(8, 4), f(12, 4), 1.0],
+        Int565 => [f(0, 5), f(5, 6), f(11, 5), 1.0],
+        Int8x2 => [f(0, 8), 0.0, 0.0, f(8, 8)],
+        Int8x3 => [f(0, 8), f(8, 8), f(16, 8), 1.0],
+        Int8x4 => [f(0, 8), f(8, 8), f(16, 8), f(24, 8)],
+        Int16x2 => [f(0, 16), f(16, 16), 0.0, 1.0],
+        Int1010102 => [f(0, 2), f(2, 10), f(12, 10), f(22, 10)],
+        Int2101010 => [f(0, 10), f(10, 10), f(20, 10), f(30, 2)],
+        Int101010_ => [f(2, 10), f(12, 10), f(22, 10), 1.0],
+        Int_101010 => [f(0, 10), f(10, 10), f(20, 10), 1.0],
+        // Above 32-bits, not supported by staging.
+        Int16x3 | Int16x4 | Float16x4 | Float32x4 => return None,
+    })
+}
+
+/// Swap the parts into the canonical location, see `parts_normalize` in `stage.frag`.
+fn parts_normalize(c: [f32; 4], parts: SampleParts) -> Option<[f32; 4]> {
+    use SampleParts::*;
+    let [x, y, z, w] = c;
+    Some(match parts {
+        A => [0.0, 0.0, 0.0, x],
+        R => [x, 0.0, 0.0, 1.0],
+        G => [0.0, x, 0.0, 1.0],
+        B => [0.0, 0.0, x, 1.0],
+        Luma => [x, x, x, 1.0],
+        LumaA => [x, x, x, w],
+        Rgb | Rgb_ => [x, y, z, 1.0],
+        Bgr | Bgr_ => [z, y, x, 1.0],
+        Rgba => [x, y, z, w],
+        Bgra => [z, y, x, w],
+        Argb => [y, z, w, x],
+        Abgr => [w, z, y, x],
+        _Rgb => [y, z, w, 1.0],
+        _Bgr => [w, z, y, 1.0],
+        Lab | LCh => [x, y, z, 1.0],
+        LabA | LChA => [x, y, z, w],
+        Yuv => return None,
+    })
+}
+
+/// Undo the transfer function, see `parts_untransfer` in `stage.frag`.
+fn parts_untransfer(c: [f32; 4], transfer: Transfer) -> [f32; 4] {
+    let [x, y, z, w] = c;
+    let with_xyz = |f: fn(f32) -> f32| [f(x), f(y), f(z), w];
+
+    match transfer {
+        Transfer::Rgb(transfer) => match transfer {
+            RgbTransfer::Bt709 | RgbTransfer::Bt601 => with_xyz(transfer_eo_bt709),
+            RgbTransfer::Bt2020_10bit | RgbTransfer::Bt2020_12bit => with_xyz(transfer_eo_bt709),
+            RgbTransfer::Bt470M => with_xyz(|v| v.powf(2.2)),
+            RgbTransfer::Smpte240 => with_xyz(transfer_eo_smpte240),
+            RgbTransfer::Srgb => with_xyz(transfer_eo_srgb),
+            RgbTransfer::Smpte2084 | RgbTransfer::Bt2100Pq => with_xyz(transfer_eo_smpte2084),
+            // FIXME: unimplemented, as in the shader.
+            RgbTransfer::Bt2100Hlg => c,
+            RgbTransfer::Linear | RgbTransfer::Bt2100Scene => c,
+        },
+        Transfer::Oklab => {
+            let angle = core::f32::consts::PI * 2.0 * (z - 0.5);
+            [x, y * angle.cos(), y * angle.sin(), w]
+        }
+    }
+}
+
+fn transfer_eo_bt709(val: f32) -> f32 {
+    // The encoded value of the linear threshold 0.018.
+    if val >= 1.099 * 0.018f32.powf(0.45) - 0.099 {
+        ((val + 0.099) / 1.099).powf(1.0 / 0.45)
+    } else {
+        val / 4.5
+    }
+}
+
+fn transfer_eo_smpte240(val: f32) -> f32 {
+    if val < 0.0913 {
+        val / 4.0
+    } else {
+        ((val - 0.1115) / 1.1115).powf(1.0 / 0.45)
+    }
+}
+
+fn transfer_eo_srgb(val: f32) -> f32 {
+    if val < -0.04045 {
+        -((-val + 0.055) / 1.055).powf(2.4)
+    } else if val <= 0.04045 {
+        val / 12.92
+    } else {
+        ((val + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn transfer_eo_smpte2084(val: f32) -> f32 {
+    const M1: f32 = 2610.0 / 16384.0;
+    const M2: f32 = 2523.0 / 4096.0;
+    const C1: f32 = 3424.0 / 4096.0;
+    const C2: f32 = 2413.0 / 128.0;
+    const C3: f32 = 2392.0 / 128.0;
+
+    let n = val.powf(1.0 / M2);
+    let nom = (n - C1).max(0.0);
+    let denom = C2 - C3 * n;
+    (nom / denom).powf(1.0 / M1)
 }
 
 impl StageKind {
@@ -169,3 +309,26 @@ impl From<RgbTransfer> for Transfer {
         Transfer::Rgb(t)
     }
 }
+
+#[test]
+fn decode_texel() {
+    let srgb = XyzParameter {
+        bits: SampleBits::Int8x4,
+        parts: SampleParts::Bgra,
+        transfer: RgbTransfer::Srgb.into(),
+    };
+
+    assert_eq!(
+        srgb.decode_texel(&[0, 0, 255, 128]),
+        Some([1.0, 0.0, 0.0, 128.0 / 255.0])
+    );
+    assert_eq!(srgb.decode_texel(&[0, 0, 255]), None);
+
+    let luma = XyzParameter {
+        bits: SampleBits::Int16,
+        parts: SampleParts::Luma,
+        transfer: RgbTransfer::Linear.into(),
+    };
+
+    assert_eq!(luma.decode_texel(&[0xff, 0xff]), Some([1.0; 4]));
+}
@@ -0,0 +1,139 @@
+#version 450
+#extension GL_GOOGLE_include_directive : require
+layout (location = 0) in vec2 uv;
+layout (location = 0) out vec4 f_color;
+
+layout (set = 1, binding = 0) uniform sampler texture_sampler;
+layout (set = 1, binding = 1) uniform texture2D in_texture;
+
+layout (set = 2, binding = 0) uniform Statistics {
+    // x: the pass, see the constants below.
+    // y: the level of the input of a merge, each of its rows covers 2^level rows of the image.
+    // z: if non-zero, encode the result with the most significant byte first.
+    uvec4 config;
+    // xy: the width and height of the image.
+    uvec4 size;
+} u_statistics;
+
+// Must be kept in sync with `shaders::statistics::Pass`.
+#define PASS_ROWS 0u
+#define PASS_MERGE 1u
+#define PASS_FINISH 2u
+
+// The partial statistics of a range of rows, each in a cell of four columns.
+#define ROW_MIN 0
+#define ROW_MAX 1
+#define ROW_MEAN 2
+#define ROW_M2 3
+
+// The result, must be kept in sync with `command::Statistics::from_words`.
+#define OUT_MIN 0
+#define OUT_MAX 1
+#define OUT_SUM 2
+#define OUT_MEAN 3
+#define OUT_VARIANCE 4
+
+#define FLT_MAX 3.402823466e+38
+
+#include "words.glsl"
+
+float partial(int channel, int stat, int row) {
+    vec4 encoded = texelFetch(sampler2D(in_texture, texture_sampler), ivec2(4 * stat + channel, row), 0);
+    return uintBitsToFloat(decode_word(encoded, false));
+}
+
+/* The number of pixels covered by a row of the input of a merge. */
+float count_of(int row) {
+    int rows = 1 << int(u_statistics.config.y);
+    int covered = min(rows, int(u_statistics.size.y) - row * rows);
+    return float(u_statistics.size.x) * float(covered);
+}
+
+/* One statistic of one channel of an image row. Only the second moment needs the mean along. */
+float reduce_row(int channel, int stat, int row) {
+    int width = textureSize(sampler2D(in_texture, texture_sampler), 0).x;
+    float minimum = FLT_MAX;
+    float maximum = -FLT_MAX;
+    float mean = 0.0;
+    float m2 = 0.0;
+
+    for (int x = 0; x < width; x++) {
+        float value = texelFetch(sampler2D(in_texture, texture_sampler), ivec2(x, row), 0)[channel];
+
+        if (stat == ROW_MIN) {
+            minimum = min(minimum, value);
+        } else if (stat == ROW_MAX) {
+            maximum = max(maximum, value);
+        } else {
+            // Welford's algorithm.
+            float delta = value - mean;
+            mean += delta / float(x + 1);
+            m2 += delta * (value - mean);
+        }
+    }
+
+    float result[4] = float[4](minimum, maximum, mean, m2);
+    return result[stat];
+}
+
+/* One statistic of one channel of two adjacent rows of partial statistics.
+ *
+ * Reference: Chan, Golub, LeVeque, Updating formulae and a pairwise algorithm for computing
+ * sample variances.
+ */
+float merge(int channel, int stat, int first) {
+    int rows = textureSize(sampler2D(in_texture, texture_sampler), 0).y;
+    int second = first + 1;
+    float lhs = partial(channel, stat, first);
+
+    // The last row of an odd number of rows stays on its own.
+    if (second >= rows) {
+        return lhs;
+    }
+
+    float rhs = partial(channel, stat, second);
+
+    if (stat == ROW_MIN) {
+        return min(lhs, rhs);
+    } else if (stat == ROW_MAX) {
+        return max(lhs, rhs);
+    }
+
+    float count_lhs = count_of(first);
+    float count_rhs = count_of(second);
+    float merged = count_lhs + count_rhs;
+    float delta = partial(channel, ROW_MEAN, second) - partial(channel, ROW_MEAN, first);
+
+    if (stat == ROW_MEAN) {
+        return lhs + delta * count_rhs / merged;
+    } else {
+        return lhs + rhs + delta * delta * count_lhs * count_rhs / merged;
+    }
+}
+
+void main() {
+    ivec2 coord = ivec2(gl_FragCoord.xy);
+    int channel = coord.x % 4;
+    int stat = coord.x / 4;
+
+    if (u_statistics.config.x == PASS_ROWS) {
+        // The input is the image, the output has one row of partial statistics per image row.
+        f_color = encode_word(floatBitsToUint(reduce_row(channel, stat, coord.y)), false);
+    } else if (u_statistics.config.x == PASS_MERGE) {
+        // Each output row merges two input rows.
+        f_color = encode_word(floatBitsToUint(merge(channel, stat, 2 * coord.y)), false);
+    } else {
+        // Merge the at most two remaining rows into the 4×5 result, one channel per column.
+        channel = coord.x;
+        float count = float(u_statistics.size.x) * float(u_statistics.size.y);
+        float mean = merge(channel, ROW_MEAN, 0);
+
+        float result[5] = float[5](
+            merge(channel, ROW_MIN, 0),
+            merge(channel, ROW_MAX, 0),
+            mean * count,
+            mean,
+            merge(channel, ROW_M2, 0) / count);
+        f_color = encode_word(floatBitsToUint(result[coord.y]), u_statistics.config.z != 0u);
+    }
+}
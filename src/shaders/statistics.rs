@@ -0,0 +1,60 @@
+use std::borrow::Cow;
+
+use super::{BufferInitContent, FragmentShaderData, FragmentShaderKey};
+
+/// Reduce an image to the minimum, maximum, sum, mean and variance of each channel.
+pub const SHADER: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/spirv/statistics.frag.v"));
+
+/// One of the passes of the reduction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Pass {
+    /// Reduce each row, into a 16 pixel wide texture with one row per image row.
+    Rows = 0,
+    /// Merge pairs of rows, halving their number.
+    Merge = 1,
+    /// Merge the last one or two rows into the 4×5 result.
+    Finish = 2,
+}
+
+/// The statistics shader, a single pass of the reduction.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Shader {
+    /// The pass to perform.
+    pub(crate) pass: Pass,
+    /// The number of merges before this pass, each input row covers `2^level` image rows.
+    pub(crate) level: u32,
+    /// The number of pixels in each row of the image.
+    pub(crate) width: u32,
+    /// The number of rows of the image.
+    pub(crate) height: u32,
+}
+
+impl FragmentShaderData for Shader {
+    fn key(&self) -> Option<FragmentShaderKey> {
+        Some(FragmentShaderKey::Statistics)
+    }
+
+    fn spirv_source(&self) -> Cow<'static, [u8]> {
+        Cow::Borrowed(SHADER)
+    }
+
+    fn binary_data(&self, buffer: &mut Vec<u8>) -> Option<BufferInitContent> {
+        // The result is read as `u32` on the host, so we write its bytes in its order.
+        let big_endian = cfg!(target_endian = "big");
+        let data: [u32; 8] = [
+            self.pass as u32,
+            self.level,
+            big_endian as u32,
+            0,
+            self.width,
+            self.height,
+            0,
+            0,
+        ];
+        Some(BufferInitContent::new(buffer, &data))
+    }
+
+    fn num_args(&self) -> u32 {
+        1
+    }
+}
@@ -0,0 +1,66 @@
+#version 450
+#extension GL_GOOGLE_include_directive : require
+layout (location = 0) in vec2 uv;
+layout (location = 0) out vec4 f_color;
+
+// The image to sample from and the displacement of each pixel.
+layout (set = 1, binding = 0) uniform sampler texture_sampler;
+layout (set = 1, binding = 1) uniform texture2D in_texture;
+layout (set = 1, binding = 2) uniform texture2D displacement;
+
+layout (set = 2, binding = 0) uniform Warp {
+    // x: the factor from displacement values to pixels.
+    // y: the displacement value of no offset.
+    vec4 displace;
+    // x: the border mode, see `border.glsl`.
+    // y: if non-zero, interpolate bi-linearly instead of choosing the nearest pixel.
+    uvec4 mode;
+    // The color of pixels outside the image, with a constant border.
+    vec4 border_color;
+} u_warp;
+
+#include "border.glsl"
+
+vec4 fetch(ivec2 at, ivec2 size) {
+    return fetch_border(in_texture, at, size, u_warp.mode.x, u_warp.border_color);
+}
+
+vec4 premultiply(vec4 color) {
+    return vec4(color.rgb * color.a, color.a);
+}
+
+void main() {
+    ivec2 size = textureSize(sampler2D(in_texture, texture_sampler), 0);
+    ivec2 center = ivec2(floor(uv * vec2(size)));
+
+    vec2 offset = texelFetch(sampler2D(displacement, texture_sampler), center, 0).rg;
+    offset = u_warp.displace.x * (offset - u_warp.displace.y);
+
+    // The position to sample, in pixel coordinates where centers are at half integers.
+    vec2 position = vec2(center) + 0.5 + offset;
+
+    if (u_warp.mode.y == 0u) {
+        f_color = fetch(ivec2(floor(position)), size);
+        return;
+    }
+
+    vec2 corner = position - 0.5;
+    ivec2 base = ivec2(floor(corner));
+    vec2 t = corner - floor(corner);
+
+    vec4 top = mix(
+        premultiply(fetch(base, size)),
+        premultiply(fetch(base + ivec2(1, 0), size)),
+        t.x);
+    vec4 bottom = mix(
+        premultiply(fetch(base + ivec2(0, 1), size)),
+        premultiply(fetch(base + ivec2(1, 1), size)),
+        t.x);
+    vec4 color = mix(top, bottom, t.y);
+
+    if (color.a > 0.0) {
+        f_color = vec4(color.rgb / color.a, color.a);
+    } else {
+        f_color = vec4(0.0);
+    }
+}
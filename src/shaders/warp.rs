@@ -0,0 +1,55 @@
+use std::borrow::Cow;
+
+use super::{BufferInitContent, FragmentShaderData, FragmentShaderKey};
+
+/// Sample an image at positions offset by a displacement image.
+pub const SHADER: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/spirv/warp.frag.v"));
+
+/// The displacement warp shader.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Shader {
+    /// The factor from displacement values to pixels.
+    pub(crate) scale: f32,
+    /// The displacement value corresponding to no offset.
+    pub(crate) neutral: f32,
+    /// How pixels outside the image are sampled, as defined in the shader source.
+    pub(crate) border: u32,
+    /// The color of pixels outside the image, for a constant border.
+    pub(crate) border_color: [f32; 4],
+    /// Whether to interpolate bi-linearly instead of choosing the nearest pixel.
+    pub(crate) bilinear: bool,
+}
+
+impl FragmentShaderData for Shader {
+    fn key(&self) -> Option<FragmentShaderKey> {
+        Some(FragmentShaderKey::Warp)
+    }
+
+    fn spirv_source(&self) -> Cow<'static, [u8]> {
+        Cow::Borrowed(SHADER)
+    }
+
+    fn binary_data(&self, buffer: &mut Vec<u8>) -> Option<BufferInitContent> {
+        let [r, g, b, a] = self.border_color;
+        let data: [u32; 12] = [
+            self.scale.to_bits(),
+            self.neutral.to_bits(),
+            0,
+            0,
+            self.border,
+            self.bilinear as u32,
+            0,
+            0,
+            r.to_bits(),
+            g.to_bits(),
+            b.to_bits(),
+            a.to_bits(),
+        ];
+
+        Some(BufferInitContent::new(buffer, &data))
+    }
+
+    fn num_args(&self) -> u32 {
+        2
+    }
+}
@@ -0,0 +1,57 @@
+#version 450
+layout (location = 0) in vec2 uv;
+layout (location = 0) out vec4 f_color;
+
+layout (set = 1, binding = 0) uniform WhiteNoise {
+    // x: the distribution, see the constants below.
+    // y: the seed.
+    uvec4 config;
+    // x: the mean, for a normal distribution.
+    // y: the standard deviation, for a normal distribution.
+    vec4 gaussian;
+} u_whiteNoise;
+
+// Must be kept in sync with `shaders::white_noise::Distribution`.
+#define DISTRIBUTION_UNIFORM 0u
+#define DISTRIBUTION_GAUSSIAN 1u
+
+#define PI 3.1415926538
+
+/* A counter-based hash of four words at once, the result only depends on its input.
+ * Reference: Jarzynski, Olano, Hash Functions for GPU Rendering, JCGT 2020
+ */
+uvec4 pcg4d(uvec4 v) {
+    v = v * 1664525u + 1013904223u;
+    v.x += v.y * v.w;
+    v.y += v.z * v.x;
+    v.z += v.x * v.y;
+    v.w += v.y * v.z;
+    v ^= v >> 16u;
+    v.x += v.y * v.w;
+    v.y += v.z * v.x;
+    v.z += v.x * v.y;
+    v.w += v.y * v.z;
+    return v;
+}
+
+/* The upper 24 bits of each hash as a float in [0, 1), which is exact. */
+vec4 unit_float(uvec4 hash) {
+    return vec4(hash >> 8u) / 16777216.0;
+}
+
+void main() {
+    uvec4 counter = uvec4(uvec2(gl_FragCoord.xy), u_whiteNoise.config.y, 0u);
+    vec4 uniform_values = unit_float(pcg4d(counter));
+
+    if (u_whiteNoise.config.x == DISTRIBUTION_UNIFORM) {
+        f_color = vec4(uniform_values.rgb, 1.0);
+    } else {
+        // The Box-Muller transform, two pairs of independent normal values.
+        vec2 radius = sqrt(-2.0 * log(1.0 - uniform_values.xz));
+        vec2 angle = 2.0 * PI * uniform_values.yw;
+        vec4 normal = vec4(radius * cos(angle), radius * sin(angle));
+
+        vec3 value = u_whiteNoise.gaussian.x + u_whiteNoise.gaussian.y * normal.xyz;
+        f_color = vec4(value, 1.0);
+    }
+}
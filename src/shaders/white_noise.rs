@@ -0,0 +1,52 @@
+use std::borrow::Cow;
+
+use super::{BufferInitContent, FragmentShaderData, FragmentShaderKey};
+
+/// Independent random values for each pixel.
+pub const SHADER: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/spirv/white_noise.frag.v"));
+
+/// The distribution of the values.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Distribution {
+    Uniform = 0,
+    Gaussian = 1,
+}
+
+/// The white noise shader, hashing the seed and the pixel coordinate.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Shader {
+    pub(crate) distribution: Distribution,
+    pub(crate) seed: u32,
+    /// The mean and standard deviation, only used for `Distribution::Gaussian`.
+    pub(crate) gaussian: [f32; 2],
+}
+
+impl FragmentShaderData for Shader {
+    fn key(&self) -> Option<FragmentShaderKey> {
+        Some(FragmentShaderKey::WhiteNoise)
+    }
+
+    fn spirv_source(&self) -> Cow<'static, [u8]> {
+        Cow::Borrowed(SHADER)
+    }
+
+    fn binary_data(&self, buffer: &mut Vec<u8>) -> Option<BufferInitContent> {
+        let [mean, std_dev] = self.gaussian;
+        let data: [u32; 8] = [
+            self.distribution as u32,
+            self.seed,
+            0,
+            0,
+            mean.to_bits(),
+            std_dev.to_bits(),
+            0,
+            0,
+        ];
+
+        Some(BufferInitContent::new(buffer, &data))
+    }
+
+    fn num_args(&self) -> u32 {
+        0
+    }
+}
@@ -0,0 +1,23 @@
+/* Numbers that are too large for a single normalized channel, such as counts or
+ * the bits of a float, are stored as the four bytes of a word with one byte in
+ * each channel. With `swap` the most significant byte comes first, such that the
+ * bytes of an output on a big endian host are the word in host order.
+ *
+ * The quantization of the output truncates, each byte is biased by half a step
+ * to survive any rounding of intermediate float formats.
+ */
+vec4 encode_word(uint word, bool swap) {
+    uvec4 bytes = (uvec4(word) >> uvec4(0, 8, 16, 24)) & 0xffu;
+    if (swap) {
+        bytes = bytes.wzyx;
+    }
+    return min((vec4(bytes) + 0.5) / 255.0, 1.0);
+}
+
+uint decode_word(vec4 encoded, bool swap) {
+    uvec4 bytes = uvec4(encoded * 255.0);
+    if (swap) {
+        bytes = bytes.wzyx;
+    }
+    return bytes.x | (bytes.y << 8) | (bytes.z << 16) | (bytes.w << 24);
+}
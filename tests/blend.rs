@@ -10,7 +10,7 @@
 mod util;
 
 use stealth_paint::buffer::{self, Descriptor, Whitepoint};
-use stealth_paint::command::{self, CommandBuffer, Rectangle};
+use stealth_paint::command::{self, CommandBuffer, CommandError, Rectangle, Register};
 use stealth_paint::pool::{Pool, PoolKey};
 use stealth_paint::program::Program;
 
@@ -71,6 +71,58 @@ fn integration() {
     run_oklab(&mut pool);
 
     run_derivative(&mut pool, pool_background.clone());
+
+    run_blend_modes(&mut pool, pool_foreground.clone(), pool_background.clone());
+
+    run_blend_mode_reference(&mut pool);
+
+    run_solid(&mut pool);
+
+    run_decode_texel(&mut pool);
+
+    run_smooth(&mut pool, pool_background.clone());
+
+    run_median(&mut pool);
+
+    run_bilateral(&mut pool);
+
+    run_kuwahara(&mut pool);
+
+    run_convolve(&mut pool);
+
+    run_affine_bilinear(&mut pool);
+
+    run_resize(&mut pool);
+
+    run_resize_area(&mut pool);
+
+    run_perspective(&mut pool);
+
+    run_warp(&mut pool);
+
+    run_orientation(&mut pool);
+
+    run_pad(&mut pool);
+
+    run_gradient(&mut pool);
+
+    run_canny(&mut pool);
+
+    run_hough(&mut pool);
+
+    run_ssim(&mut pool, pool_background.clone());
+
+    run_perceptual_hash(&mut pool);
+
+    run_histogram(&mut pool, pool_background.clone(), &background);
+
+    run_equalize(&mut pool);
+
+    run_statistics(&mut pool, pool_background.clone(), &background);
+
+    run_noise(&mut pool);
+
+    run_white_and_blue_noise(&mut pool);
 }
 
 fn run_blending(
@@ -528,3 +580,2118 @@ fn run_derivative(pool: &mut Pool, (bg_key, background): (PoolKey, Descriptor))
         util::assert_reference(image_derived.into(), &reference);
     }
 }
+
+fn run_blend_modes(
+    pool: &mut Pool,
+    (fg_key, foreground): (PoolKey, Descriptor),
+    (bg_key, background): (PoolKey, Descriptor),
+) {
+    const MODES: &[command::Blend] = &[
+        command::Blend::Clear,
+        command::Blend::SourceOver,
+        command::Blend::DestinationOver,
+        command::Blend::In,
+        command::Blend::Out,
+        command::Blend::Atop,
+        command::Blend::Xor,
+        command::Blend::Plus,
+        command::Blend::Multiply,
+        command::Blend::Screen,
+        command::Blend::Overlay,
+        command::Blend::Darken,
+        command::Blend::Lighten,
+        command::Blend::ColorDodge,
+        command::Blend::ColorBurn,
+        command::Blend::HardLight,
+        command::Blend::SoftLight,
+        command::Blend::Difference,
+        command::Blend::Exclusion,
+        command::Blend::Hue,
+        command::Blend::Saturation,
+        command::Blend::Color,
+        command::Blend::Luminosity,
+    ];
+
+    // Somewhere in the middle, so that every operator also sees uncovered background.
+    let placement = Rectangle {
+        x: 64,
+        y: 32,
+        max_x: 64 + foreground.layout.width(),
+        max_y: 32 + foreground.layout.height(),
+    };
+
+    let width = background.layout.width();
+    let background_bytes = pool.entry(bg_key).unwrap().as_bytes().unwrap().to_vec();
+    let foreground_bytes = pool.entry(fg_key).unwrap().as_bytes().unwrap().to_vec();
+
+    // Both fixtures are opaque, so each Porter-Duff operator either selects one of the images or
+    // is transparent. Only `Plus` mixes colors, by adding them in linear light.
+    assert!(background_bytes.chunks_exact(4).all(|t| t[3] == 0xff));
+    assert!(foreground_bytes.chunks_exact(4).all(|t| t[3] == 0xff));
+
+    const TRANSPARENT: [u8; 4] = [0; 4];
+    let plus = |above: &[u8], below: &[u8]| -> [u8; 4] {
+        let decode = |c: u8| srgb_decode(f64::from(c) / 255.0);
+        let channel = |i: usize| (srgb_encode(decode(above[i]) + decode(below[i])) * 255.0).round();
+        [channel(0) as u8, channel(1) as u8, channel(2) as u8, 0xff]
+    };
+    let texel = |t: &[u8]| [t[0], t[1], t[2], t[3]];
+
+    for &mode in MODES {
+        let mut commands = CommandBuffer::default();
+
+        // Describe the pipeline:
+        // 0: in (background)
+        // 1: in (foreground)
+        // 2: blend(0, placement, 1, mode)
+        // 3: out(2)
+        let background = commands.input(background.clone()).unwrap();
+        let foreground = commands.input(foreground.clone()).unwrap();
+
+        let blended = commands
+            .blend(background, placement, foreground, mode)
+            .expect("Valid to blend");
+
+        let (output, outformat) = commands.output(blended).expect("Valid for output");
+
+        let result = run_once_with_output(
+            commands,
+            pool,
+            vec![(background, bg_key), (foreground, fg_key)],
+            retire_with_one_image(output),
+        );
+
+        let image_blended = pool.entry(result).unwrap();
+        assert_eq!(image_blended.descriptor(), outformat);
+
+        if let command::Blend::Clear = mode {
+            let bytes = image_blended.as_bytes().expect("Not a byte image");
+            assert!(
+                bytes.iter().all(|&b| b == 0),
+                "Clear must be fully transparent"
+            );
+        }
+
+        // Plus is computed in linear light, everything else is copied.
+        let tolerance = match mode {
+            command::Blend::Plus => 2,
+            _ => 1,
+        };
+
+        let bytes = image_blended.as_bytes().expect("Not a byte image");
+        let texels = bytes.chunks_exact(4).zip(background_bytes.chunks_exact(4));
+        for (idx, (output, below)) in texels.enumerate() {
+            let (x, y) = (idx as u32 % width, idx as u32 / width);
+            let covered = (placement.x..placement.max_x).contains(&x)
+                && (placement.y..placement.max_y).contains(&y);
+            let above = if covered {
+                let above_idx = (y - placement.y) * placement.width() + (x - placement.x);
+                Some(&foreground_bytes[4 * above_idx as usize..][..4])
+            } else {
+                None
+            };
+
+            let expected: [u8; 4] = match mode {
+                command::Blend::SourceOver | command::Blend::Atop => texel(above.unwrap_or(below)),
+                command::Blend::DestinationOver => texel(below),
+                command::Blend::In => above.map_or(TRANSPARENT, texel),
+                command::Blend::Out => TRANSPARENT,
+                command::Blend::Xor => match above {
+                    Some(_) => TRANSPARENT,
+                    None => texel(below),
+                },
+                command::Blend::Plus => above.map_or(texel(below), |a| plus(a, below)),
+                _ => break,
+            };
+
+            let close = output
+                .iter()
+                .zip(&expected)
+                .all(|(&c, &e)| (i16::from(c) - i16::from(e)).abs() <= tolerance);
+            assert!(
+                close,
+                "{:?} at ({}, {}): {:?} instead of {:?}",
+                mode, x, y, output, expected
+            );
+        }
+    }
+}
+
+fn run_blend_mode_reference(pool: &mut Pool) {
+    // Each mode, and whether it mixes sRGB encoded values instead of linear ones.
+    const MODES: &[(command::Blend, bool)] = &[
+        (command::Blend::Multiply, false),
+        (command::Blend::Screen, false),
+        (command::Blend::Overlay, true),
+        (command::Blend::Darken, false),
+        (command::Blend::Lighten, false),
+        (command::Blend::ColorDodge, false),
+        (command::Blend::ColorBurn, false),
+        (command::Blend::HardLight, true),
+        (command::Blend::SoftLight, true),
+        (command::Blend::Difference, false),
+        (command::Blend::Exclusion, false),
+        (command::Blend::Hue, true),
+        (command::Blend::Saturation, true),
+        (command::Blend::Color, true),
+        (command::Blend::Luminosity, true),
+    ];
+
+    // Backdrop and source, such that each mode takes both of its branches in some component.
+    const PAIRS: &[([u8; 3], [u8; 3])] = &[
+        ([0x20, 0x80, 0xe0], [0xc0, 0x40, 0x90]),
+        ([0xf0, 0x18, 0x60], [0x30, 0xd0, 0x70]),
+        ([0x80, 0x90, 0x38], [0x48, 0xa8, 0xe8]),
+    ];
+
+    let layout = image::DynamicImage::new_rgba8(8, 8);
+    let descriptor = Descriptor::with_srgb_image(&layout);
+    let placement = Rectangle::with_width_height(8, 8);
+
+    for &(mode, encoded) in MODES {
+        for &(backdrop, source) in PAIRS {
+            let mut commands = CommandBuffer::default();
+            let opaque = |c: [u8; 3]| [c[0], c[1], c[2], 0xff];
+            let below = commands
+                .solid(descriptor.clone(), &opaque(backdrop))
+                .unwrap();
+            let above = commands.solid(descriptor.clone(), &opaque(source)).unwrap();
+            let blended = commands.blend(below, placement, above, mode).unwrap();
+            let (output, _outformat) = commands.output(blended).expect("Valid for output");
+
+            let result =
+                run_once_with_output(commands, pool, vec![], retire_with_one_image(output));
+
+            let image_blended = pool.entry(result).unwrap();
+            let texel = &image_blended.as_bytes().expect("Not a byte image")[..4];
+
+            // Both are opaque, so the result is the blend function itself.
+            let to_space = |c: [u8; 3]| {
+                let mut values = [0.0; 3];
+                for (value, &c) in values.iter_mut().zip(&c) {
+                    let c = f64::from(c) / 255.0;
+                    *value = if encoded { c } else { srgb_decode(c) };
+                }
+                values
+            };
+
+            let mixed = w3c_blend(mode, to_space(backdrop), to_space(source));
+            let expected: Vec<u8> = mixed
+                .iter()
+                .map(|&c| {
+                    if encoded {
+                        c.max(0.0).min(1.0)
+                    } else {
+                        srgb_encode(c)
+                    }
+                })
+                .map(|c| (c * 255.0).round() as u8)
+                .chain(core::iter::once(0xff))
+                .collect();
+
+            let close = texel
+                .iter()
+                .zip(&expected)
+                .all(|(&c, &e)| (i16::from(c) - i16::from(e)).abs() <= 2);
+            assert!(
+                close,
+                "{:?} of {:?} and {:?}: {:?} instead of {:?}",
+                mode, backdrop, source, texel, expected
+            );
+        }
+    }
+}
+
+/// The blend functions of the W3C Compositing and Blending Level 1 specification.
+///
+/// Reference: <https://www.w3.org/TR/compositing-1/#blending>
+fn w3c_blend(mode: command::Blend, cb: [f64; 3], cs: [f64; 3]) -> [f64; 3] {
+    fn separable(cb: [f64; 3], cs: [f64; 3], f: impl Fn(f64, f64) -> f64) -> [f64; 3] {
+        [f(cb[0], cs[0]), f(cb[1], cs[1]), f(cb[2], cs[2])]
+    }
+
+    fn multiply(cb: f64, cs: f64) -> f64 {
+        cb * cs
+    }
+
+    fn screen(cb: f64, cs: f64) -> f64 {
+        cb + cs - cb * cs
+    }
+
+    fn hard_light(cb: f64, cs: f64) -> f64 {
+        if cs <= 0.5 {
+            multiply(cb, 2.0 * cs)
+        } else {
+            screen(cb, 2.0 * cs - 1.0)
+        }
+    }
+
+    fn lum(c: [f64; 3]) -> f64 {
+        0.3 * c[0] + 0.59 * c[1] + 0.11 * c[2]
+    }
+
+    fn clip_color(c: [f64; 3]) -> [f64; 3] {
+        let l = lum(c);
+        let n = c[0].min(c[1]).min(c[2]);
+        let x = c[0].max(c[1]).max(c[2]);
+        let mut c = c;
+
+        if n < 0.0 {
+            for v in c.iter_mut() {
+                *v = l + (*v - l) * l / (l - n);
+            }
+        }
+
+        if x > 1.0 {
+            for v in c.iter_mut() {
+                *v = l + (*v - l) * (1.0 - l) / (x - l);
+            }
+        }
+
+        c
+    }
+
+    fn set_lum(c: [f64; 3], l: f64) -> [f64; 3] {
+        let d = l - lum(c);
+        clip_color([c[0] + d, c[1] + d, c[2] + d])
+    }
+
+    fn sat(c: [f64; 3]) -> f64 {
+        c[0].max(c[1]).max(c[2]) - c[0].min(c[1]).min(c[2])
+    }
+
+    fn set_sat(c: [f64; 3], s: f64) -> [f64; 3] {
+        let mut order = [0, 1, 2];
+        order.sort_by(|&a, &b| c[a].partial_cmp(&c[b]).unwrap());
+        let [min, mid, max] = order;
+
+        let mut result = [0.0; 3];
+        if c[max] > c[min] {
+            result[mid] = (c[mid] - c[min]) * s / (c[max] - c[min]);
+            result[max] = s;
+        }
+
+        result
+    }
+
+    match mode {
+        command::Blend::Multiply => separable(cb, cs, multiply),
+        command::Blend::Screen => separable(cb, cs, screen),
+        command::Blend::Overlay => separable(cb, cs, |cb, cs| hard_light(cs, cb)),
+        command::Blend::Darken => separable(cb, cs, f64::min),
+        command::Blend::Lighten => separable(cb, cs, f64::max),
+        command::Blend::ColorDodge => separable(cb, cs, |cb, cs| {
+            if cb <= 0.0 {
+                0.0
+            } else if cs >= 1.0 {
+                1.0
+            } else {
+                (cb / (1.0 - cs)).min(1.0)
+            }
+        }),
+        command::Blend::ColorBurn => separable(cb, cs, |cb, cs| {
+            if cb >= 1.0 {
+                1.0
+            } else if cs <= 0.0 {
+                0.0
+            } else {
+                1.0 - ((1.0 - cb) / cs).min(1.0)
+            }
+        }),
+        command::Blend::HardLight => separable(cb, cs, hard_light),
+        command::Blend::SoftLight => separable(cb, cs, |cb, cs| {
+            if cs <= 0.5 {
+                cb - (1.0 - 2.0 * cs) * cb * (1.0 - cb)
+            } else {
+                let d = if cb <= 0.25 {
+                    ((16.0 * cb - 12.0) * cb + 4.0) * cb
+                } else {
+                    cb.sqrt()
+                };
+                cb + (2.0 * cs - 1.0) * (d - cb)
+            }
+        }),
+        command::Blend::Difference => separable(cb, cs, |cb, cs| (cb - cs).abs()),
+        command::Blend::Exclusion => separable(cb, cs, |cb, cs| cb + cs - 2.0 * cb * cs),
+        command::Blend::Hue => set_lum(set_sat(cs, sat(cb)), lum(cb)),
+        command::Blend::Saturation => set_lum(set_sat(cb, sat(cs)), lum(cb)),
+        command::Blend::Color => set_lum(cs, lum(cb)),
+        command::Blend::Luminosity => set_lum(cb, lum(cs)),
+        _ => unreachable!("Not a blend mode: {:?}", mode),
+    }
+}
+
+fn run_solid(pool: &mut Pool) {
+    const MAGENTA: [u8; 4] = [0xff, 0x00, 0xff, 0xff];
+
+    let layout = image::DynamicImage::new_rgba8(128, 64);
+    let descriptor = Descriptor::with_srgb_image(&layout);
+
+    let mut commands = CommandBuffer::default();
+    let solid = commands
+        .solid(descriptor, &MAGENTA)
+        .expect("Valid solid texel");
+
+    let (output, _outformat) = commands.output(solid).expect("Valid for output");
+
+    let result = run_once_with_output(commands, pool, vec![], retire_with_one_image(output));
+
+    let image_solid = pool.entry(result).unwrap();
+    let bytes = image_solid.as_bytes().expect("Not a byte image");
+    assert!(bytes.chunks_exact(4).all(|texel| texel == MAGENTA));
+}
+
+fn run_decode_texel(pool: &mut Pool) {
+    struct Case {
+        bits: buffer::SampleBits,
+        parts: buffer::SampleParts,
+        color: buffer::Color,
+        texel: &'static [u8],
+        /// The normalized red, green, blue and alpha of the texel, encoded for RGB colors.
+        components: [f64; 4],
+    }
+
+    let linear = buffer::Color::Scalars {
+        transfer: buffer::Transfer::Linear,
+    };
+
+    // All channels differ, such that any swap or misplaced field changes the texel.
+    let cases = [
+        Case {
+            bits: buffer::SampleBits::Int8x4,
+            parts: buffer::SampleParts::Bgra,
+            color: linear.clone(),
+            texel: &[0x20, 0x60, 0xa0, 0xe0],
+            components: [160.0 / 255.0, 96.0 / 255.0, 32.0 / 255.0, 224.0 / 255.0],
+        },
+        Case {
+            bits: buffer::SampleBits::Int444_,
+            parts: buffer::SampleParts::Rgb,
+            color: linear,
+            texel: &[0x50, 0xc3],
+            components: [5.0 / 15.0, 3.0 / 15.0, 12.0 / 15.0, 1.0],
+        },
+        // Staging alone round-trips even with the directions of the transfer swapped, the host
+        // decoding for the solid color does not.
+        Case {
+            bits: buffer::SampleBits::Int8x4,
+            parts: buffer::SampleParts::Rgba,
+            color: buffer::Color::Rgb {
+                luminance: buffer::Luminance::Sdr,
+                primary: buffer::Primaries::Bt709,
+                transfer: buffer::Transfer::Bt601,
+                whitepoint: buffer::Whitepoint::D65,
+            },
+            texel: &[0x20, 0x60, 0xa0, 0xe0],
+            components: [32.0 / 255.0, 96.0 / 255.0, 160.0 / 255.0, 224.0 / 255.0],
+        },
+    ];
+
+    for case in cases.iter() {
+        let Case {
+            bits,
+            parts,
+            ref color,
+            texel,
+            components,
+        } = *case;
+
+        let texel_desc = buffer::Texel {
+            block: buffer::Block::Pixel,
+            samples: buffer::Samples { bits, parts },
+            color: color.clone(),
+        };
+
+        let layout = buffer::BufferLayout::with_texel(&texel_desc, 8, 8).unwrap();
+        let descriptor = Descriptor {
+            layout: layout.clone(),
+            texel: texel_desc.clone(),
+        };
+
+        // Decoded by the staging shader, then encoded again.
+        let mut image = buffer::ImageBuffer::with_layout(&layout);
+        for chunk in image.as_bytes_mut().chunks_exact_mut(texel.len()) {
+            chunk.copy_from_slice(texel);
+        }
+
+        let key = pool.insert(image, texel_desc).key();
+
+        let mut commands = CommandBuffer::default();
+        let input = commands.input(descriptor.clone()).unwrap();
+        let (output, _outformat) = commands.output(input).expect("Valid for output");
+
+        let result = run_once_with_output(
+            commands,
+            pool,
+            vec![(input, key)],
+            retire_with_one_image(output),
+        );
+
+        let image_staged = pool.entry(result).unwrap();
+        let bytes = image_staged.as_bytes().expect("Not a byte image");
+        assert!(
+            bytes.chunks_exact(texel.len()).all(|t| t == texel),
+            "Staging changed {:?} {:?}",
+            bits,
+            parts
+        );
+
+        // The decoded input on its own, every channel is constant.
+        let mut commands = CommandBuffer::default();
+        let input = commands.input(descriptor.clone()).unwrap();
+        let statistics = commands.statistics(input, None).unwrap();
+        let (output, _outformat) = commands.output(statistics).expect("Valid for output");
+
+        let statistics = run_once_with_output(commands, pool, vec![(input, key)], |retire| {
+            retire.statistics(output).expect("Valid for statistics")
+        });
+
+        for (channel, &expected) in components.iter().enumerate() {
+            let mean = f64::from(statistics.mean[channel]);
+            assert!(
+                (mean - expected).abs() < 1e-3,
+                "Channel {} of {:?} {:?} decoded to {} instead of {}",
+                channel,
+                bits,
+                parts,
+                mean,
+                expected
+            );
+        }
+
+        // Decoded on the host, then encoded by the same staging shader.
+        let mut commands = CommandBuffer::default();
+        let solid = commands
+            .solid(descriptor, texel)
+            .expect("Valid solid texel");
+        let (output, _outformat) = commands.output(solid).expect("Valid for output");
+
+        let result = run_once_with_output(commands, pool, vec![], retire_with_one_image(output));
+
+        let image_solid = pool.entry(result).unwrap();
+        let bytes = image_solid.as_bytes().expect("Not a byte image");
+        assert!(
+            bytes.chunks_exact(texel.len()).all(|t| t == texel),
+            "Host decoding differs from staging for {:?} {:?}",
+            bits,
+            parts
+        );
+    }
+}
+
+fn run_smooth(pool: &mut Pool, (bg_key, background): (PoolKey, Descriptor)) {
+    const MAGENTA: [u8; 4] = [0xff, 0x00, 0xff, 0xff];
+
+    let smoothings = [
+        command::Smoothing::gaussian(2.0),
+        command::Smoothing::laplace(3),
+        command::Smoothing::median(1),
+        command::Smoothing::median(4),
+        command::Smoothing::bilateral(2.0, 0.1),
+        command::Smoothing::bilateral(2.0, 0.1).with_range_distance(command::RangeDistance::Oklab),
+        command::Smoothing::kuwahara(3),
+    ];
+
+    for smoothing in smoothings.iter() {
+        let mut commands = CommandBuffer::default();
+
+        // Describe the pipeline:
+        // 0: in (background)
+        // 1: smooth(0, smoothing)
+        // 2: out(1)
+        let input = commands.input(background.clone()).unwrap();
+        let smooth = commands.smooth(input, smoothing.clone()).unwrap();
+        let (output, _outformat) = commands.output(smooth).expect("Valid for output");
+
+        let result = run_once_with_output(
+            commands,
+            pool,
+            vec![(input, bg_key)],
+            retire_with_one_image(output),
+        );
+
+        let image_smooth = pool.entry(result).unwrap();
+        assert_eq!(image_smooth.descriptor(), background);
+
+        // A constant image stays constant, in particular at the edges.
+        let layout = image::DynamicImage::new_rgba8(128, 64);
+        let descriptor = Descriptor::with_srgb_image(&layout);
+
+        let mut commands = CommandBuffer::default();
+        let solid = commands.solid(descriptor, &MAGENTA).unwrap();
+        let smooth = commands.smooth(solid, smoothing.clone()).unwrap();
+        let (output, _outformat) = commands.output(smooth).expect("Valid for output");
+
+        let result = run_once_with_output(commands, pool, vec![], retire_with_one_image(output));
+
+        let image_smooth = pool.entry(result).unwrap();
+        let bytes = image_smooth.as_bytes().expect("Not a byte image");
+        assert!(bytes.chunks_exact(4).all(|texel| texel == MAGENTA));
+    }
+}
+
+fn run_median(pool: &mut Pool) {
+    const BLACK: [u8; 4] = [0x00, 0x00, 0x00, 0xff];
+    const WHITE: [u8; 4] = [0xff, 0xff, 0xff, 0xff];
+
+    // Salt and pepper: a single white pixel is noise, a 3×3 white block is not.
+    let mut noisy = image::RgbaImage::from_pixel(32, 32, image::Rgba(BLACK));
+    noisy.put_pixel(8, 8, image::Rgba(WHITE));
+    for (x, y) in (19..22).flat_map(|x| (19..22).map(move |y| (x, y))) {
+        noisy.put_pixel(x, y, image::Rgba(WHITE));
+    }
+
+    let noisy = image::DynamicImage::ImageRgba8(noisy);
+    let (key, descriptor) = {
+        let entry = pool.insert_srgb(&noisy);
+        (entry.key(), entry.descriptor())
+    };
+
+    let mut commands = CommandBuffer::default();
+    let input = commands.input(descriptor).unwrap();
+    let median = commands
+        .smooth(input, command::Smoothing::median(1))
+        .unwrap();
+    let (output, _outformat) = commands.output(median).expect("Valid for output");
+
+    let result = run_once_with_output(
+        commands,
+        pool,
+        vec![(input, key)],
+        retire_with_one_image(output),
+    );
+
+    let image_median = pool.entry(result).unwrap();
+    let bytes = image_median.as_bytes().expect("Not a byte image");
+    let texel = |x: usize, y: usize| &bytes[4 * (32 * y + x)..][..4];
+
+    assert_eq!(texel(8, 8), BLACK);
+    assert_eq!(texel(20, 20), WHITE);
+    // Every result is one of the input pixels, never a mix of them.
+    assert!(bytes
+        .chunks_exact(4)
+        .all(|texel| texel == BLACK || texel == WHITE));
+}
+
+fn run_bilateral(pool: &mut Pool) {
+    const BLACK: [u8; 4] = [0x00, 0x00, 0x00, 0xff];
+    const WHITE: [u8; 4] = [0xff, 0xff, 0xff, 0xff];
+
+    // A hard edge down the middle which a gaussian blur would smear.
+    let edge = image::RgbaImage::from_fn(32, 32, |x, _| {
+        image::Rgba(if x < 16 { BLACK } else { WHITE })
+    });
+
+    let edge = image::DynamicImage::ImageRgba8(edge);
+    let (key, descriptor) = {
+        let entry = pool.insert_srgb(&edge);
+        (entry.key(), entry.descriptor())
+    };
+
+    let distances = [
+        command::RangeDistance::Luma,
+        command::RangeDistance::Linear,
+        command::RangeDistance::Oklab,
+    ];
+
+    for &distance in distances.iter() {
+        let mut commands = CommandBuffer::default();
+        let input = commands.input(descriptor.clone()).unwrap();
+        let smoothing = command::Smoothing::bilateral(3.0, 0.05).with_range_distance(distance);
+        let smooth = commands.smooth(input, smoothing).unwrap();
+        let (output, _outformat) = commands.output(smooth).expect("Valid for output");
+
+        let result = run_once_with_output(
+            commands,
+            pool,
+            vec![(input, key)],
+            retire_with_one_image(output),
+        );
+
+        let image_smooth = pool.entry(result).unwrap();
+        let bytes = image_smooth.as_bytes().expect("Not a byte image");
+        assert_eq!(
+            bytes,
+            edge.as_bytes(),
+            "Edge not preserved with {:?}",
+            distance
+        );
+    }
+}
+
+fn run_kuwahara(pool: &mut Pool) {
+    const BLACK: [u8; 4] = [0x00, 0x00, 0x00, 0xff];
+    const WHITE: [u8; 4] = [0xff, 0xff, 0xff, 0xff];
+
+    // A hard edge down the middle. Every pixel has a region entirely on its side of the edge.
+    let edge = image::RgbaImage::from_fn(32, 32, |x, _| {
+        image::Rgba(if x < 16 { BLACK } else { WHITE })
+    });
+
+    let edge = image::DynamicImage::ImageRgba8(edge);
+    let (key, descriptor) = {
+        let entry = pool.insert_srgb(&edge);
+        (entry.key(), entry.descriptor())
+    };
+
+    let mut commands = CommandBuffer::default();
+    let input = commands.input(descriptor).unwrap();
+    let smooth = commands
+        .smooth(input, command::Smoothing::kuwahara(4))
+        .unwrap();
+    let (output, _outformat) = commands.output(smooth).expect("Valid for output");
+
+    let result = run_once_with_output(
+        commands,
+        pool,
+        vec![(input, key)],
+        retire_with_one_image(output),
+    );
+
+    let image_smooth = pool.entry(result).unwrap();
+    let bytes = image_smooth.as_bytes().expect("Not a byte image");
+    assert_eq!(bytes, edge.as_bytes());
+}
+
+fn run_convolve(pool: &mut Pool) {
+    const BLACK: [u8; 4] = [0x00, 0x00, 0x00, 0xff];
+
+    // Each column has its own color, such that we can identify where a pixel comes from.
+    let columns = image::RgbaImage::from_fn(8, 8, |x, _| image::Rgba([32 * x as u8, 0, 0, 0xff]));
+    let columns = image::DynamicImage::ImageRgba8(columns);
+    let (key, descriptor) = {
+        let entry = pool.insert_srgb(&columns);
+        (entry.key(), entry.descriptor())
+    };
+
+    // Which input column ends up in the last column, after shifting everything left by one.
+    let borders = [
+        (command::BorderMode::Clamp, [32 * 7, 0, 0, 0xff]),
+        (command::BorderMode::Mirror, [32 * 7, 0, 0, 0xff]),
+        (command::BorderMode::Wrap, [0, 0, 0, 0xff]),
+        (command::BorderMode::Constant([0.0, 0.0, 0.0, 1.0]), BLACK),
+    ];
+
+    for &(border, last) in borders.iter() {
+        let shift = command::Kernel::separable(&[0.0, 0.0, 1.0], &[1.0]).with_border(border);
+
+        let mut commands = CommandBuffer::default();
+        let input = commands.input(descriptor.clone()).unwrap();
+        let shifted = commands.convolve(input, shift).unwrap();
+        let (output, _outformat) = commands.output(shifted).expect("Valid for output");
+
+        let result = run_once_with_output(
+            commands,
+            pool,
+            vec![(input, key)],
+            retire_with_one_image(output),
+        );
+
+        let image_shifted = pool.entry(result).unwrap();
+        assert_eq!(image_shifted.descriptor(), descriptor);
+
+        let bytes = image_shifted.as_bytes().expect("Not a byte image");
+        for (y, row) in bytes.chunks_exact(4 * 8).enumerate() {
+            for (x, texel) in row.chunks_exact(4).enumerate() {
+                let expected = match x {
+                    7 => last,
+                    x => {
+                        columns
+                            .as_rgba8()
+                            .unwrap()
+                            .get_pixel(x as u32 + 1, y as u32)
+                            .0
+                    }
+                };
+
+                assert_eq!(texel, expected, "At {}, {} with {:?}", x, y, border);
+            }
+        }
+    }
+}
+
+fn run_affine_bilinear(pool: &mut Pool) {
+    const BLACK: [u8; 4] = [0x00, 0x00, 0x00, 0xff];
+    const WHITE: [u8; 4] = [0xff, 0xff, 0xff, 0xff];
+
+    let edge =
+        image::RgbaImage::from_fn(8, 8, |x, _| image::Rgba(if x < 4 { BLACK } else { WHITE }));
+
+    let edge = image::DynamicImage::ImageRgba8(edge);
+    let (key, descriptor) = {
+        let entry = pool.insert_srgb(&edge);
+        (entry.key(), entry.descriptor())
+    };
+
+    // Sampling at the pixel centers reproduces the image, while a shift by half a pixel must
+    // mix the two pixels at the edge.
+    for &shift in [0.0, 0.5].iter() {
+        let affine = command::Affine::new(command::AffineSample::BiLinear).shift(shift, 0.0);
+
+        let mut commands = CommandBuffer::default();
+        let below = commands.solid(descriptor.clone(), &BLACK).unwrap();
+        let above = commands.input(descriptor.clone()).unwrap();
+        let result = commands
+            .affine(below, affine, above)
+            .expect("Valid to sample bi-linearly");
+        let (output, _outformat) = commands.output(result).expect("Valid for output");
+
+        let result = run_once_with_output(
+            commands,
+            pool,
+            vec![(above, key)],
+            retire_with_one_image(output),
+        );
+
+        let image_affine = pool.entry(result).unwrap();
+        let bytes = image_affine.as_bytes().expect("Not a byte image");
+
+        if shift == 0.0 {
+            assert_eq!(bytes, edge.as_bytes());
+        } else {
+            assert!(bytes
+                .chunks_exact(4)
+                .any(|texel| texel[0] > 0x00 && texel[0] < 0xff));
+        }
+    }
+}
+
+fn run_resize(pool: &mut Pool) {
+    // Smooth gradients away from the extremes, such that overshoot is not clamped.
+    let pattern = image::RgbaImage::from_fn(37, 29, |x, y| {
+        let r = (16 + x * 224 / 36) as u8;
+        let g = (16 + y * 224 / 28) as u8;
+        let b = (16 + x * y * 224 / (36 * 28)) as u8;
+        image::Rgba([r, g, b, 0xff])
+    });
+
+    let pattern = image::DynamicImage::ImageRgba8(pattern);
+    let (key, descriptor) = {
+        let entry = pool.insert_srgb(&pattern);
+        (entry.key(), entry.descriptor())
+    };
+
+    // The `image` crate filters the samples as they are, which is only comparable for a linear
+    // transfer function.
+    let linear = buffer::Texel {
+        color: buffer::Color::Rgb {
+            luminance: buffer::Luminance::Sdr,
+            primary: buffer::Primaries::Bt709,
+            transfer: buffer::Transfer::Linear,
+            whitepoint: Whitepoint::D65,
+        },
+        ..descriptor.texel.clone()
+    };
+
+    let filters = [
+        (
+            command::ResizeFilter::Triangle,
+            image::imageops::FilterType::Triangle,
+        ),
+        (
+            command::ResizeFilter::CatmullRom,
+            image::imageops::FilterType::CatmullRom,
+        ),
+        (
+            command::ResizeFilter::Lanczos3,
+            image::imageops::FilterType::Lanczos3,
+        ),
+    ];
+
+    // Upscale and downscale, as well as both at once along different axes.
+    let sizes = [(64, 48), (13, 11), (80, 9)];
+
+    for &(filter, reference) in filters.iter() {
+        for &(width, height) in sizes.iter() {
+            let mut commands = CommandBuffer::default();
+            let input = commands.input(descriptor.clone()).unwrap();
+            let transmuted = commands.transmute(input, linear.clone()).unwrap();
+            let resized = commands.resize(transmuted, width, height, filter).unwrap();
+            let (output, _outformat) = commands.output(resized).expect("Valid for output");
+
+            let result = run_once_with_output(
+                commands,
+                pool,
+                vec![(input, key)],
+                retire_with_one_image(output),
+            );
+
+            let image_resized = pool.entry(result).unwrap();
+            assert_eq!(image_resized.descriptor().size(), (width, height));
+
+            let expected = image::imageops::resize(&pattern, width, height, reference);
+            let bytes = image_resized.as_bytes().expect("Not a byte image");
+
+            for (texel, expected) in bytes.chunks_exact(4).zip(expected.pixels()) {
+                for (&actual, &expected) in texel.iter().zip(expected.0.iter()) {
+                    let difference = (i16::from(actual) - i16::from(expected)).abs();
+                    assert!(
+                        difference <= 3,
+                        "{:?} to {}x{}: {:?} instead of {:?}",
+                        filter,
+                        width,
+                        height,
+                        texel,
+                        expected,
+                    );
+                }
+            }
+        }
+    }
+}
+
+fn run_resize_area(pool: &mut Pool) {
+    const BLACK: [u8; 4] = [0x00, 0x00, 0x00, 0xff];
+    const WHITE: [u8; 4] = [0xff, 0xff, 0xff, 0xff];
+
+    // An attack on sparse sampling: only every fourth pixel along each axis is white.
+    let hidden = image::RgbaImage::from_fn(16, 16, |x, y| {
+        image::Rgba(if x % 4 == 2 && y % 4 == 2 {
+            WHITE
+        } else {
+            BLACK
+        })
+    });
+
+    let hidden = image::DynamicImage::ImageRgba8(hidden);
+    let (key, descriptor) = {
+        let entry = pool.insert_srgb(&hidden);
+        (entry.key(), entry.descriptor())
+    };
+
+    let linear = buffer::Texel {
+        color: buffer::Color::Rgb {
+            luminance: buffer::Luminance::Sdr,
+            primary: buffer::Primaries::Bt709,
+            transfer: buffer::Transfer::Linear,
+            whitepoint: Whitepoint::D65,
+        },
+        ..descriptor.texel.clone()
+    };
+
+    let mut commands = CommandBuffer::default();
+    commands.refuse_sparse_sampling(1.0);
+
+    let input = commands.input(descriptor.clone()).unwrap();
+    let transmuted = commands.transmute(input, linear).unwrap();
+
+    let shrink = command::Affine::new(command::AffineSample::Nearest).scale(0.25, 0.25);
+    assert!(commands.affine(transmuted, shrink, transmuted).is_err());
+
+    let resized = commands
+        .resize(transmuted, 4, 4, command::ResizeFilter::Area)
+        .unwrap();
+    let (output, _outformat) = commands.output(resized).expect("Valid for output");
+
+    let result = run_once_with_output(
+        commands,
+        pool,
+        vec![(input, key)],
+        retire_with_one_image(output),
+    );
+
+    let image_resized = pool.entry(result).unwrap();
+    let bytes = image_resized.as_bytes().expect("Not a byte image");
+
+    // Every pixel is the true average, one sixteenth of white.
+    assert!(bytes
+        .chunks_exact(4)
+        .all(|texel| texel == [0x10, 0x10, 0x10, 0xff]));
+}
+
+fn run_perspective(pool: &mut Pool) {
+    const BLACK: [u8; 4] = [0x00, 0x00, 0x00, 0xff];
+
+    // Each column has its own color, such that we can identify where a pixel comes from.
+    let columns = image::RgbaImage::from_fn(8, 8, |x, _| image::Rgba([32 * x as u8, 0, 0, 0xff]));
+    let columns = image::DynamicImage::ImageRgba8(columns);
+    let (key, descriptor) = {
+        let entry = pool.insert_srgb(&columns);
+        (entry.key(), entry.descriptor())
+    };
+
+    let background = image::DynamicImage::new_rgba8(64, 64);
+    let background = Descriptor::with_srgb_image(&background);
+
+    let source = [[0.0, 0.0], [8.0, 0.0], [8.0, 8.0], [0.0, 8.0]];
+    let target = [[8.0, 4.0], [56.0, 12.0], [60.0, 60.0], [4.0, 52.0]];
+    let perspective =
+        command::Perspective::with_correspondences(command::AffineSample::Nearest, source, target)
+            .expect("A unique homography");
+    let inverse =
+        command::Perspective::with_correspondences(command::AffineSample::Nearest, target, source)
+            .expect("A unique homography");
+
+    let mut commands = CommandBuffer::default();
+    let below = commands.solid(background, &BLACK).unwrap();
+    let above = commands.input(descriptor).unwrap();
+    let result = commands
+        .perspective(below, perspective, above)
+        .expect("Valid to transform");
+    let (output, _outformat) = commands.output(result).expect("Valid for output");
+
+    let result = run_once_with_output(
+        commands,
+        pool,
+        vec![(above, key)],
+        retire_with_one_image(output),
+    );
+
+    let image_perspective = pool.entry(result).unwrap();
+    let bytes = image_perspective.as_bytes().expect("Not a byte image");
+
+    let [h0, h1, h2, h3, h4, h5, h6, h7, h8] = inverse.transformation;
+    for (idx, texel) in bytes.chunks_exact(4).enumerate() {
+        let x = (idx % 64) as f32 + 0.5;
+        let y = (idx / 64) as f32 + 0.5;
+
+        let w = h6 * x + h7 * y + h8;
+        let u = (h0 * x + h1 * y + h2) / w;
+        let v = (h3 * x + h4 * y + h5) / w;
+
+        // Avoid the edges, where rounding may choose either pixel.
+        let near_edge = |c: f32| (c - c.round()).abs() < 0.05;
+        if near_edge(u) || near_edge(v) {
+            continue;
+        }
+
+        let expected = if u > 0.0 && u < 8.0 && v > 0.0 && v < 8.0 {
+            [32 * u.floor() as u8, 0, 0, 0xff]
+        } else {
+            BLACK
+        };
+
+        assert_eq!(texel, expected, "At {}, {}", x, y);
+    }
+}
+
+fn run_warp(pool: &mut Pool) {
+    const BLACK: [u8; 4] = [0x00, 0x00, 0x00, 0xff];
+
+    // Each column has its own color, such that we can identify where a pixel comes from.
+    let columns = image::RgbaImage::from_fn(8, 8, |x, _| image::Rgba([32 * x as u8, 0, 0, 0xff]));
+    let columns = image::DynamicImage::ImageRgba8(columns);
+    let (key, descriptor) = {
+        let entry = pool.insert_srgb(&columns);
+        (entry.key(), entry.descriptor())
+    };
+
+    // A displacement of one pixel along the width.
+    let displacement = buffer::Descriptor {
+        texel: buffer::Texel {
+            color: buffer::Color::Scalars {
+                transfer: buffer::Transfer::Linear,
+            },
+            ..descriptor.texel.clone()
+        },
+        ..descriptor.clone()
+    };
+
+    // Which input column ends up in the last column, after shifting everything left by one.
+    let borders = [
+        (command::BorderMode::Clamp, [32 * 7, 0, 0, 0xff]),
+        (command::BorderMode::Mirror, [32 * 7, 0, 0, 0xff]),
+        (command::BorderMode::Wrap, [0, 0, 0, 0xff]),
+        (command::BorderMode::Constant([0.0, 0.0, 0.0, 1.0]), BLACK),
+    ];
+
+    let samplings = [
+        command::AffineSample::Nearest,
+        command::AffineSample::BiLinear,
+    ];
+
+    for &(border, last) in borders.iter() {
+        for &sampling in samplings.iter() {
+            let warp = command::Warp::new(1.0)
+                .with_sampling(sampling)
+                .with_border(border);
+
+            let mut commands = CommandBuffer::default();
+            let input = commands.input(descriptor.clone()).unwrap();
+            let offsets = commands
+                .solid(displacement.clone(), &[0xff, 0x00, 0x00, 0xff])
+                .unwrap();
+            let warped = commands.warp(input, offsets, warp).unwrap();
+            let (output, _outformat) = commands.output(warped).expect("Valid for output");
+
+            let result = run_once_with_output(
+                commands,
+                pool,
+                vec![(input, key)],
+                retire_with_one_image(output),
+            );
+
+            let image_warped = pool.entry(result).unwrap();
+            assert_eq!(image_warped.descriptor(), descriptor);
+
+            let bytes = image_warped.as_bytes().expect("Not a byte image");
+            for (y, row) in bytes.chunks_exact(4 * 8).enumerate() {
+                for (x, texel) in row.chunks_exact(4).enumerate() {
+                    let expected = match x {
+                        7 => last,
+                        x => {
+                            columns
+                                .as_rgba8()
+                                .unwrap()
+                                .get_pixel(x as u32 + 1, y as u32)
+                                .0
+                        }
+                    };
+
+                    assert_eq!(texel, expected, "At {}, {} with {:?}", x, y, border);
+                }
+            }
+        }
+    }
+}
+
+fn run_orientation(pool: &mut Pool) {
+    // Every pixel is unique and the image is not square.
+    let pattern = image::RgbaImage::from_fn(5, 3, |x, y| {
+        image::Rgba([40 * x as u8, 80 * y as u8, (x * y) as u8, 0xff - x as u8])
+    });
+
+    let transposed = image::RgbaImage::from_fn(3, 5, |x, y| *pattern.get_pixel(y, x));
+
+    let pattern = image::DynamicImage::ImageRgba8(pattern);
+    let (key, descriptor) = {
+        let entry = pool.insert_srgb(&pattern);
+        (entry.key(), entry.descriptor())
+    };
+
+    let orientations: [(
+        fn(&mut CommandBuffer, Register) -> Result<Register, CommandError>,
+        image::RgbaImage,
+    ); 6] = [
+        (
+            CommandBuffer::flip_horizontal,
+            image::imageops::flip_horizontal(&pattern),
+        ),
+        (
+            CommandBuffer::flip_vertical,
+            image::imageops::flip_vertical(&pattern),
+        ),
+        (CommandBuffer::transpose, transposed),
+        (CommandBuffer::rotate90, image::imageops::rotate90(&pattern)),
+        (
+            CommandBuffer::rotate180,
+            image::imageops::rotate180(&pattern),
+        ),
+        (
+            CommandBuffer::rotate270,
+            image::imageops::rotate270(&pattern),
+        ),
+    ];
+
+    for (orient, expected) in orientations.iter() {
+        let mut commands = CommandBuffer::default();
+        let input = commands.input(descriptor.clone()).unwrap();
+        let oriented = orient(&mut commands, input).unwrap();
+        let (output, _outformat) = commands.output(oriented).expect("Valid for output");
+
+        let result = run_once_with_output(
+            commands,
+            pool,
+            vec![(input, key)],
+            retire_with_one_image(output),
+        );
+
+        let image_oriented = pool.entry(result).unwrap();
+        assert_eq!(image_oriented.descriptor().size(), expected.dimensions(),);
+
+        let bytes = image_oriented.as_bytes().expect("Not a byte image");
+        assert_eq!(bytes, expected.as_raw().as_slice());
+    }
+}
+
+fn run_pad(pool: &mut Pool) {
+    const BLACK: [u8; 4] = [0x00, 0x00, 0x00, 0xff];
+
+    // Each column has its own color, such that we can identify where a pixel comes from.
+    let column = |x: u32| [64 * x as u8, 0, 0, 0xff];
+    let columns = image::RgbaImage::from_fn(4, 2, |x, _| image::Rgba(column(x)));
+    let columns = image::DynamicImage::ImageRgba8(columns);
+    let (key, descriptor) = {
+        let entry = pool.insert_srgb(&columns);
+        (entry.key(), entry.descriptor())
+    };
+
+    // The source column of the three pixels left and right of the image.
+    let borders = [
+        (command::BorderMode::Clamp, [0, 0, 0], [3, 3, 3]),
+        (command::BorderMode::Mirror, [2, 1, 0], [3, 2, 1]),
+        (command::BorderMode::Wrap, [1, 2, 3], [0, 1, 2]),
+        (command::BorderMode::Reflect101, [3, 2, 1], [2, 1, 0]),
+    ];
+
+    for &(border, before, after) in borders.iter() {
+        let mut commands = CommandBuffer::default();
+        let input = commands.input(descriptor.clone()).unwrap();
+        let padded = commands.pad(input, 3, 0, 3, 0, border).unwrap();
+        let (output, _outformat) = commands.output(padded).expect("Valid for output");
+
+        let result = run_once_with_output(
+            commands,
+            pool,
+            vec![(input, key)],
+            retire_with_one_image(output),
+        );
+
+        let image_padded = pool.entry(result).unwrap();
+        assert_eq!(image_padded.descriptor().size(), (10, 2));
+
+        let expected: Vec<u8> = before
+            .iter()
+            .chain(&[0, 1, 2, 3])
+            .chain(after.iter())
+            .flat_map(|&x| column(x).to_vec())
+            .collect();
+
+        let bytes = image_padded.as_bytes().expect("Not a byte image");
+        for row in bytes.chunks_exact(4 * 10) {
+            assert_eq!(row, expected.as_slice(), "With {:?}", border);
+        }
+    }
+
+    // A constant border and a crop past the edge, which is transparent.
+    let mut commands = CommandBuffer::default();
+    let input = commands.input(descriptor.clone()).unwrap();
+    let padded = commands
+        .pad(
+            input,
+            1,
+            1,
+            1,
+            1,
+            command::BorderMode::Constant([0.0, 0.0, 0.0, 1.0]),
+        )
+        .unwrap();
+    let cropped = commands
+        .crop(
+            padded,
+            Rectangle {
+                x: 4,
+                y: 0,
+                max_x: 8,
+                max_y: 4,
+            },
+        )
+        .unwrap();
+    let (output, _outformat) = commands.output(cropped).expect("Valid for output");
+
+    let result = run_once_with_output(
+        commands,
+        pool,
+        vec![(input, key)],
+        retire_with_one_image(output),
+    );
+
+    let image_cropped = pool.entry(result).unwrap();
+    assert_eq!(image_cropped.descriptor().size(), (4, 4));
+
+    let bytes = image_cropped.as_bytes().expect("Not a byte image");
+    for (y, row) in bytes.chunks_exact(4 * 4).enumerate() {
+        let inside = y == 1 || y == 2;
+        let expected: Vec<u8> = [
+            if inside { column(3) } else { BLACK },
+            BLACK,
+            [0; 4],
+            [0; 4],
+        ]
+        .concat();
+
+        assert_eq!(row, expected.as_slice(), "In row {}", y);
+    }
+}
+
+fn run_gradient(pool: &mut Pool) {
+    const METHODS: &[command::DerivativeMethod] = &[
+        command::DerivativeMethod::Scharr3,
+        command::DerivativeMethod::Prewitt,
+        command::DerivativeMethod::Sobel,
+    ];
+
+    // A ramp along the width and one along the height, in steps of 16.
+    let ramps = [
+        (
+            image::RgbaImage::from_fn(8, 6, |x, _| image::Rgba([16 * x as u8, 0, 0, 0xff])),
+            command::GradientKind::Cartesian,
+        ),
+        (
+            image::RgbaImage::from_fn(8, 6, |_, y| image::Rgba([16 * y as u8, 0, 0, 0xff])),
+            command::GradientKind::Polar,
+        ),
+    ];
+
+    const STEP: f32 = 16.0 / 255.0;
+
+    for (ramp, kind) in ramps.iter() {
+        let ramp = image::DynamicImage::ImageRgba8(ramp.clone());
+        let (key, descriptor) = {
+            let entry = pool.insert_srgb(&ramp);
+            (entry.key(), entry.descriptor())
+        };
+
+        // Use the stored value itself as the luma.
+        let scalars = buffer::Texel {
+            color: buffer::Color::Scalars {
+                transfer: buffer::Transfer::Linear,
+            },
+            ..descriptor.texel.clone()
+        };
+
+        for method in METHODS {
+            let mut commands = CommandBuffer::default();
+            let input = commands.input(descriptor.clone()).unwrap();
+            let transmuted = commands.transmute(input, scalars.clone()).unwrap();
+            let gradient = commands
+                .gradient(transmuted, method.clone(), *kind)
+                .unwrap();
+            let (output, _outformat) = commands.output(gradient).expect("Valid for output");
+
+            let result = run_once_with_output(
+                commands,
+                pool,
+                vec![(input, key)],
+                retire_with_one_image(output),
+            );
+
+            let image_gradient = pool.entry(result).unwrap();
+            assert_eq!(image_gradient.descriptor().size(), (8, 6));
+
+            let bytes = image_gradient.as_bytes().expect("Not a byte image");
+            let values: Vec<f32> = bytes
+                .chunks_exact(2)
+                .map(|v| u16::from_ne_bytes([v[0], v[1]]) as f32 / 65535.0)
+                .collect();
+
+            for (idx, texel) in values.chunks_exact(2).enumerate() {
+                let (x, y) = (idx % 8, idx / 8);
+                // The edge pixel is repeated, halving the central difference.
+                let expected = match kind {
+                    command::GradientKind::Cartesian => {
+                        let dx = if x == 0 || x == 7 { STEP / 2.0 } else { STEP };
+                        [0.5 + dx, 0.5]
+                    }
+                    command::GradientKind::Polar => {
+                        let dy = if y == 0 || y == 5 { STEP / 2.0 } else { STEP };
+                        [dy, 0.75]
+                    }
+                    _ => unreachable!(),
+                };
+
+                for (&value, &expected) in texel.iter().zip(expected.iter()) {
+                    assert!(
+                        (value - expected).abs() < 2e-3,
+                        "At {}, {} with {:?}: {:?} instead of {:?}",
+                        x,
+                        y,
+                        method,
+                        texel,
+                        expected,
+                    );
+                }
+            }
+        }
+    }
+}
+
+fn run_canny(pool: &mut Pool) {
+    // A step from black to white halfway along the width.
+    let step = image::RgbaImage::from_fn(16, 12, |x, _| {
+        let value = if x < 8 { 0x00 } else { 0xff };
+        image::Rgba([value, value, value, 0xff])
+    });
+
+    let step = image::DynamicImage::ImageRgba8(step);
+    let (key, descriptor) = {
+        let entry = pool.insert_srgb(&step);
+        (entry.key(), entry.descriptor())
+    };
+
+    // Thresholds which detect the step and ones which are above its gradient.
+    let thresholds = [(0.05, 0.1, true), (0.05, 0.9, false)];
+
+    for &(low, high, detected) in thresholds.iter() {
+        let mut commands = CommandBuffer::default();
+        let input = commands.input(descriptor.clone()).unwrap();
+        let edges = commands
+            .canny(input, low, high, command::DerivativeMethod::Sobel)
+            .unwrap();
+        let (output, _outformat) = commands.output(edges).expect("Valid for output");
+
+        let result = run_once_with_output(
+            commands,
+            pool,
+            vec![(input, key)],
+            retire_with_one_image(output),
+        );
+
+        let image_edges = pool.entry(result).unwrap();
+        assert_eq!(image_edges.descriptor().size(), (16, 12));
+
+        let bytes = image_edges.as_bytes().expect("Not a byte image");
+        for (y, row) in bytes.chunks_exact(16).enumerate() {
+            assert!(row.iter().all(|&v| v == 0x00 || v == 0xff), "Not binary");
+
+            let edges: Vec<usize> = (0..16).filter(|&x| row[x] != 0).collect();
+            if detected {
+                // Thinned to one pixel, on either side of the step.
+                assert!(edges == [7] || edges == [8], "In row {}: {:?}", y, edges);
+            } else {
+                assert!(edges.is_empty(), "In row {}: {:?}", y, edges);
+            }
+        }
+    }
+
+    // Steps with a gradient of about 0.25 are strong, those between 0x6c or 0xed and black or
+    // white differ by 0.15 in linear light and have a gradient of about 0.04, which is weak.
+    let (low, high) = (0.01, 0.1);
+    let (width, height) = (32, 24);
+
+    let edges_of = |pool: &mut Pool, image: image::RgbaImage, low: f32| {
+        let (key, descriptor) = {
+            let entry = pool.insert_srgb(&image::DynamicImage::ImageRgba8(image));
+            (entry.key(), entry.descriptor())
+        };
+
+        let mut commands = CommandBuffer::default();
+        let input = commands.input(descriptor).unwrap();
+        let edges = commands
+            .canny(input, low, high, command::DerivativeMethod::Sobel)
+            .unwrap();
+        let (output, _outformat) = commands.output(edges).expect("Valid for output");
+
+        let result = run_once_with_output(
+            commands,
+            pool,
+            vec![(input, key)],
+            retire_with_one_image(output),
+        );
+
+        let image_edges = pool.entry(result).unwrap();
+        let bytes = image_edges.as_bytes().expect("Not a byte image");
+        bytes
+            .chunks_exact(width as usize)
+            .map(|row| (0..row.len()).filter(|&x| row[x] != 0).collect())
+            .collect::<Vec<Vec<usize>>>()
+    };
+
+    let gray = |value: u8| image::Rgba([value, value, value, 0xff]);
+    let on_step = |edges: &[usize]| edges == [7] || edges == [8];
+
+    // A weak segment continuing a strong one along the same step is kept. Where the contrast of
+    // the step changes there is also a horizontal edge, so only the rows below are checked.
+    let touching = image::RgbaImage::from_fn(width, height, |x, y| match (x < 8, y < 4) {
+        (true, _) => gray(0x00),
+        (false, true) => gray(0xff),
+        (false, false) => gray(0x6c),
+    });
+
+    let edges = edges_of(pool, touching.clone(), low);
+    for (y, row) in edges.iter().enumerate().skip(8) {
+        assert!(on_step(row), "In row {}: {:?}", y, row);
+    }
+
+    // Without hysteresis, the weak segment is not an edge on its own.
+    let edges = edges_of(pool, touching, high);
+    for (y, row) in edges.iter().enumerate().skip(8) {
+        assert!(row.is_empty(), "In row {}: {:?}", y, row);
+    }
+
+    // A weak step far from the strong one is dropped.
+    let isolated = image::RgbaImage::from_fn(width, height, |x, _| match x {
+        0..=7 => gray(0x00),
+        8..=23 => gray(0xff),
+        _ => gray(0xed),
+    });
+
+    let edges = edges_of(pool, isolated, low);
+    for (y, row) in edges.iter().enumerate() {
+        assert!(on_step(row), "In row {}: {:?}", y, row);
+    }
+}
+
+fn run_hough(pool: &mut Pool) {
+    const WHITE: image::Rgba<u8> = image::Rgba([0xff, 0xff, 0xff, 0xff]);
+    const BLACK: image::Rgba<u8> = image::Rgba([0x00, 0x00, 0x00, 0xff]);
+
+    // A horizontal line along the sixth row, and a ring around the center of pixel (8, 6).
+    let line = image::RgbaImage::from_fn(16, 12, |_, y| if y == 5 { WHITE } else { BLACK });
+    let ring = image::RgbaImage::from_fn(16, 12, |x, y| {
+        let distance = (x as f32 - 8.0).hypot(y as f32 - 6.0);
+        if (distance - 4.0).abs() < 0.5 {
+            WHITE
+        } else {
+            BLACK
+        }
+    });
+
+    // The line has the normal angle π/2 and lies half a pixel above the center. With a diagonal
+    // of 20 pixels, it passes through 16 of 20 samples.
+    let lines = command::Hough::Lines {
+        thetas: 4,
+        rhos: 20,
+    };
+    let circles = command::Hough::Circles { radius: 4.0 };
+    let shapes = [(line, lines, (2, 9)), (ring, circles, (8, 6))];
+
+    for (edges, hough, expected) in shapes.iter() {
+        let edges = image::DynamicImage::ImageRgba8(edges.clone());
+        let (key, descriptor) = {
+            let entry = pool.insert_srgb(&edges);
+            (entry.key(), entry.descriptor())
+        };
+
+        let mut commands = CommandBuffer::default();
+        let input = commands.input(descriptor.clone()).unwrap();
+        let accumulator = commands.hough(input, *hough).unwrap();
+        let (output, _outformat) = commands.output(accumulator).expect("Valid for output");
+
+        let result = run_once_with_output(
+            commands,
+            pool,
+            vec![(input, key)],
+            retire_with_one_image(output),
+        );
+
+        let image_accumulator = pool.entry(result).unwrap();
+        let peaks = command::Hough::peaks(&image_accumulator.into(), 1).expect("Decodable");
+        assert_eq!(peaks.len(), 1);
+        assert_eq!((peaks[0].x, peaks[0].y), *expected, "{:?}", hough);
+
+        if let command::Hough::Lines { .. } = hough {
+            assert!((peaks[0].votes - 0.8).abs() < 2e-3, "{:?}", peaks[0]);
+            let (rho, theta) = hough.line(16, 12, &peaks[0]).unwrap();
+            assert!((rho + 0.5).abs() < 1e-5);
+            assert!((theta - core::f32::consts::FRAC_PI_2).abs() < 1e-5);
+        }
+    }
+}
+
+fn run_ssim(pool: &mut Pool, (bg_key, background): (PoolKey, Descriptor)) {
+    for &ssim in [command::Ssim::Single, command::Ssim::MultiScale].iter() {
+        let mut commands = CommandBuffer::default();
+        let input = commands.input(background.clone()).unwrap();
+        let blurred = commands
+            .smooth(input, command::Smoothing::gaussian(2.0))
+            .unwrap();
+
+        // An image is perfectly similar to itself, and less so to a blurred version.
+        let same = commands.ssim(input, input, ssim).unwrap();
+        let different = commands.ssim(input, blurred, ssim).unwrap();
+        let (output_same, _outformat) = commands.output(same).expect("Valid for output");
+        let (output_different, _outformat) = commands.output(different).expect("Valid for output");
+
+        let (same, different) =
+            run_once_with_output(commands, pool, vec![(input, bg_key)], |retire| {
+                let same = retire.output(output_same).expect("Valid for output").key();
+                let different = retire
+                    .output(output_different)
+                    .expect("Valid for output")
+                    .key();
+                (same, different)
+            });
+
+        let image_same = pool.entry(same).unwrap();
+        assert_eq!(image_same.descriptor().size(), background.size());
+        let mean_same = command::Ssim::mean(&image_same.into()).expect("Decodable");
+
+        let image_different = pool.entry(different).unwrap();
+        let mean_different = command::Ssim::mean(&image_different.into()).expect("Decodable");
+
+        assert!(mean_same > 0.999, "{:?}: {}", ssim, mean_same);
+        assert!(
+            0.1 < mean_different && mean_different < 0.99,
+            "{:?}: {}",
+            ssim,
+            mean_different
+        );
+    }
+
+    // A checkerboard and the same with half its contrast, `y = 0.5 * (x - 0.5) + 0.5`. The local
+    // means agree, so the luminance term is 1, and for a variance `v` of the checkerboard the
+    // contrast-structure term is `(v + C2) / (1.25 * v + C2)`.
+    let (width, height) = (64, 64);
+    let checkerboard = |low: u8, high: u8| {
+        let gray = image::GrayImage::from_fn(width, height, |x, y| {
+            image::Luma([if (x + y) % 2 == 0 { low } else { high }])
+        });
+
+        let mut entry = pool.insert_srgb(&image::DynamicImage::ImageLuma8(gray));
+        entry.set_color(buffer::Color::Scalars {
+            transfer: buffer::Transfer::Linear,
+        });
+        (entry.key(), entry.descriptor())
+    };
+
+    let (lhs_key, lhs_descriptor) = checkerboard(64, 192);
+    let (rhs_key, rhs_descriptor) = checkerboard(96, 160);
+
+    let variance = (64.0f32 / 255.0).powi(2);
+    let c2 = 0.03f32 * 0.03;
+    let single = (variance + c2) / (1.25 * variance + c2);
+
+    // Halving averages the checkerboards to a uniform gray, so all coarser scales are equal.
+    let expected = [
+        (command::Ssim::Single, single),
+        (command::Ssim::MultiScale, single.powf(0.0448)),
+    ];
+
+    for &(ssim, expected) in expected.iter() {
+        let mut commands = CommandBuffer::default();
+        let lhs = commands.input(lhs_descriptor.clone()).unwrap();
+        let rhs = commands.input(rhs_descriptor.clone()).unwrap();
+        let map = commands.ssim(lhs, rhs, ssim).unwrap();
+        let (output, _outformat) = commands.output(map).expect("Valid for output");
+
+        let result = run_once_with_output(
+            commands,
+            pool,
+            vec![(lhs, lhs_key), (rhs, rhs_key)],
+            retire_with_one_image(output),
+        );
+
+        let image_map = pool.entry(result).unwrap();
+        let mean = command::Ssim::mean(&image_map.into()).expect("Decodable");
+
+        // The edges repeat pixels, which changes the statistics of their windows a little.
+        assert!(
+            (mean - expected).abs() < 0.005,
+            "{:?}: {} instead of {}",
+            ssim,
+            mean,
+            expected
+        );
+    }
+}
+
+fn run_perceptual_hash(pool: &mut Pool) {
+    /// The hashes of the fixtures in order of `average_hash`, `dhash` and `phash` of the
+    /// `imagehash` Python library with their default size. They were computed by a port of its
+    /// definitions and of the `convert('L')` and Lanczos `resize` of Pillow, not by `imagehash`
+    /// itself.
+    const REFERENCE: &[(&str, [&str; 3])] = &[
+        (
+            BACKGROUND,
+            ["01833f3cbc98ebfc", "1fabea6869305668", "df20607d1fa0d88f"],
+        ),
+        (
+            FOREGROUND,
+            ["8f87632b7c6c44e1", "1c6dcbcbd4d59487", "a3bf7c10c711c8d9"],
+        ),
+    ];
+
+    const HASHES: [command::PerceptualHash; 3] = [
+        command::PerceptualHash::Average,
+        command::PerceptualHash::Difference,
+        command::PerceptualHash::Dct,
+    ];
+
+    // Pillow resamples with fixed-point weights and rounds to 8 bits after each pass, our
+    // intermediate textures are `Rgba16Float` whatever their texel. Bits close to their threshold
+    // may flip.
+    const TOLERANCE: u32 = 2;
+
+    for &(path, hex) in REFERENCE {
+        let input_image = image::open(path).expect("Fixture opened");
+        let (key, descriptor) = {
+            let entry = pool.insert_srgb(&input_image);
+            (entry.key(), entry.descriptor())
+        };
+
+        for (&hash, &hex) in HASHES.iter().zip(hex.iter()) {
+            let mut commands = CommandBuffer::default();
+            let input = commands.input(descriptor.clone()).unwrap();
+            let bits = commands.perceptual_hash(input, hash).unwrap();
+            let (output, _outformat) = commands.output(bits).expect("Valid for output");
+
+            let result = run_once_with_output(
+                commands,
+                pool,
+                vec![(input, key)],
+                retire_with_one_image(output),
+            );
+
+            let image_hash = pool.entry(result).unwrap();
+            let bits = command::PerceptualHash::bits(&image_hash.into()).expect("Decodable");
+            let reference = u64::from_str_radix(hex, 16).unwrap();
+
+            let distance = (bits ^ reference).count_ones();
+            assert!(
+                distance <= TOLERANCE,
+                "{:?} of {}: {:016x} instead of {}",
+                hash,
+                path,
+                bits,
+                hex
+            );
+        }
+    }
+}
+
+fn run_histogram(
+    pool: &mut Pool,
+    (bg_key, background): (PoolKey, Descriptor),
+    image: &image::DynamicImage,
+) {
+    use buffer::ColorChannel;
+    let rgba = image.to_rgba8();
+
+    for &(channel, index, bins) in [
+        (ColorChannel::R, 0, 256),
+        (ColorChannel::G, 1, 4),
+        (ColorChannel::Alpha, 3, 16),
+    ]
+    .iter()
+    {
+        let mut commands = CommandBuffer::default();
+        let input = commands.input(background.clone()).unwrap();
+        let histogram = commands.histogram(input, channel, bins).unwrap();
+        let (output, _outformat) = commands.output(histogram).expect("Valid for output");
+
+        let counts = run_once_with_output(commands, pool, vec![(input, bg_key)], |retire| {
+            retire.counts(output).expect("Valid for counts").to_vec()
+        });
+
+        // The encoded values, binned without any rounding.
+        let mut reference = vec![0u32; bins as usize];
+        for pixel in rgba.pixels() {
+            let value = u32::from(pixel.0[index]);
+            let bin = (value * bins / 255).min(bins - 1);
+            reference[bin as usize] += 1;
+        }
+
+        assert_eq!(counts, reference, "{:?} in {} bins", channel, bins);
+    }
+}
+
+fn run_equalize(pool: &mut Pool) {
+    // A low contrast image, with values evenly spread in a narrow band.
+    let (width, height) = (64, 48);
+    let gray = image::GrayImage::from_fn(width, height, |x, y| {
+        image::Luma([100 + ((x + y) % 41) as u8])
+    });
+
+    let (key, descriptor) = {
+        let mut entry = pool.insert_srgb(&image::DynamicImage::ImageLuma8(gray.clone()));
+        entry.set_color(buffer::Color::Scalars {
+            transfer: buffer::Transfer::Linear,
+        });
+        (entry.key(), entry.descriptor())
+    };
+
+    let equalize = |pool: &mut Pool, equalization| -> Vec<u8> {
+        let mut commands = CommandBuffer::default();
+        let input = commands.input(descriptor.clone()).unwrap();
+        let equalized = commands.equalize(input, equalization).unwrap();
+        let (output, _outformat) = commands.output(equalized).expect("Valid for output");
+
+        let result = run_once_with_output(
+            commands,
+            pool,
+            vec![(input, key)],
+            retire_with_one_image(output),
+        );
+
+        let image = pool.entry(result).unwrap();
+        image.as_bytes().expect("Not a byte image").to_vec()
+    };
+
+    // Each value is mapped to the share of pixels at or below it.
+    let mut cumulative = [0u32; 256];
+    for pixel in gray.pixels() {
+        for count in &mut cumulative[usize::from(pixel.0[0])..] {
+            *count += 1;
+        }
+    }
+
+    let global = equalize(pool, command::Equalization::Global);
+    for (pixel, &equalized) in gray.pixels().zip(&global) {
+        let share = cumulative[usize::from(pixel.0[0])] as f32 / (width * height) as f32;
+        let expected = (share * 255.0) as i32;
+        assert!(
+            (i32::from(equalized) - expected).abs() <= 1,
+            "{} mapped to {} instead of {}",
+            pixel.0[0],
+            equalized,
+            expected
+        );
+    }
+
+    // With a clip limit the contrast is still enhanced, but less so.
+    let adaptive = equalize(
+        pool,
+        command::Equalization::Adaptive {
+            tiles: [4, 4],
+            clip_limit: 2.0,
+        },
+    );
+
+    let spread = |values: &[u8]| values.iter().max().unwrap() - values.iter().min().unwrap();
+    assert!(spread(&adaptive) > 80, "{}", spread(&adaptive));
+    assert!(spread(&adaptive) < spread(&global));
+
+    // A low contrast color image, four hues of little chroma in a narrow band of lightness.
+    let color = image::RgbImage::from_fn(width, height, |x, y| {
+        let lightness = 0.55 + 0.1 * ((x + y) % 41) as f64 / 40.0;
+        let hue = core::f64::consts::FRAC_PI_2 * f64::from(x / 16);
+        image::Rgb(oklab_to_srgb([
+            lightness,
+            0.04 * hue.cos(),
+            0.04 * hue.sin(),
+        ]))
+    });
+
+    let (key, descriptor) = {
+        let entry = pool.insert_srgb(&image::DynamicImage::ImageRgb8(color.clone()));
+        (entry.key(), entry.descriptor())
+    };
+
+    let mut commands = CommandBuffer::default();
+    let input = commands.input(descriptor).unwrap();
+    let equalized = commands
+        .equalize(input, command::Equalization::Global)
+        .unwrap();
+    let (output, _outformat) = commands.output(equalized).expect("Valid for output");
+
+    let result = run_once_with_output(
+        commands,
+        pool,
+        vec![(input, key)],
+        retire_with_one_image(output),
+    );
+
+    let image = pool.entry(result).unwrap();
+    let bytes = image.as_bytes().expect("Not a byte image");
+
+    let before: Vec<[f64; 3]> = color.pixels().map(|p| srgb_to_oklab(p.0)).collect();
+    let after: Vec<[f64; 3]> = bytes
+        .chunks_exact(3)
+        .map(|p| srgb_to_oklab([p[0], p[1], p[2]]))
+        .collect();
+
+    let lightness_spread = |lab: &[[f64; 3]]| {
+        let lightness = lab.iter().map(|lab| lab[0]);
+        let max = lightness.clone().fold(f64::MIN, f64::max);
+        max - lightness.fold(f64::MAX, f64::min)
+    };
+
+    assert!(lightness_spread(&before) < 0.15);
+    assert!(
+        lightness_spread(&after) > 0.6,
+        "{}",
+        lightness_spread(&after)
+    );
+
+    // Only the lightness changes. Close to black and white the color is clipped into sRGB.
+    let mut compared = 0;
+    for (lab, equalized) in before.iter().zip(&after) {
+        if !(0.25..0.8).contains(&equalized[0]) {
+            continue;
+        }
+
+        compared += 1;
+        assert!(
+            (lab[1] - equalized[1]).abs() < 0.015 && (lab[2] - equalized[2]).abs() < 0.015,
+            "{:?} equalized to {:?}",
+            lab,
+            equalized
+        );
+    }
+
+    assert!(compared > before.len() / 4, "{}", compared);
+}
+
+/// The sRGB electro-optical transfer function, from encoded values to linear light.
+fn srgb_decode(c: f64) -> f64 {
+    let c = c.max(0.0).min(1.0);
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Invert `srgb_decode`, clipping to the range of encoded values.
+fn srgb_encode(c: f64) -> f64 {
+    let c = c.max(0.0).min(1.0);
+    if c <= 0.0031308 {
+        12.92 * c
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Convert an sRGB color to Oklab, with the matrices of its reference implementation.
+fn srgb_to_oklab(rgb: [u8; 3]) -> [f64; 3] {
+    let decode = |c: u8| srgb_decode(f64::from(c) / 255.0);
+    let (r, g, b) = (decode(rgb[0]), decode(rgb[1]), decode(rgb[2]));
+
+    let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+    let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+    let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+
+    [
+        0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+        1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+        0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+    ]
+}
+
+/// Invert `srgb_to_oklab`, clipping to the sRGB gamut.
+fn oklab_to_srgb(lab: [f64; 3]) -> [u8; 3] {
+    let [lightness, a, b] = lab;
+    let l = (lightness + 0.3963377774 * a + 0.2158037573 * b).powi(3);
+    let m = (lightness - 0.1055613458 * a - 0.0638541728 * b).powi(3);
+    let s = (lightness - 0.0894841775 * a - 1.2914855480 * b).powi(3);
+
+    let encode = |c: f64| (srgb_encode(c) * 255.0).round() as u8;
+
+    [
+        encode(4.0767416621 * l - 3.3077115913 * m + 0.2309699292 * s),
+        encode(-1.2684380046 * l + 2.6097574011 * m - 0.3413193965 * s),
+        encode(-0.0041960863 * l - 0.7034186147 * m + 1.7076147010 * s),
+    ]
+}
+
+fn run_statistics(
+    pool: &mut Pool,
+    (bg_key, background): (PoolKey, Descriptor),
+    image: &image::DynamicImage,
+) {
+    let rgba = image.to_rgba8();
+    let (width, height) = background.size();
+
+    for &region in [
+        None,
+        Some(Rectangle {
+            x: width / 4,
+            y: height / 3,
+            max_x: width / 2,
+            max_y: height / 2,
+        }),
+        // An odd number of rows, the last one is merged on its own.
+        Some(Rectangle {
+            x: 0,
+            y: height / 3,
+            max_x: width,
+            max_y: height / 3 + 5,
+        }),
+    ]
+    .iter()
+    {
+        let mut commands = CommandBuffer::default();
+        let input = commands.input(background.clone()).unwrap();
+        let statistics = commands.statistics(input, region).unwrap();
+        let (output, _outformat) = commands.output(statistics).expect("Valid for output");
+
+        let statistics = run_once_with_output(commands, pool, vec![(input, bg_key)], |retire| {
+            retire.statistics(output).expect("Valid for statistics")
+        });
+
+        let region = region.unwrap_or(Rectangle {
+            x: 0,
+            y: 0,
+            max_x: width,
+            max_y: height,
+        });
+
+        let values = |channel: usize| -> Vec<f64> {
+            let mut values = vec![];
+            for y in region.y..region.max_y {
+                for x in region.x..region.max_x {
+                    values.push(f64::from(rgba.get_pixel(x, y).0[channel]) / 255.0);
+                }
+            }
+            values
+        };
+
+        for channel in 0..4 {
+            let values = values(channel);
+            let count = values.len() as f64;
+            let sum: f64 = values.iter().sum();
+            let mean = sum / count;
+            let variance = values.iter().map(|v| (v - mean) * (v - mean)).sum::<f64>() / count;
+            let min = values.iter().cloned().fold(f64::INFINITY, f64::min);
+            let max = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+
+            let close = |got: f32, expected: f64, tolerance: f64| {
+                assert!(
+                    (f64::from(got) - expected).abs() <= tolerance,
+                    "channel {} in {:?}: {} instead of {}",
+                    channel,
+                    region,
+                    got,
+                    expected
+                );
+            };
+
+            close(statistics.min[channel], min, 1e-6);
+            close(statistics.max[channel], max, 1e-6);
+            close(statistics.mean[channel], mean, 1e-5);
+            close(statistics.sum[channel], sum, 1e-5 * count);
+            close(statistics.variance[channel], variance, 1e-5);
+        }
+    }
+}
+
+fn run_noise(pool: &mut Pool) {
+    let layout = image::DynamicImage::new_luma8(128, 128);
+    let descriptor = Descriptor {
+        layout: (&layout).into(),
+        texel: buffer::Texel::with_srgb_image(&layout),
+    };
+
+    let generate = |pool: &mut Pool, noise: command::Noise| -> Vec<u8> {
+        let mut commands = CommandBuffer::default();
+        let generated = commands.noise(descriptor.clone(), noise).unwrap();
+        let (output, _outformat) = commands.output(generated).expect("Valid for output");
+
+        let result = run_once_with_output(commands, pool, vec![], retire_with_one_image(output));
+        let image = pool.entry(result).unwrap();
+        image.as_bytes().expect("Not a byte image").to_vec()
+    };
+
+    for &kind in [
+        command::NoiseKind::Value,
+        command::NoiseKind::Perlin,
+        command::NoiseKind::Simplex,
+        command::NoiseKind::Worley,
+    ]
+    .iter()
+    {
+        let noise = command::Noise::new(kind, 7, 1.0 / 8.0).with_octaves(3);
+        let first = generate(pool, noise);
+        let second = generate(pool, noise);
+        assert_eq!(first, second, "{:?} is not reproducible", kind);
+
+        let spread = first.iter().max().unwrap() - first.iter().min().unwrap();
+        assert!(spread > 32, "{:?} has a spread of only {}", kind, spread);
+
+        // Other seeds, close or not, share none of their octaves and are uncorrelated.
+        for &seed in [8, 0x5eed_1234].iter() {
+            let other = generate(
+                pool,
+                command::Noise::new(kind, seed, 1.0 / 8.0).with_octaves(3),
+            );
+            let correlation = correlation(&first, &other);
+            assert!(
+                correlation.abs() < 0.5,
+                "{:?} with seed {} has a correlation of {}",
+                kind,
+                seed,
+                correlation
+            );
+        }
+    }
+}
+
+/// The Pearson correlation coefficient of two images with the same size.
+fn correlation(lhs: &[u8], rhs: &[u8]) -> f64 {
+    let mean =
+        |values: &[u8]| values.iter().map(|&v| f64::from(v)).sum::<f64>() / values.len() as f64;
+    let (mean_lhs, mean_rhs) = (mean(lhs), mean(rhs));
+
+    let (mut covariance, mut var_lhs, mut var_rhs) = (0.0, 0.0, 0.0);
+    for (&l, &r) in lhs.iter().zip(rhs) {
+        let (dl, dr) = (f64::from(l) - mean_lhs, f64::from(r) - mean_rhs);
+        covariance += dl * dr;
+        var_lhs += dl * dl;
+        var_rhs += dr * dr;
+    }
+
+    covariance / (var_lhs * var_rhs).sqrt()
+}
+
+fn run_white_and_blue_noise(pool: &mut Pool) {
+    let layout = image::DynamicImage::new_luma8(128, 64);
+    let linear = Descriptor {
+        layout: (&layout).into(),
+        texel: buffer::Texel {
+            color: buffer::Color::Scalars {
+                transfer: buffer::Transfer::Linear,
+            },
+            ..buffer::Texel::with_srgb_image(&layout)
+        },
+    };
+    // The distribution is that of the stored values, not of the decoded sRGB values.
+    let srgb = Descriptor::with_srgb_image(&layout);
+
+    let generate = |pool: &mut Pool,
+                    descriptor: &Descriptor,
+                    white: Option<command::WhiteNoise>,
+                    seed|
+     -> Vec<u8> {
+        let mut commands = CommandBuffer::default();
+        let generated = match white {
+            Some(distribution) => commands.white_noise(descriptor.clone(), distribution, seed),
+            None => commands.blue_noise(descriptor.clone(), seed),
+        };
+
+        let (output, _outformat) = commands
+            .output(generated.unwrap())
+            .expect("Valid for output");
+
+        let result = run_once_with_output(commands, pool, vec![], retire_with_one_image(output));
+        let image = pool.entry(result).unwrap();
+        image.as_bytes().expect("Not a byte image").to_vec()
+    };
+
+    let moments = |values: &[u8]| {
+        let count = values.len() as f64;
+        let mean = values.iter().map(|&v| f64::from(v)).sum::<f64>() / count;
+        let variance = values
+            .iter()
+            .map(|&v| (f64::from(v) - mean).powi(2))
+            .sum::<f64>()
+            / count;
+        (mean, variance.sqrt())
+    };
+
+    let gaussian = command::WhiteNoise::Gaussian {
+        mean: 0.5,
+        std_dev: 0.1,
+    };
+
+    for &descriptor in [&linear, &srgb].iter() {
+        for &white in [Some(command::WhiteNoise::Uniform), Some(gaussian), None].iter() {
+            let first = generate(pool, descriptor, white, 7);
+            assert_eq!(
+                first,
+                generate(pool, descriptor, white, 7),
+                "{:?} is not reproducible",
+                white
+            );
+            assert_ne!(
+                first,
+                generate(pool, descriptor, white, 8),
+                "{:?} ignores the seed",
+                white
+            );
+
+            let (mean, std_dev) = moments(&first);
+            assert!(
+                (mean - 127.5).abs() < 3.0,
+                "{:?} has a mean of {}",
+                white,
+                mean
+            );
+
+            match white {
+                // The standard deviation of a uniform distribution of the full range.
+                Some(command::WhiteNoise::Uniform) | None => {
+                    let expected = 255.0 / 12f64.sqrt();
+                    assert!((std_dev - expected).abs() < 3.0, "{}", std_dev);
+                }
+                Some(_) => assert!((std_dev - 25.5).abs() < 2.0, "{}", std_dev),
+            }
+        }
+    }
+
+    // The blue noise mask is repeated, the right half of the image is equal to the left.
+    let blue = generate(pool, &linear, None, 7);
+    for row in blue.chunks(128) {
+        assert_eq!(row[..64], row[64..]);
+    }
+}